@@ -0,0 +1,624 @@
+//! The command line. With no command the game is played in the terminal; the other commands run
+//! without it. Flags may be given anywhere, but only to commands that use them.
+
+use std::error::Error;
+use std::fs;
+
+use enum_like::EnumValues as _;
+use klondike2_lib::model::action::Actionable as _;
+use klondike2_lib::model::game::TableAction;
+use klondike2_lib::model::table::Table as _;
+use klondike2_lib::{klondike, model};
+use rand::seq::SliceRandom as _;
+use rand::SeedableRng as _;
+use snafu::Snafu;
+
+use crate::{paths, ui};
+
+const USAGE: &str = "\
+usage: klondike2 [SETTINGS] [COMMAND]
+
+commands:
+  play                      play in the terminal (the default)
+  deal DEAL                 print the table as dealt
+  apply DEAL MOVES...       apply moves to a deal and print the table
+  check FILE                check that a saved game or table is valid
+  simulate N [--seed N]     play N games of random moves
+  stats                     summarize past games
+
+deals (for deal and apply; simulate takes only --seed):
+  --seed N                  one of our own deals
+  --microsoft N             a deal from Microsoft's numbering, e.g., --microsoft 11982
+
+settings (for play, deal, apply, and simulate):
+  --draw-count N
+  --tableaux-width N
+  --foundation-take-back yes|no
+  --auto-foundation yes|no
+  --max-redeals N|none
+  --scoring none|standard|vegas
+  --timed yes|no
+
+moves:
+  d                         draw from the stock
+  rN                        reveal the top of tableaux N
+  FROM-TO[xCOUNT]           move cards, where piles are w (waste), f (foundation), or a tableaux
+                            number, e.g., w-3, 2-f, or 4-1x3; to move a card off a foundation,
+                            give its suit, e.g., fh-5";
+
+/// How many random moves a simulated game gets before it's given up on.
+const SIMULATION_MOVE_LIMIT: usize = 1000;
+
+/// The widest tableaux that the deck can fill.
+const MAX_TABLEAUX_WIDTH: usize = 9;
+
+#[derive(Debug, Snafu)]
+pub enum CliError {
+    #[snafu(display("{}", USAGE))]
+    Usage,
+    #[snafu(display("{} needs a value", flag))]
+    MissingValue { flag: String },
+    #[snafu(display("{} is not a valid value for {}", value, flag))]
+    InvalidValue { flag: String, value: String },
+    #[snafu(display("Unknown flag {}", flag))]
+    UnknownFlag { flag: String },
+    #[snafu(display("{} is not a valid move", text))]
+    InvalidMove { text: String },
+    #[snafu(display("Move {} ({}) failed: {}", index + 1, text, message))]
+    MoveFailed {
+        index: usize,
+        text: String,
+        message: String,
+    },
+}
+
+#[derive(Debug, Default)]
+struct Options {
+    settings: klondike::settings::KlondikeSettings,
+    seed: Option<u64>,
+    /// A Microsoft game number to deal instead of a seed.
+    microsoft: Option<u32>,
+    /// Whether any flag changed the settings.
+    settings_given: bool,
+    arguments: Vec<String>,
+}
+
+pub fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let options = parse_options(args)?;
+
+    match options
+        .arguments
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        [] | ["play"] => {
+            reject_deal(&options)?;
+            ui::run(options.settings)
+        }
+        ["deal"] => deal(&options),
+        ["apply", moves @ ..] => apply(&options, moves),
+        ["check", path] => {
+            reject_deal(&options)?;
+            reject_settings(&options)?;
+            check(path)
+        }
+        ["simulate", count] => simulate(&options, count),
+        ["stats"] => {
+            reject_deal(&options)?;
+            reject_settings(&options)?;
+            stats()
+        }
+        _ => Err(CliError::Usage.into()),
+    }
+}
+
+fn parse_options(args: &[String]) -> Result<Options, CliError> {
+    let mut options = Options::default();
+    let settings = &mut options.settings;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        if !arg.starts_with("--") {
+            options.arguments.push(arg.clone());
+            continue;
+        }
+
+        let flag = arg.as_str();
+        let value = args.next().ok_or_else(|| CliError::MissingValue {
+            flag: flag.to_owned(),
+        })?;
+        let invalid = || CliError::InvalidValue {
+            flag: flag.to_owned(),
+            value: value.clone(),
+        };
+
+        match flag {
+            "--seed" => options.seed = Some(value.parse().map_err(|_| invalid())?),
+            "--microsoft" => options.microsoft = Some(value.parse().map_err(|_| invalid())?),
+            "--draw-count" => {
+                settings.draw_count = value
+                    .parse()
+                    .ok()
+                    .filter(|&count| count > 0)
+                    .ok_or_else(invalid)?
+            }
+            "--tableaux-width" => {
+                settings.tableaux_width = value
+                    .parse()
+                    .ok()
+                    .filter(|width| (1..=MAX_TABLEAUX_WIDTH).contains(width))
+                    .ok_or_else(invalid)?
+            }
+            "--foundation-take-back" => {
+                settings.allow_move_from_foundation = parse_bool(value).ok_or_else(invalid)?
+            }
+            "--auto-foundation" => {
+                settings.auto_move_to_foundation = parse_bool(value).ok_or_else(invalid)?
+            }
+            "--max-redeals" => {
+                settings.max_redeals = match value.as_str() {
+                    "none" => None,
+                    value => Some(value.parse().map_err(|_| invalid())?),
+                }
+            }
+            "--scoring" => {
+                settings.scoring = match value.as_str() {
+                    "none" => klondike::scoring::KlondikeScoring::None,
+                    "standard" => klondike::scoring::KlondikeScoring::Standard,
+                    "vegas" => klondike::scoring::KlondikeScoring::Vegas,
+                    _ => return Err(invalid()),
+                }
+            }
+            "--timed" => settings.timed = parse_bool(value).ok_or_else(invalid)?,
+            _ => {
+                return Err(CliError::UnknownFlag {
+                    flag: flag.to_owned(),
+                })
+            }
+        }
+        // Every flag but the deal flags changes the settings.
+        options.settings_given |= !matches!(flag, "--seed" | "--microsoft");
+    }
+
+    Ok(options)
+}
+
+/// Commands that don't start from a particular deal fail if given one, rather than quietly
+/// ignoring it.
+fn reject_deal(options: &Options) -> Result<(), CliError> {
+    if options.seed.is_some() || options.microsoft.is_some() {
+        return Err(CliError::Usage);
+    }
+
+    Ok(())
+}
+
+/// Commands that don't play a game fail if given settings, rather than quietly ignoring them.
+fn reject_settings(options: &Options) -> Result<(), CliError> {
+    if options.settings_given {
+        return Err(CliError::Usage);
+    }
+
+    Ok(())
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "yes" | "true" | "on" => Some(true),
+        "no" | "false" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// Parse a move in the notation described in `USAGE`. Moves to a foundation go to the one for the
+/// suit of the card being moved.
+fn parse_move(
+    text: &str,
+    table: &klondike::table::KlondikeTable,
+    settings: &klondike::settings::KlondikeSettings,
+) -> Result<klondike::table::KlondikeTableAction, CliError> {
+    let invalid = || CliError::InvalidMove {
+        text: text.to_owned(),
+    };
+    let tableaux = |index: &str| {
+        index
+            .parse::<usize>()
+            .ok()
+            .filter(|index| (1..=settings.tableaux_width).contains(index))
+            .map(|index| klondike::table::KlondikePileId::Tableaux(index - 1))
+    };
+
+    if text == "d" {
+        return Ok(klondike::table::KlondikeTableAction::Draw(
+            settings.draw_count,
+        ));
+    }
+    if let Some(index) = text.strip_prefix('r') {
+        return tableaux(index)
+            .map(klondike::table::KlondikeTableAction::Reveal)
+            .ok_or_else(invalid);
+    }
+
+    let (piles, count) = match text.split_once('x') {
+        Some((piles, count)) => (piles, count.parse().map_err(|_| invalid())?),
+        None => (text, 1),
+    };
+    let (source, target) = piles.split_once('-').ok_or_else(invalid)?;
+
+    let source_pile_id = match source {
+        "w" => klondike::table::KlondikePileId::Waste,
+        "fs" => klondike::table::KlondikePileId::Foundation(model::card::Suit::Spades),
+        "fh" => klondike::table::KlondikePileId::Foundation(model::card::Suit::Hearts),
+        "fd" => klondike::table::KlondikePileId::Foundation(model::card::Suit::Diamonds),
+        "fc" => klondike::table::KlondikePileId::Foundation(model::card::Suit::Clubs),
+        index => tableaux(index).ok_or_else(invalid)?,
+    };
+    let target_pile_id = match target {
+        "f" => {
+            let card = table.pile(source_pile_id).top_card().ok_or_else(invalid)?;
+            klondike::table::KlondikePileId::Foundation(card.suit())
+        }
+        index => tableaux(index).ok_or_else(invalid)?,
+    };
+
+    Ok(klondike::table::KlondikeTableAction::Move(
+        source_pile_id,
+        target_pile_id,
+        count,
+    ))
+}
+
+fn dealt_game<SH>(
+    settings: klondike::settings::KlondikeSettings,
+    shuffle: SH,
+) -> klondike::game::KlondikeGame<SH>
+where
+    SH: model::deck::Shuffle,
+{
+    let mut game = klondike::game::KlondikeGame::new(
+        klondike::dealer::KlondikeDealer,
+        klondike::rules::KlondikeRules,
+        settings,
+        shuffle,
+    );
+
+    game.deal_and_start()
+        .expect("the dealer should only deal legal actions");
+
+    game
+}
+
+fn deal(options: &Options) -> Result<(), Box<dyn Error>> {
+    match (options.seed, options.microsoft) {
+        (Some(seed), None) => {
+            print_table(&dealt_game(
+                options.settings,
+                model::deck::SeededShuffle::new(seed),
+            ));
+        }
+        (None, Some(game)) => {
+            print_table(&dealt_game(
+                options.settings,
+                model::deck::MicrosoftShuffle::new(game),
+            ));
+        }
+        _ => return Err(CliError::Usage.into()),
+    }
+
+    Ok(())
+}
+
+fn apply(options: &Options, moves: &[&str]) -> Result<(), Box<dyn Error>> {
+    match (options.seed, options.microsoft) {
+        (Some(seed), None) => apply_moves(
+            dealt_game(options.settings, model::deck::SeededShuffle::new(seed)),
+            moves,
+        ),
+        (None, Some(game)) => apply_moves(
+            dealt_game(options.settings, model::deck::MicrosoftShuffle::new(game)),
+            moves,
+        ),
+        _ => Err(CliError::Usage.into()),
+    }
+}
+
+fn apply_moves<SH>(
+    mut game: klondike::game::KlondikeGame<SH>,
+    moves: &[&str],
+) -> Result<(), Box<dyn Error>>
+where
+    SH: model::deck::Shuffle,
+{
+    play_moves(&mut game, moves)?;
+
+    print_table(&game);
+    println!("Status: {}", game.status());
+    Ok(())
+}
+
+/// Apply each move in turn, along with any moves to the foundations the settings ask for.
+fn play_moves<SH>(
+    game: &mut klondike::game::KlondikeGame<SH>,
+    moves: &[&str],
+) -> Result<(), CliError>
+where
+    SH: model::deck::Shuffle,
+{
+    for (index, &text) in moves.iter().enumerate() {
+        let move_failed = |message| CliError::MoveFailed {
+            index,
+            text: text.to_owned(),
+            message,
+        };
+
+        let action = parse_move(text, game.table(), game.settings())?;
+        game.apply(TableAction(action))
+            .map_err(|error| move_failed(describe_error(error)))?;
+        klondike::autoplay::apply_safe_moves(game).map_err(|error| {
+            move_failed(format!(
+                "unable to move cards to the foundations: {}",
+                describe_error(error)
+            ))
+        })?;
+    }
+
+    Ok(())
+}
+
+fn describe_error(error: klondike::game::KlondikeGameError) -> String {
+    match error {
+        model::rules::RulesGuardError::RuleError { source, .. } => source.to_string(),
+        model::rules::RulesGuardError::ActionError { source, .. } => match source {},
+    }
+}
+
+fn print_table<SH>(game: &klondike::game::KlondikeGame<SH>) {
+    print!(
+        "{}",
+        klondike::text::display(game.table(), game.settings().tableaux_width)
+    );
+}
+
+/// Check a saved game, or a table in the plain-text form.
+fn check(path: &str) -> Result<(), Box<dyn Error>> {
+    let contents = fs::read_to_string(path)?;
+
+    if contents.trim_start().starts_with('{') {
+        let game = klondike::save::from_str(&contents)?;
+        println!("{} is valid ({})", path, game.status());
+    } else {
+        klondike::text::parse(&contents)?;
+        println!("{} is valid", path);
+    }
+
+    Ok(())
+}
+
+/// Simulate games from consecutive seeds. Microsoft's numbering isn't supported, since its deals
+/// only match Microsoft's for the standard settings.
+fn simulate(options: &Options, count: &str) -> Result<(), Box<dyn Error>> {
+    if options.microsoft.is_some() {
+        return Err(CliError::Usage.into());
+    }
+    let count: usize = count.parse().map_err(|_| CliError::InvalidValue {
+        flag: "simulate".to_owned(),
+        value: count.to_owned(),
+    })?;
+    let seed = options
+        .seed
+        .unwrap_or_else(|| rand::Rng::gen(&mut rand::thread_rng()));
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+
+    // How many games ended with each number of cards on the foundations.
+    let mut results = [0usize; 53];
+    for game_index in 0..count {
+        let mut game = dealt_game(
+            options.settings,
+            model::deck::SeededShuffle::new(seed.wrapping_add(game_index as u64)),
+        );
+
+        for _ in 0..SIMULATION_MOVE_LIMIT {
+            let context = klondike::rules::KlondikeRulesContext::new(
+                game.settings(),
+                game.is_started(),
+                game.table(),
+            );
+            let actions = klondike::rules::KlondikeRules.legal_actions(&context);
+            let action = match actions.choose(&mut rng) {
+                Some(&action) if !game.table().is_won() => action,
+                _ => break,
+            };
+            game.apply(TableAction(action))?;
+            klondike::autoplay::apply_safe_moves(&mut game)?;
+        }
+
+        let foundation_cards = model::card::Suit::values()
+            .map(|suit| {
+                game.table()
+                    .pile(klondike::table::KlondikePileId::Foundation(suit))
+                    .len()
+            })
+            .sum::<usize>();
+        results[foundation_cards] += 1;
+    }
+
+    println!("Simulated {} games from seed {}", count, seed);
+    println!("Cards on foundations: games");
+    for (foundation_cards, games) in results.iter().enumerate() {
+        if *games > 0 {
+            println!("{:>20}: {}", foundation_cards, games);
+        }
+    }
+    let total: usize = results
+        .iter()
+        .enumerate()
+        .map(|(foundation_cards, games)| foundation_cards * games)
+        .sum();
+    if count > 0 {
+        println!("Average: {:.1}", total as f64 / count as f64);
+    }
+
+    Ok(())
+}
+
+fn stats() -> Result<(), Box<dyn Error>> {
+    let statistics = match paths::statistics() {
+        Some(path) => klondike::statistics::Statistics::load(&path)?,
+        None => Default::default(),
+    };
+
+    print!("{}", statistics.summary());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use klondike::table::{KlondikePileId, KlondikeTableAction};
+    use model::card::Suit;
+    use test_case::test_case;
+
+    use super::*;
+
+    const MID_GAME: &str = "\
+Stock: 20
+Waste: 3D 8H KC
+Foundations: AS 2H -- AC
+Redeals: 1
+1: 7S
+2: [**] 9C 8D
+3:
+4: [**] [**] KH QS JH
+5: [**] [**] [**] 5S
+6: [**] [**] [**] [**] 4D
+7: [**] [**] [**] [**] [**] [**] 9S
+";
+
+    #[test_case(true => 1; "with auto foundation")]
+    #[test_case(false => 0; "without auto foundation")]
+    fn play_moves_should_follow_auto_foundation_setting(auto_move_to_foundation: bool) -> usize {
+        let settings = klondike::settings::KlondikeSettings {
+            auto_move_to_foundation,
+            draw_count: 1,
+            ..Default::default()
+        };
+        // Drawing uncovers nothing, but the Ace of Clubs is already showing on the second tableaux.
+        let mut game = dealt_game(settings, model::deck::SeededShuffle::new(1));
+
+        play_moves(&mut game, &["d"]).unwrap();
+
+        game.table()
+            .pile(KlondikePileId::Foundation(Suit::Clubs))
+            .len()
+    }
+
+    fn args(text: &str) -> Vec<String> {
+        text.split_whitespace().map(str::to_owned).collect()
+    }
+
+    #[test_case("d" => KlondikeTableAction::Draw(3); "draw")]
+    #[test_case("r7" => KlondikeTableAction::Reveal(KlondikePileId::Tableaux(6)); "reveal")]
+    #[test_case(
+        "w-3" => KlondikeTableAction::Move(KlondikePileId::Waste, KlondikePileId::Tableaux(2), 1);
+        "from waste"
+    )]
+    #[test_case(
+        "w-f" => KlondikeTableAction::Move(
+            KlondikePileId::Waste,
+            KlondikePileId::Foundation(Suit::Clubs),
+            1,
+        );
+        "to foundation of card's suit"
+    )]
+    #[test_case(
+        "4-1x3" => KlondikeTableAction::Move(
+            KlondikePileId::Tableaux(3),
+            KlondikePileId::Tableaux(0),
+            3,
+        );
+        "with count"
+    )]
+    #[test_case(
+        "fh-5" => KlondikeTableAction::Move(
+            KlondikePileId::Foundation(Suit::Hearts),
+            KlondikePileId::Tableaux(4),
+            1,
+        );
+        "from foundation"
+    )]
+    fn parse_move_should_read_notation(text: &str) -> KlondikeTableAction {
+        let table = klondike::text::parse(MID_GAME).unwrap();
+
+        parse_move(text, &table, &Default::default()).unwrap()
+    }
+
+    #[test_case(""; "empty")]
+    #[test_case("r8"; "tableaux out of range")]
+    #[test_case("0-1"; "tableaux zero")]
+    #[test_case("3-f"; "empty source to foundation")]
+    #[test_case("f-1"; "foundation without suit")]
+    #[test_case("w-1xy"; "bad count")]
+    #[test_case("w3"; "missing separator")]
+    fn parse_move_should_reject_invalid_notation(text: &str) {
+        let table = klondike::text::parse(MID_GAME).unwrap();
+
+        assert_matches!(
+            parse_move(text, &table, &Default::default()),
+            Err(CliError::InvalidMove { text: error_text }) if error_text == text
+        );
+    }
+
+    #[test]
+    fn parse_options_should_set_settings_anywhere() {
+        let options =
+            parse_options(&args("deal --draw-count 1 --seed 5 --max-redeals none")).unwrap();
+
+        assert_eq!(options.settings.draw_count, 1);
+        assert_eq!(options.settings.max_redeals, None);
+        assert_eq!(options.seed, Some(5));
+        assert_eq!(options.arguments, args("deal"));
+    }
+
+    #[test]
+    fn parse_options_should_reject_missing_value() {
+        assert_matches!(
+            parse_options(&args("deal --seed")),
+            Err(CliError::MissingValue { flag }) if flag == "--seed"
+        );
+    }
+
+    #[test_case("--draw-count 0"; "zero draw count")]
+    #[test_case("--tableaux-width 10"; "tableaux too wide")]
+    #[test_case("--timed maybe"; "bad yes or no")]
+    #[test_case("--scoring golf"; "unknown scoring")]
+    #[test_case("--microsoft -1"; "negative game")]
+    fn parse_options_should_reject_invalid_value(text: &str) {
+        assert_matches!(
+            parse_options(&args(text)),
+            Err(CliError::InvalidValue { .. })
+        );
+    }
+
+    #[test]
+    fn parse_options_should_reject_unknown_flag() {
+        assert_matches!(
+            parse_options(&args("--colour red")),
+            Err(CliError::UnknownFlag { flag }) if flag == "--colour"
+        );
+    }
+
+    #[test_case("play --seed 5"; "play with seed")]
+    #[test_case("--microsoft 11982"; "default play with game")]
+    #[test_case("stats --seed 5"; "stats with seed")]
+    #[test_case("simulate 10 --microsoft 11982"; "simulate with game")]
+    #[test_case("check game.json --draw-count 1"; "check with settings")]
+    #[test_case("stats --timed yes"; "stats with settings")]
+    fn run_should_reject_unused_flags(text: &str) {
+        assert_matches!(
+            run(&args(text)).unwrap_err().downcast_ref(),
+            Some(CliError::Usage)
+        );
+    }
+}
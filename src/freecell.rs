@@ -0,0 +1,5 @@
+pub mod dealer;
+pub mod game;
+pub mod rules;
+pub mod settings;
+pub mod table;
@@ -0,0 +1,76 @@
+use crate::freecell::{game, table};
+use crate::model;
+
+/// How many cards are dealt, one at a time, across the cascades.
+const DECK_SIZE: usize = 52;
+
+#[derive(Debug, Clone)]
+pub struct FreeCellDealer;
+
+impl model::dealer::Dealer for FreeCellDealer {
+    type Action = table::FreeCellTableAction;
+    type Context<'a> = FreeCellDealerContext;
+    type Iter = FreeCellDealerIter;
+
+    fn deal(&self, _context: Self::Context<'_>) -> Self::Iter {
+        FreeCellDealerIter { dealt: 0 }
+    }
+}
+
+/// Every card is dealt face up to one of the eight cascades, so no setting changes the deal.
+#[derive(Debug, Clone, Copy)]
+pub struct FreeCellDealerContext;
+
+impl<'a> From<game::FreeCellGameDealerContext<'a>> for FreeCellDealerContext {
+    fn from(_context: game::FreeCellGameDealerContext<'a>) -> Self {
+        Self
+    }
+}
+
+/// Deals the whole deck face up, a row at a time from left to right, so the first four cascades
+/// get seven cards and the rest get six.
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct FreeCellDealerIter {
+    dealt: usize,
+}
+
+impl Iterator for FreeCellDealerIter {
+    type Item = table::FreeCellTableAction;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.dealt < DECK_SIZE {
+            let pile_id = table::FreeCellPileId::Cascade(self.dealt % table::CASCADE_COUNT);
+            self.dealt += 1;
+            Some(table::FreeCellTableAction::Deal(pile_id))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools as _;
+
+    use super::*;
+    use crate::model::dealer::Dealer as _;
+
+    #[test]
+    fn dealer_should_deal_rows_across_cascades() {
+        let actions = FreeCellDealer.deal(FreeCellDealerContext).collect_vec();
+
+        assert_eq!(actions.len(), 52);
+        assert_eq!(
+            actions[..9],
+            [0, 1, 2, 3, 4, 5, 6, 7, 0].map(|index| table::FreeCellTableAction::Deal(
+                table::FreeCellPileId::Cascade(index)
+            ))
+        );
+        assert_eq!(
+            actions.last(),
+            Some(&table::FreeCellTableAction::Deal(
+                table::FreeCellPileId::Cascade(3)
+            ))
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use std::convert;
+
+use crate::freecell::{dealer, rules, settings, table};
+use crate::model;
+
+pub type FreeCellGame<SH> = model::game::Game<
+    dealer::FreeCellDealer,
+    rules::FreeCellRules,
+    settings::FreeCellSettings,
+    SH,
+    table::FreeCellTable,
+>;
+
+pub type FreeCellGameError = model::rules::RulesGuardError<
+    rules::FreeCellRulesError,
+    convert::Infallible,
+    table::FreeCellTableAction,
+>;
+pub type FreeCellGameHistoryError =
+    model::game::GameHistoryError<convert::Infallible, convert::Infallible>;
+
+pub type FreeCellGameDealerContext<'a> =
+    model::game::GameDealerContext<'a, settings::FreeCellSettings>;
+pub type FreeCellGameRulesContext<'a> =
+    model::game::GameRulesContext<'a, settings::FreeCellSettings, table::FreeCellTable>;
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools as _;
+
+    use super::*;
+    use crate::model::action::Actionable as _;
+    use crate::model::deck;
+    use crate::model::game::{GameStatus, TableAction};
+    use crate::model::table::Table as _;
+
+    fn started_game(game_number: u32) -> FreeCellGame<deck::MicrosoftShuffle> {
+        let mut game = FreeCellGame::new(
+            dealer::FreeCellDealer,
+            rules::FreeCellRules,
+            Default::default(),
+            deck::MicrosoftShuffle::new(game_number),
+        );
+        game.deal_and_start().unwrap();
+
+        game
+    }
+
+    fn cascade(game: &FreeCellGame<deck::MicrosoftShuffle>, index: usize) -> String {
+        game.table()
+            .pile(table::FreeCellPileId::Cascade(index))
+            .iter()
+            .map(|card| card.notated(model::card::Notation::Letters))
+            .join(" ")
+    }
+
+    #[test]
+    fn game_should_deal_microsoft_layout() {
+        let game = started_game(1);
+
+        assert_eq!(cascade(&game, 0), "JD KD 2S 4C 3S 6D 6S");
+        assert_eq!(cascade(&game, 3), "JC 5S QD QH TH QS 6H");
+        assert_eq!(cascade(&game, 7), "5H 3H 3C 7S 7D TC");
+        assert!(game.table().pile(table::FreeCellPileId::Stock).is_empty());
+        assert_eq!(game.status(), GameStatus::InProgress);
+    }
+
+    #[test]
+    fn undo_should_restore_table() {
+        let mut game = started_game(1);
+        let before = game.table().clone();
+
+        game.apply(TableAction(table::FreeCellTableAction::Move(
+            table::FreeCellPileId::Cascade(0),
+            table::FreeCellPileId::Cell(0),
+            1,
+        )))
+        .unwrap();
+        assert_ne!(game.table(), &before);
+
+        game.undo().unwrap();
+        assert_eq!(game.table(), &before);
+    }
+}
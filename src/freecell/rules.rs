@@ -0,0 +1,422 @@
+use snafu::Snafu;
+
+use crate::freecell::{game, settings, table};
+use crate::model;
+use crate::model::rules::Rules as _;
+use crate::model::table::Table as _;
+
+#[derive(Debug, Clone, Default)]
+pub struct FreeCellRules;
+
+#[derive(Debug, Clone, Copy)]
+pub struct FreeCellRulesContext<'a> {
+    settings: &'a settings::FreeCellSettings,
+    started: bool,
+    table: &'a table::FreeCellTable,
+}
+
+impl<'a> FreeCellRulesContext<'a> {
+    pub fn new(
+        settings: &'a settings::FreeCellSettings,
+        started: bool,
+        table: &'a table::FreeCellTable,
+    ) -> Self {
+        Self {
+            settings,
+            started,
+            table,
+        }
+    }
+}
+
+impl<'a> From<game::FreeCellGameRulesContext<'a>> for FreeCellRulesContext<'a> {
+    fn from(context: game::FreeCellGameRulesContext<'a>) -> Self {
+        Self {
+            settings: context.settings,
+            started: context.started,
+            table: context.table,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, derive_more::Display)]
+pub enum FoundationMismatchType {
+    #[display(fmt = "only the Ace of {} may start the foundation", _0)]
+    Start(model::card::Suit),
+    #[display(fmt = "it does not follow the {}", _0)]
+    Follow(model::card::CardFace),
+}
+
+#[derive(Debug, Snafu)]
+pub enum FreeCellRulesError {
+    #[snafu(display("The game has already started"))]
+    GameAlreadyStarted,
+    #[snafu(display("The game has not started yet"))]
+    GameNotStarted,
+    #[snafu(display("There is no {}", pile_id))]
+    PileOutOfBounds { pile_id: table::FreeCellPileId },
+    #[snafu(display("Cannot deal to the {}", pile_id))]
+    IllegalDealTarget { pile_id: table::FreeCellPileId },
+    #[snafu(display("Cannot move zero cards"))]
+    EmptyMove,
+    #[snafu(display("The {} does not have {} cards", pile_id, count))]
+    InsufficientCards {
+        pile_id: table::FreeCellPileId,
+        count: usize,
+    },
+    #[snafu(display("Moving cards out of a foundation is not allowed"))]
+    IllegalMoveFromFoundation,
+    #[snafu(display("May only take a single card from the {}", pile_id))]
+    MayOnlyTakeSingleCard { pile_id: table::FreeCellPileId },
+    #[snafu(display("Cannot move cards from the {}", pile_id))]
+    IllegalMoveSource { pile_id: table::FreeCellPileId },
+    #[snafu(display("Cannot move cards to the {}", pile_id))]
+    IllegalMoveTarget { pile_id: table::FreeCellPileId },
+    #[snafu(display("The cards being moved are not in sequence"))]
+    OutOfSequence,
+    #[snafu(display(
+        "Can only move {} cards at once with the free cells and cascades available",
+        max_count
+    ))]
+    SupermoveLimit { count: usize, max_count: usize },
+    #[snafu(display("The {} may only accept a single card", pile_id))]
+    MayOnlyAcceptSingleCard { pile_id: table::FreeCellPileId },
+    #[snafu(display("The {} is already occupied", pile_id))]
+    CellOccupied { pile_id: table::FreeCellPileId },
+    #[snafu(display("Cannot place the {} on the {}", card, target_card))]
+    CascadeMismatch {
+        card: model::card::CardFace,
+        target_card: model::card::CardFace,
+    },
+    #[snafu(display("Cannot place the {} on the foundation: {}", card, mismatch))]
+    FoundationMismatch {
+        card: model::card::CardFace,
+        mismatch: FoundationMismatchType,
+    },
+}
+
+impl model::rules::Rules<table::FreeCellTableAction> for FreeCellRules {
+    type Context<'a> = FreeCellRulesContext<'a>;
+    type Error = FreeCellRulesError;
+
+    fn validate(
+        &self,
+        action: &table::FreeCellTableAction,
+        context: &Self::Context<'_>,
+    ) -> Result<(), Self::Error> {
+        match *action {
+            table::FreeCellTableAction::Deal(target_pile_id) => {
+                self.validate_deal(target_pile_id, context)
+            }
+            table::FreeCellTableAction::Move(source_pile_id, target_pile_id, count) => {
+                self.validate_move(source_pile_id, target_pile_id, count, context)
+            }
+        }
+    }
+
+    fn is_stuck(&self, context: &Self::Context<'_>) -> bool {
+        context.started && self.legal_actions(context).is_empty()
+    }
+}
+
+impl FreeCellRules {
+    /// List every move a player could legally make in this context, between any of the cascades,
+    /// free cells and foundations. There are no face-down cards, so there's nothing to reveal.
+    pub fn legal_actions(
+        &self,
+        context: &FreeCellRulesContext<'_>,
+    ) -> Vec<table::FreeCellTableAction> {
+        let pile_ids = || {
+            table::FreeCellPileId::full_iter()
+                .filter(|&pile_id| pile_id != table::FreeCellPileId::Stock)
+        };
+
+        pile_ids()
+            .flat_map(|source_pile_id| {
+                let max_count = context.table.movable_cards(source_pile_id).len();
+
+                pile_ids()
+                    .filter(move |&target_pile_id| target_pile_id != source_pile_id)
+                    .flat_map(move |target_pile_id| {
+                        (1..=max_count).map(move |count| {
+                            table::FreeCellTableAction::Move(source_pile_id, target_pile_id, count)
+                        })
+                    })
+            })
+            .filter(|action| self.validate(action, context).is_ok())
+            .collect()
+    }
+
+    /// The most cards that can be moved to a pile at once. Only one card may officially move at a
+    /// time, but a run can be shuffled across one card at a time through the free cells, and each
+    /// empty cascade (other than the target) doubles how long a run can be moved that way.
+    pub fn supermove_limit(
+        &self,
+        target_pile_id: table::FreeCellPileId,
+        context: &FreeCellRulesContext<'_>,
+    ) -> usize {
+        match target_pile_id {
+            table::FreeCellPileId::Cascade(_) => {
+                let mut empty_cascades = context.table.empty_cascades();
+                if context.table.pile(target_pile_id).is_empty() {
+                    empty_cascades -= 1;
+                }
+                (context.table.free_cells() + 1) << empty_cascades
+            }
+            _ => 1,
+        }
+    }
+
+    fn validate_deal(
+        &self,
+        target_pile_id: table::FreeCellPileId,
+        context: &FreeCellRulesContext<'_>,
+    ) -> Result<(), FreeCellRulesError> {
+        snafu::ensure!(!context.started, GameAlreadyStarted);
+
+        match target_pile_id {
+            table::FreeCellPileId::Cascade(_) => self.validate_pile_id(target_pile_id),
+            _ => IllegalDealTarget {
+                pile_id: target_pile_id,
+            }
+            .fail(),
+        }
+    }
+
+    fn validate_move(
+        &self,
+        source_pile_id: table::FreeCellPileId,
+        target_pile_id: table::FreeCellPileId,
+        count: usize,
+        context: &FreeCellRulesContext<'_>,
+    ) -> Result<(), FreeCellRulesError> {
+        snafu::ensure!(context.started, GameNotStarted);
+        snafu::ensure!(count > 0, EmptyMove);
+        self.validate_pile_id(source_pile_id)?;
+        self.validate_pile_id(target_pile_id)?;
+
+        match source_pile_id {
+            table::FreeCellPileId::Stock => {
+                return IllegalMoveSource {
+                    pile_id: source_pile_id,
+                }
+                .fail();
+            }
+            table::FreeCellPileId::Foundation(_) => {
+                snafu::ensure!(
+                    context.settings.allow_move_from_foundation,
+                    IllegalMoveFromFoundation
+                );
+                snafu::ensure!(
+                    count == 1,
+                    MayOnlyTakeSingleCard {
+                        pile_id: source_pile_id
+                    }
+                );
+            }
+            table::FreeCellPileId::Cell(_) | table::FreeCellPileId::Cascade(_) => {}
+        }
+
+        let source_top_cards = context.table.pile(source_pile_id).top_cards(count);
+        snafu::ensure!(
+            count == source_top_cards.len(),
+            InsufficientCards {
+                pile_id: source_pile_id,
+                count
+            }
+        );
+        snafu::ensure!(table::is_run(source_top_cards), OutOfSequence);
+
+        let source_card = &source_top_cards[0];
+        let target_top_card = context.table.pile(target_pile_id).top_card();
+
+        match target_pile_id {
+            table::FreeCellPileId::Cascade(_) => {
+                let max_count = self.supermove_limit(target_pile_id, context);
+                snafu::ensure!(count <= max_count, SupermoveLimit { count, max_count });
+
+                if let Some(target_top_card) = target_top_card {
+                    snafu::ensure!(
+                        target_top_card.rank().follows(source_card.rank())
+                            && target_top_card.color() != source_card.color(),
+                        CascadeMismatch {
+                            card: source_card.face,
+                            target_card: target_top_card.face,
+                        }
+                    );
+                }
+            }
+            table::FreeCellPileId::Cell(_) => {
+                snafu::ensure!(
+                    count == 1,
+                    MayOnlyAcceptSingleCard {
+                        pile_id: target_pile_id
+                    }
+                );
+                snafu::ensure!(
+                    target_top_card.is_none(),
+                    CellOccupied {
+                        pile_id: target_pile_id
+                    }
+                );
+            }
+            table::FreeCellPileId::Foundation(suit) => {
+                snafu::ensure!(
+                    count == 1,
+                    MayOnlyAcceptSingleCard {
+                        pile_id: target_pile_id
+                    }
+                );
+
+                if let Some(target_top_card) = target_top_card {
+                    snafu::ensure!(
+                        source_card.suit() == suit
+                            && source_card.rank().follows(target_top_card.rank()),
+                        FoundationMismatch {
+                            card: source_card.face,
+                            mismatch: FoundationMismatchType::Follow(target_top_card.face)
+                        }
+                    );
+                } else {
+                    snafu::ensure!(
+                        source_card.suit() == suit && source_card.is_ace(),
+                        FoundationMismatch {
+                            card: source_card.face,
+                            mismatch: FoundationMismatchType::Start(suit)
+                        }
+                    );
+                }
+            }
+            table::FreeCellPileId::Stock => {
+                return IllegalMoveTarget {
+                    pile_id: target_pile_id,
+                }
+                .fail();
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_pile_id(&self, pile_id: table::FreeCellPileId) -> Result<(), FreeCellRulesError> {
+        let in_bounds = match pile_id {
+            table::FreeCellPileId::Cell(index) => index < table::CELL_COUNT,
+            table::FreeCellPileId::Cascade(index) => index < table::CASCADE_COUNT,
+            table::FreeCellPileId::Stock | table::FreeCellPileId::Foundation(_) => true,
+        };
+        snafu::ensure!(in_bounds, PileOutOfBounds { pile_id });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use enum_like::EnumValues as _;
+    use itertools::Itertools as _;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::model::action::Actionable as _;
+    use crate::model::card::{Rank, Suit};
+    use crate::test_support::validate_started;
+
+    /// A table with a descending run of hearts and spades from the King in the first cascade,
+    /// `filled_cells` cells taken up, and the rest of the deck spread over the other cascades
+    /// except for the last `empty_cascades`.
+    fn table_with_run(filled_cells: usize, empty_cascades: usize) -> table::FreeCellTable {
+        // The stock is dealt from the end, so listing the run from the Ace puts the King at the
+        // bottom of the cascade.
+        let run_cards = Rank::values()
+            .zip([Suit::Hearts, Suit::Spades].iter().cycle())
+            .map(|(rank, &suit)| rank.of(suit))
+            .collect_vec();
+        let rest = model::card::CardFace::values()
+            .filter(|face| !run_cards.contains(face))
+            .collect_vec();
+
+        let deck = rest.iter().chain(&run_cards).map(|face| face.face_down());
+        let mut table = table::FreeCellTable::new_with_cards(deck);
+        for _ in 0..13 {
+            table
+                .apply(table::FreeCellTableAction::Deal(
+                    table::FreeCellPileId::Cascade(0),
+                ))
+                .unwrap();
+        }
+        for index in 0..rest.len() {
+            let pile_id = if index < filled_cells {
+                table::FreeCellPileId::Cell(index)
+            } else {
+                table::FreeCellPileId::Cascade(1 + index % (7 - empty_cascades))
+            };
+            table
+                .apply(table::FreeCellTableAction::Deal(pile_id))
+                .unwrap();
+        }
+
+        table
+    }
+
+    #[test_case(0, 0, false => 1; "no space")]
+    #[test_case(4, 0, false => 5; "free cells")]
+    #[test_case(0, 1, false => 2; "empty cascade")]
+    #[test_case(2, 2, false => 12; "both")]
+    #[test_case(2, 2, true => 6; "onto empty cascade")]
+    fn supermove_limit(free_cells: usize, empty_cascades: usize, to_empty: bool) -> usize {
+        let table = table_with_run(4 - free_cells, empty_cascades);
+        let settings = Default::default();
+        let context = FreeCellRulesContext::new(&settings, true, &table);
+        let target_pile_id = if to_empty {
+            table::FreeCellPileId::Cascade(7)
+        } else {
+            table::FreeCellPileId::Cascade(1)
+        };
+
+        FreeCellRules.supermove_limit(target_pile_id, &context)
+    }
+
+    #[test_case(6 => true; "at limit")]
+    #[test_case(7 => false; "over limit")]
+    fn move_to_empty_cascade_should_honor_limit(count: usize) -> bool {
+        // Two free cells and two empty cascades, one of which is the target.
+        let table = table_with_run(2, 2);
+        let action = table::FreeCellTableAction::Move(
+            table::FreeCellPileId::Cascade(0),
+            table::FreeCellPileId::Cascade(7),
+            count,
+        );
+
+        validate_started(&FreeCellRules, &Default::default(), &table, &action).is_ok()
+    }
+
+    #[test]
+    fn occupied_cell_should_be_rejected() {
+        let table = table_with_run(1, 0);
+        let action = table::FreeCellTableAction::Move(
+            table::FreeCellPileId::Cascade(0),
+            table::FreeCellPileId::Cell(0),
+            1,
+        );
+
+        assert_matches!(
+            validate_started(&FreeCellRules, &Default::default(), &table, &action),
+            Err(FreeCellRulesError::CellOccupied { .. })
+        );
+    }
+
+    #[test]
+    fn broken_run_should_not_move() {
+        let table = table_with_run(0, 1);
+        let action = table::FreeCellTableAction::Move(
+            table::FreeCellPileId::Cascade(1),
+            table::FreeCellPileId::Cascade(7),
+            2,
+        );
+
+        assert_matches!(
+            validate_started(&FreeCellRules, &Default::default(), &table, &action),
+            Err(FreeCellRulesError::OutOfSequence)
+        );
+    }
+}
@@ -0,0 +1,14 @@
+use crate::freecell::table;
+use crate::model;
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct FreeCellSettings {
+    /// Allow cards to be taken back off the foundations, which the classic game doesn't.
+    #[serde(default)]
+    pub allow_move_from_foundation: bool,
+}
+
+impl model::scoring::ScoringScheme<table::FreeCellTableAction, table::FreeCellTable>
+    for FreeCellSettings
+{
+}
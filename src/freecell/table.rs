@@ -0,0 +1,277 @@
+use std::convert;
+
+use enum_like::EnumValues as _;
+
+use crate::model;
+use crate::model::table::Table as _;
+
+/// How many free cells there are, each of which can hold a single card.
+pub const CELL_COUNT: usize = 4;
+/// How many cascades the cards are dealt to.
+pub const CASCADE_COUNT: usize = 8;
+
+#[derive(
+    Debug, Copy, Clone, Eq, PartialEq, derive_more::Display, serde::Deserialize, serde::Serialize,
+)]
+pub enum FreeCellPileId {
+    /// Where the deck waits to be dealt. It's empty once the game has started.
+    #[display(fmt = "Stock")]
+    Stock,
+    #[display(fmt = "Cell {}", _0 + 1)]
+    Cell(usize),
+    #[display(fmt = "{} Foundation", _0)]
+    Foundation(model::card::Suit),
+    #[display(fmt = "Cascade {}", _0 + 1)]
+    Cascade(usize),
+}
+
+impl FreeCellPileId {
+    pub fn full_iter() -> impl Iterator<Item = FreeCellPileId> {
+        velcro::iter![
+            FreeCellPileId::Stock,
+            ..(0..CELL_COUNT).map(FreeCellPileId::Cell),
+            ..model::card::Suit::values().map(FreeCellPileId::Foundation),
+            ..(0..CASCADE_COUNT).map(FreeCellPileId::Cascade),
+        ]
+    }
+}
+
+#[derive(Debug, Default, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct FreeCellTable {
+    stock: model::pile::Pile,
+
+    cells: [model::pile::Pile; CELL_COUNT],
+
+    spades_foundation: model::pile::Pile,
+    hearts_foundation: model::pile::Pile,
+    diamonds_foundation: model::pile::Pile,
+    clubs_foundation: model::pile::Pile,
+
+    cascades: [model::pile::Pile; CASCADE_COUNT],
+}
+
+impl model::table::Table for FreeCellTable {
+    type PileId = FreeCellPileId;
+
+    fn new_with_cards<I>(cards: I) -> Self
+    where
+        I: IntoIterator<Item = model::card::Card>,
+    {
+        let stock = model::pile::Pile::new_with_cards(cards);
+        assert!(stock.is_face_down());
+
+        Self {
+            stock,
+            ..Default::default()
+        }
+    }
+
+    fn pile(&self, pile_id: FreeCellPileId) -> &model::pile::Pile {
+        static EMPTY: model::pile::Pile = model::pile::Pile::new();
+
+        match pile_id {
+            FreeCellPileId::Stock => &self.stock,
+            FreeCellPileId::Cell(index) => self.cells.get(index).unwrap_or(&EMPTY),
+            FreeCellPileId::Foundation(suit) => match suit {
+                model::card::Suit::Spades => &self.spades_foundation,
+                model::card::Suit::Hearts => &self.hearts_foundation,
+                model::card::Suit::Diamonds => &self.diamonds_foundation,
+                model::card::Suit::Clubs => &self.clubs_foundation,
+            },
+            FreeCellPileId::Cascade(index) => self.cascades.get(index).unwrap_or(&EMPTY),
+        }
+    }
+
+    fn is_won(&self) -> bool {
+        model::card::Suit::values()
+            .all(|suit| self.pile(FreeCellPileId::Foundation(suit)).len() == 13)
+    }
+
+    /// Once no cascade has a card on top of a higher one, the lowest card left is always free to
+    /// go to its foundation, so the rest of the game plays itself.
+    fn is_auto_completable(&self) -> bool {
+        self.stock.is_empty()
+            && self.cascades.iter().all(|cascade| {
+                cascade
+                    .iter()
+                    .zip(cascade.iter().skip(1))
+                    .all(|(below, above)| below.rank() >= above.rank())
+            })
+    }
+
+    /// The cards that could be picked up from the top of a pile as a group, bottom to top: the
+    /// run of alternating colors at the top of a cascade, or the top card of a cell or foundation.
+    /// How many of them can actually be moved at once depends on the space to move them through.
+    fn movable_cards(&self, pile_id: FreeCellPileId) -> &[model::card::Card] {
+        match pile_id {
+            FreeCellPileId::Stock => &[],
+            FreeCellPileId::Cell(_) | FreeCellPileId::Foundation(_) => {
+                self.pile(pile_id).top_cards(1)
+            }
+            FreeCellPileId::Cascade(_) => {
+                let pile = self.pile(pile_id);
+                let run_length = (1..pile.len())
+                    .find(|&count| !is_run(pile.top_cards(count + 1)))
+                    .unwrap_or(pile.len());
+                pile.top_cards(run_length)
+            }
+        }
+    }
+}
+
+impl FreeCellTable {
+    /// How many cells have no card in them.
+    pub fn free_cells(&self) -> usize {
+        self.cells.iter().filter(|cell| cell.is_empty()).count()
+    }
+
+    /// How many cascades have no cards in them.
+    pub fn empty_cascades(&self) -> usize {
+        self.cascades
+            .iter()
+            .filter(|cascade| cascade.is_empty())
+            .count()
+    }
+
+    /// Panics if the pile doesn't exist, which the rules never allow.
+    fn pile_mut(&mut self, pile_id: FreeCellPileId) -> &mut model::pile::Pile {
+        match pile_id {
+            FreeCellPileId::Stock => &mut self.stock,
+            FreeCellPileId::Cell(index) => &mut self.cells[index],
+            FreeCellPileId::Foundation(suit) => match suit {
+                model::card::Suit::Spades => &mut self.spades_foundation,
+                model::card::Suit::Hearts => &mut self.hearts_foundation,
+                model::card::Suit::Diamonds => &mut self.diamonds_foundation,
+                model::card::Suit::Clubs => &mut self.clubs_foundation,
+            },
+            FreeCellPileId::Cascade(index) => &mut self.cascades[index],
+        }
+    }
+}
+
+/// Whether the cards, bottom to top, go down by one rank at a time in alternating colors.
+pub fn is_run(cards: &[model::card::Card]) -> bool {
+    cards
+        .windows(2)
+        .all(|pair| pair[0].rank().follows(pair[1].rank()) && pair[0].color() != pair[1].color())
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, derive_more::Display, serde::Deserialize, serde::Serialize,
+)]
+pub enum FreeCellTableAction {
+    #[display(fmt = "Deal to the {}", _0)]
+    Deal(FreeCellPileId),
+    #[display(fmt = "Move {} from the {} to the {}", _2, _0, _1)]
+    Move(FreeCellPileId, FreeCellPileId, usize),
+}
+
+impl model::action::Action<FreeCellTable> for FreeCellTableAction {
+    type Error = convert::Infallible;
+
+    fn apply_to(self, table: &mut FreeCellTable) -> Result<(), Self::Error> {
+        match self {
+            // Every card is dealt face up.
+            Self::Deal(target_pile_id) => {
+                let card = table.stock.take_top().flipped();
+                table.pile_mut(target_pile_id).place(card);
+            }
+            Self::Move(source_pile_id, target_pile_id, count) => {
+                let moved_cards = table.pile_mut(source_pile_id).take(count);
+                table.pile_mut(target_pile_id).place(moved_cards);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The actions needed to undo a `FreeCellTableAction`. These are never validated by the rules, so
+/// they aren't suitable as player actions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FreeCellTableInverseAction {
+    /// Turn the top card of a pile face down and put it back on the stock.
+    Undeal(FreeCellPileId),
+    /// Move cards from one pile to another as they are.
+    Move(FreeCellPileId, FreeCellPileId, usize),
+}
+
+impl model::action::Action<FreeCellTable> for FreeCellTableInverseAction {
+    type Error = convert::Infallible;
+
+    fn apply_to(self, table: &mut FreeCellTable) -> Result<(), Self::Error> {
+        match self {
+            Self::Undeal(source_pile_id) => {
+                let card = table.pile_mut(source_pile_id).take_top().flipped();
+                table.stock.place(card);
+            }
+            Self::Move(source_pile_id, target_pile_id, count) => {
+                let moved_cards = table.pile_mut(source_pile_id).take(count);
+                table.pile_mut(target_pile_id).place(moved_cards);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl model::action::Invertible<FreeCellTable> for FreeCellTableAction {
+    type Inverse = FreeCellTableInverseAction;
+
+    fn inverse(&self, table: &FreeCellTable) -> Self::Inverse {
+        match *self {
+            Self::Deal(target_pile_id) => {
+                if table.stock.is_empty() {
+                    // Nothing will be dealt, so there's nothing to put back.
+                    FreeCellTableInverseAction::Move(target_pile_id, FreeCellPileId::Stock, 0)
+                } else {
+                    FreeCellTableInverseAction::Undeal(target_pile_id)
+                }
+            }
+            Self::Move(source_pile_id, target_pile_id, count) => {
+                let count = table.pile(source_pile_id).len().min(count);
+                FreeCellTableInverseAction::Move(target_pile_id, source_pile_id, count)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+    use crate::model::card::{Rank, Suit};
+
+    fn cascade(cards: &[model::card::CardFace]) -> FreeCellTable {
+        let mut table = FreeCellTable::default();
+        table
+            .pile_mut(FreeCellPileId::Cascade(0))
+            .place_cards(cards.iter().map(|face| face.face_up()));
+        table
+    }
+
+    #[test_case(&[] => 0; "empty")]
+    #[test_case(&[Rank::Nine.of(Suit::Spades)] => 1; "single card")]
+    #[test_case(&[
+        Rank::Two.of(Suit::Clubs),
+        Rank::Nine.of(Suit::Spades),
+        Rank::Eight.of(Suit::Hearts),
+        Rank::Seven.of(Suit::Clubs),
+    ] => 3; "run on top")]
+    #[test_case(&[
+        Rank::Nine.of(Suit::Spades),
+        Rank::Eight.of(Suit::Clubs),
+    ] => 1; "same color")]
+    fn movable_cards(cards: &[model::card::CardFace]) -> usize {
+        cascade(cards)
+            .movable_cards(FreeCellPileId::Cascade(0))
+            .len()
+    }
+
+    #[test_case(&[Rank::Nine.of(Suit::Spades), Rank::Two.of(Suit::Clubs)] => true; "descending")]
+    #[test_case(&[Rank::Two.of(Suit::Clubs), Rank::Nine.of(Suit::Spades)] => false; "buried")]
+    fn is_auto_completable(cards: &[model::card::CardFace]) -> bool {
+        cascade(cards).is_auto_completable()
+    }
+}
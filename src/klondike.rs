@@ -1,5 +1,14 @@
+pub mod autoplay;
+pub mod bankroll;
 pub mod dealer;
 pub mod game;
+pub mod hint;
+pub mod replay;
 pub mod rules;
+pub mod save;
+pub mod scoring;
 pub mod settings;
+pub mod solver;
+pub mod statistics;
 pub mod table;
+pub mod text;
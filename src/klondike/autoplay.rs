@@ -0,0 +1,318 @@
+//! Moving cards to the foundations on the player's behalf: either finishing off a game that plays
+//! itself, or just clearing away cards that can no longer be useful on the tableaux.
+
+use std::{collections, iter};
+
+use enum_like::EnumValues as _;
+
+use crate::klondike::{game, rules, settings, table};
+use crate::model;
+use crate::model::action::Actionable as _;
+use crate::model::rules::Rules as _;
+use crate::model::table::Table as _;
+
+/// Finishes a game whose outcome no longer depends on the player. The actions are worked out up
+/// front, but applied one at a time so that front ends can show each of them.
+#[derive(Debug, Clone)]
+pub struct AutoComplete {
+    actions: collections::VecDeque<table::KlondikeTableAction>,
+}
+
+impl AutoComplete {
+    /// Plan the rest of the game, if it has started and every tableaux card is already face up.
+    pub fn new<SH>(game: &game::KlondikeGame<SH>) -> Option<Self> {
+        if !game.is_started() {
+            return None;
+        }
+
+        plan(game.table(), game.settings()).map(|actions| Self {
+            actions: actions.into(),
+        })
+    }
+
+    pub fn actions(&self) -> impl Iterator<Item = &table::KlondikeTableAction> {
+        self.actions.iter()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Apply the next action, returning it, or `None` if there is nothing left to do. If the rules
+    /// reject the action (e.g., because the game changed in the meantime), it stays next in line.
+    pub fn step<SH>(
+        &mut self,
+        game: &mut game::KlondikeGame<SH>,
+    ) -> Result<Option<table::KlondikeTableAction>, game::KlondikeGameError>
+    where
+        SH: model::deck::Shuffle,
+    {
+        let action = match self.actions.front() {
+            Some(&action) => action,
+            None => return Ok(None),
+        };
+
+        game.apply(model::game::TableAction(action))?;
+        self.actions.pop_front();
+        Ok(Some(action))
+    }
+
+    /// Apply all the remaining actions.
+    pub fn run<SH>(
+        &mut self,
+        game: &mut game::KlondikeGame<SH>,
+    ) -> Result<(), game::KlondikeGameError>
+    where
+        SH: model::deck::Shuffle,
+    {
+        while self.step(game)?.is_some() {}
+        Ok(())
+    }
+}
+
+/// The actions that win from this position without any decisions being made: playing whatever can
+/// go to a foundation, and drawing when nothing can. Returns `None` if some tableaux card is still
+/// face down, or if the stock gets cycled through without anything being played.
+pub fn plan(
+    table: &table::KlondikeTable,
+    settings: &settings::KlondikeSettings,
+) -> Option<Vec<table::KlondikeTableAction>> {
+    let all_face_up = (0..settings.tableaux_width).all(|index| {
+        table
+            .pile(table::KlondikePileId::Tableaux(index))
+            .is_face_up()
+    });
+    if !all_face_up || table.is_won() {
+        return None;
+    }
+
+    let mut table = table.clone();
+    let mut actions = Vec::new();
+    let mut draws_since_play = 0;
+
+    while !table.is_won() {
+        let action = match foundation_moves(&table, settings).next() {
+            Some(action) => {
+                draws_since_play = 0;
+                action
+            }
+            None => {
+                // One more draw than it takes to go all the way through the stock and waste, so that
+                // the waste gets turned back over.
+                let cards = table.pile(table::KlondikePileId::Stock).len()
+                    + table.pile(table::KlondikePileId::Waste).len();
+                let draw = table::KlondikeTableAction::Draw(settings.draw_count);
+                if draws_since_play > cards / settings.draw_count.max(1) + 1
+                    || !is_legal(&table, settings, draw)
+                {
+                    return None;
+                }
+
+                draws_since_play += 1;
+                draw
+            }
+        };
+
+        table.apply(action).unwrap_or_else(|never| match never {});
+        actions.push(action);
+    }
+
+    Some(actions)
+}
+
+/// A single move of a card to its foundation that can't make the game any harder, if there is one.
+/// A card is safe to move once neither card of the opposite color one rank lower could ever need
+/// to be built on it, i.e., once both of those are on their foundations already. Aces and Twos are
+/// always safe.
+pub fn safe_move(
+    table: &table::KlondikeTable,
+    settings: &settings::KlondikeSettings,
+) -> Option<table::KlondikeTableAction> {
+    foundation_moves(table, settings).find(|&action| match action {
+        table::KlondikeTableAction::Move(source_pile_id, _, _) => table
+            .pile(source_pile_id)
+            .top_card()
+            .is_some_and(|card| is_safe(table, card)),
+        _ => false,
+    })
+}
+
+/// Keep applying safe moves until there are none left, if the settings ask for it. Returns the
+/// actions that were applied. They're follow-ups to the last action applied, so they're undone
+/// along with it.
+pub fn apply_safe_moves<SH>(
+    game: &mut game::KlondikeGame<SH>,
+) -> Result<Vec<table::KlondikeTableAction>, game::KlondikeGameError>
+where
+    SH: model::deck::Shuffle,
+{
+    let mut applied = Vec::new();
+    if !game.settings().auto_move_to_foundation || !game.is_started() {
+        return Ok(applied);
+    }
+
+    while let Some(action) = safe_move(game.table(), game.settings()) {
+        game.apply(model::game::FollowUpAction(action))?;
+        applied.push(action);
+    }
+
+    Ok(applied)
+}
+
+fn is_safe(table: &table::KlondikeTable, card: &model::card::Card) -> bool {
+    if card.rank() <= model::card::Rank::Two {
+        return true;
+    }
+
+    model::card::Suit::values()
+        .filter(|suit| suit.color() != card.color())
+        .all(|suit| {
+            table
+                .pile(table::KlondikePileId::Foundation(suit))
+                .top_card()
+                .is_some_and(|top_card| top_card.rank() as u8 + 1 >= card.rank() as u8)
+        })
+}
+
+/// Every legal move of a single card from the waste or a tableaux to its foundation.
+fn foundation_moves<'a>(
+    table: &'a table::KlondikeTable,
+    settings: &'a settings::KlondikeSettings,
+) -> impl Iterator<Item = table::KlondikeTableAction> + 'a {
+    let source_ids = iter::once(table::KlondikePileId::Waste)
+        .chain((0..settings.tableaux_width).map(table::KlondikePileId::Tableaux));
+
+    source_ids
+        .filter_map(move |source_pile_id| {
+            let card = table.pile(source_pile_id).top_card()?;
+            Some(table::KlondikeTableAction::Move(
+                source_pile_id,
+                table::KlondikePileId::Foundation(card.suit()),
+                1,
+            ))
+        })
+        .filter(move |&action| is_legal(table, settings, action))
+}
+
+fn is_legal(
+    table: &table::KlondikeTable,
+    settings: &settings::KlondikeSettings,
+    action: table::KlondikeTableAction,
+) -> bool {
+    let context = rules::KlondikeRulesContext::new(settings, true, table);
+    rules::KlondikeRules.validate(&action, &context).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+    use crate::klondike::{dealer, solver};
+    use crate::model::card::{Rank, Suit};
+    use crate::model::deck;
+    use crate::model::game::{GameStatus, TableAction};
+
+    fn dealt_game(seed: u64) -> game::KlondikeGame<deck::SeededShuffle> {
+        let settings = settings::KlondikeSettings {
+            draw_count: 1,
+            ..Default::default()
+        };
+        let mut game = game::KlondikeGame::new(
+            dealer::KlondikeDealer,
+            rules::KlondikeRules,
+            settings,
+            deck::SeededShuffle::new(seed),
+        );
+        game.deal_and_start().unwrap();
+        game
+    }
+
+    #[test]
+    fn auto_complete_should_win() {
+        let mut game = dealt_game(0);
+        let actions = match solver::KlondikeSolver::default().solve_game(&game) {
+            solver::SolverOutcome::Solved(actions) => actions,
+            outcome => panic!("Expected a solution, got {:?}", outcome),
+        };
+
+        let mut auto_complete = None;
+        for action in actions {
+            auto_complete = AutoComplete::new(&game);
+            if auto_complete.is_some() {
+                break;
+            }
+            game.apply(TableAction(action)).unwrap();
+        }
+
+        auto_complete.unwrap().run(&mut game).unwrap();
+        assert_eq!(game.status(), GameStatus::Won);
+    }
+
+    #[test]
+    fn undo_should_take_back_safe_moves_with_move_before_them() {
+        let settings = settings::KlondikeSettings {
+            auto_move_to_foundation: true,
+            draw_count: 1,
+            ..Default::default()
+        };
+        let mut game = game::KlondikeGame::new(
+            dealer::KlondikeDealer,
+            rules::KlondikeRules,
+            settings,
+            deck::SeededShuffle::new(0),
+        );
+        game.deal_and_start().unwrap();
+        let actions = match solver::KlondikeSolver::default().solve_game(&game) {
+            solver::SolverOutcome::Solved(actions) => actions,
+            outcome => panic!("Expected a solution, got {:?}", outcome),
+        };
+
+        // Play until a move sets off some safe moves.
+        for action in actions {
+            let before = game.table().clone();
+            game.apply(TableAction(action)).unwrap();
+            if apply_safe_moves(&mut game).unwrap().is_empty() {
+                continue;
+            }
+            let after = game.table().clone();
+
+            game.undo().unwrap();
+            assert_eq!(game.table(), &before);
+            game.redo().unwrap();
+            assert_eq!(game.table(), &after);
+            return;
+        }
+        panic!("Expected a safe move");
+    }
+
+    #[test]
+    fn auto_complete_should_wait_for_face_down_cards() {
+        assert!(AutoComplete::new(&dealt_game(0)).is_none());
+    }
+
+    #[test_case(Rank::Two, Rank::Ace, Rank::Two => true; "two")]
+    #[test_case(Rank::Three, Rank::Two, Rank::Two => true; "both black twos played")]
+    #[test_case(Rank::Three, Rank::Ace, Rank::Two => false; "black two still in play")]
+    fn is_safe(rank: Rank, spades: Rank, clubs: Rank) -> bool {
+        let foundation = |top: Rank, suit| {
+            model::pile::Pile::new_with_cards(
+                Rank::values()
+                    .take_while(|&rank| rank <= top)
+                    .map(|rank| rank.of(suit).face_up()),
+            )
+        };
+        let table: table::KlondikeTable = serde_json::from_value(serde_json::json!({
+            "stock": [],
+            "waste": [],
+            "spades_foundation": foundation(spades, Suit::Spades),
+            "hearts_foundation": [],
+            "diamonds_foundation": [],
+            "clubs_foundation": foundation(clubs, Suit::Clubs),
+            "tableaux": [],
+        }))
+        .unwrap();
+
+        super::is_safe(&table, &rank.of(Suit::Diamonds).face_up())
+    }
+}
@@ -0,0 +1,101 @@
+//! A Vegas bankroll that carries over from game to game. It lives in a small JSON profile file, so
+//! winnings (and losses) survive between sessions.
+
+use std::path::Path;
+use std::{fs, io};
+
+use snafu::{ResultExt as _, Snafu};
+
+use crate::klondike::game;
+
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Bankroll {
+    /// The total of every settled game.
+    pub balance: i64,
+    /// How many games have been settled.
+    pub games: u64,
+}
+
+#[derive(Debug, Snafu)]
+pub enum BankrollError {
+    #[snafu(display("Unable to read bankroll: {}", source))]
+    Read { source: io::Error },
+    #[snafu(display("Bankroll is not valid: {}", source))]
+    Parse { source: serde_json::Error },
+    #[snafu(display("Unable to write bankroll: {}", source))]
+    Write { source: io::Error },
+    #[snafu(display("Unable to encode bankroll: {}", source))]
+    Encode { source: serde_json::Error },
+}
+
+impl Bankroll {
+    /// Load the bankroll from a profile file. A missing file is an empty bankroll.
+    pub fn load(path: &Path) -> Result<Self, BankrollError> {
+        match fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).context(Parse),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error).context(Read),
+        }
+    }
+
+    /// Save the bankroll to a profile file, creating its directory if needed.
+    pub fn save(&self, path: &Path) -> Result<(), BankrollError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context(Write)?;
+        }
+
+        let contents = serde_json::to_string_pretty(self).context(Encode)?;
+        fs::write(path, contents).context(Write)
+    }
+
+    /// Add a finished game's score to the bankroll.
+    pub fn settle(&mut self, score: i64) {
+        self.balance += score;
+        self.games += 1;
+    }
+
+    /// The balance as it would be if the game in progress were settled now.
+    pub fn running_total<SH>(&self, game: &game::KlondikeGame<SH>) -> i64 {
+        self.balance + game.total_score()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    fn profile_path(name: &str) -> std::path::PathBuf {
+        env::temp_dir()
+            .join(format!("klondike2-test-{}", process::id()))
+            .join(name)
+    }
+
+    #[test]
+    fn missing_profile_should_be_empty() {
+        let path = profile_path("missing.json");
+
+        assert_eq!(Bankroll::load(&path).unwrap(), Bankroll::default());
+    }
+
+    #[test]
+    fn bankroll_should_persist() {
+        let path = profile_path("bankroll.json");
+        let mut bankroll = Bankroll::default();
+        bankroll.settle(-52);
+        bankroll.settle(-52 + 13 * 5);
+        bankroll.save(&path).unwrap();
+
+        let loaded = Bankroll::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            loaded,
+            Bankroll {
+                balance: -39,
+                games: 2
+            }
+        );
+    }
+}
@@ -1,5 +1,9 @@
+use enum_like::EnumValues as _;
+use snafu::{OptionExt as _, Snafu};
+
 use crate::klondike::{game, table};
 use crate::model;
+use crate::model::table::Table as _;
 
 #[derive(Debug, Clone)]
 pub struct KlondikeDealer;
@@ -27,14 +31,31 @@ impl<'a> From<game::KlondikeGameDealerContext<'a>> for KlondikeDealerContext {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct KlondikeDealerIter {
     tableaux_width: usize,
     state: DealerIterState,
 }
 
+#[derive(Debug, Snafu)]
+pub enum KlondikeDealerError {
+    #[snafu(display(
+        "The dealer is dealing {} tableaux, but the game has {}",
+        dealer_width,
+        tableaux_width
+    ))]
+    WidthMismatch {
+        dealer_width: usize,
+        tableaux_width: usize,
+    },
+    #[snafu(display("The dealer is at a position it never reaches"))]
+    UnreachablePosition,
+    #[snafu(display("The {} doesn't match how far the deal has got", pile_id))]
+    LayoutMismatch { pile_id: table::KlondikePileId },
+}
+
 impl KlondikeDealerIter {
-    fn new(tableaux_width: usize) -> Self {
+    pub(crate) fn new(tableaux_width: usize) -> Self {
         let state = DealerIterState::init(tableaux_width);
 
         Self {
@@ -42,6 +63,75 @@ impl KlondikeDealerIter {
             state,
         }
     }
+
+    /// Whether every card has been dealt and revealed.
+    pub fn is_finished(&self) -> bool {
+        self.state == DealerIterState::Done
+    }
+
+    /// Check that the dealer could have got this far for a game with this many tableaux, and that
+    /// the table is laid out the way the deal so far would have left it. Once the deal is
+    /// finished, only the width is checked, since play may have moved any of the cards.
+    pub fn validate(
+        &self,
+        tableaux_width: usize,
+        table: &table::KlondikeTable,
+    ) -> Result<(), KlondikeDealerError> {
+        snafu::ensure!(
+            self.tableaux_width == tableaux_width,
+            WidthMismatch {
+                dealer_width: self.tableaux_width,
+                tableaux_width,
+            }
+        );
+        if self.is_finished() {
+            return Ok(());
+        }
+
+        // Replay the deal up to this position, counting the cards each tableaux should have.
+        let mut dealt = vec![0; tableaux_width];
+        let mut revealed = vec![false; tableaux_width];
+        let mut dealer_iter = Self::new(tableaux_width);
+        while dealer_iter.state != self.state {
+            match dealer_iter.next() {
+                Some(table::KlondikeTableAction::Deal(table::KlondikePileId::Tableaux(index))) => {
+                    dealt[index] += 1
+                }
+                Some(table::KlondikeTableAction::Reveal(table::KlondikePileId::Tableaux(
+                    index,
+                ))) => revealed[index] = true,
+                _ => return UnreachablePosition.fail(),
+            }
+        }
+
+        let stock_size = model::card::CardFace::values()
+            .count()
+            .checked_sub(dealt.iter().sum())
+            .context(UnreachablePosition)?;
+        let pile_sizes = velcro::iter![
+            (table::KlondikePileId::Stock, stock_size),
+            (table::KlondikePileId::Waste, 0),
+            ..model::card::Suit::values().map(|suit| (table::KlondikePileId::Foundation(suit), 0)),
+        ];
+        for (pile_id, size) in pile_sizes {
+            snafu::ensure!(
+                table.pile(pile_id).len() == size,
+                LayoutMismatch { pile_id }
+            );
+        }
+
+        for index in 0..tableaux_width {
+            let pile_id = table::KlondikePileId::Tableaux(index);
+            let pile = table.pile(pile_id);
+            snafu::ensure!(
+                pile.len() == dealt[index]
+                    && pile.top_face_up_cards().len() == usize::from(revealed[index]),
+                LayoutMismatch { pile_id }
+            );
+        }
+
+        Ok(())
+    }
 }
 
 impl Iterator for KlondikeDealerIter {
@@ -54,8 +144,8 @@ impl Iterator for KlondikeDealerIter {
     }
 }
 
-mod position {
-    #[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub(crate) mod position {
+    #[derive(Debug, Copy, Clone, Eq, PartialEq, Default, serde::Deserialize, serde::Serialize)]
     pub struct Dealing {
         column: usize,
         row: usize,
@@ -82,7 +172,7 @@ mod position {
         }
     }
 
-    #[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+    #[derive(Debug, Copy, Clone, Eq, PartialEq, Default, serde::Deserialize, serde::Serialize)]
     pub struct Revealing {
         index: usize,
     }
@@ -125,7 +215,7 @@ mod position {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
 enum DealerIterState {
     Deal(position::Dealing),
     Reveal(position::Revealing),
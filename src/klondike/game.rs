@@ -1,3 +1,5 @@
+use std::convert;
+
 use crate::klondike::{dealer, rules, settings, table};
 use crate::model;
 
@@ -9,7 +11,397 @@ pub type KlondikeGame<SH> = model::game::Game<
     table::KlondikeTable,
 >;
 
+pub type KlondikeGameError = model::rules::RulesGuardError<
+    rules::KlondikeRulesError,
+    convert::Infallible,
+    table::KlondikeTableAction,
+>;
+pub type KlondikeGameHistoryError =
+    model::game::GameHistoryError<convert::Infallible, convert::Infallible>;
+
 pub type KlondikeGameDealerContext<'a> =
     model::game::GameDealerContext<'a, settings::KlondikeSettings>;
 pub type KlondikeGameRulesContext<'a> =
     model::game::GameRulesContext<'a, settings::KlondikeSettings, table::KlondikeTable>;
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time;
+
+    use super::*;
+    use crate::klondike::solver;
+    use crate::model::action::Actionable as _;
+    use crate::model::deck;
+    use crate::model::game::{DealAction, GameAction, GameStatus, TableAction};
+    use crate::model::table::Table as _;
+
+    fn started_game() -> KlondikeGame<deck::UnShuffle> {
+        let mut game = KlondikeGame::new(
+            dealer::KlondikeDealer,
+            rules::KlondikeRules,
+            Default::default(),
+            deck::UnShuffle,
+        );
+
+        // Deal 28 cards and reveal 7, plus one extra step to discover the dealer is done.
+        for _ in 0..36 {
+            game.apply(DealAction).unwrap();
+        }
+        game.apply(GameAction::Start).unwrap();
+
+        game
+    }
+
+    #[test]
+    fn undo_should_restore_table() {
+        let mut game = started_game();
+        let before = game.table().clone();
+
+        game.apply(TableAction(table::KlondikeTableAction::Draw(3)))
+            .unwrap();
+        assert_ne!(game.table(), &before);
+
+        game.undo().unwrap();
+        assert_eq!(game.table(), &before);
+    }
+
+    #[test]
+    fn redo_should_reapply_action() {
+        let mut game = started_game();
+
+        game.apply(TableAction(table::KlondikeTableAction::Draw(3)))
+            .unwrap();
+        let after = game.table().clone();
+
+        game.undo().unwrap();
+        game.redo().unwrap();
+        assert_eq!(game.table(), &after);
+        assert!(!game.can_redo());
+    }
+
+    #[test]
+    fn undo_should_take_back_redeal() {
+        let mut game = started_game();
+        while !game.table().pile(table::KlondikePileId::Stock).is_empty() {
+            game.apply(TableAction(table::KlondikeTableAction::Draw(3)))
+                .unwrap();
+        }
+        let before = game.table().clone();
+
+        game.apply(TableAction(table::KlondikeTableAction::Draw(3)))
+            .unwrap();
+        assert_eq!(game.table().redeals(), 1);
+
+        game.undo().unwrap();
+        assert_eq!(game.table(), &before);
+        assert_eq!(game.table().redeals(), 0);
+    }
+
+    #[test]
+    fn undo_should_walk_back_to_fresh_game() {
+        let mut game = started_game();
+        game.apply(TableAction(table::KlondikeTableAction::Draw(3)))
+            .unwrap();
+        game.apply(GameAction::Clear).unwrap();
+
+        while game.can_undo() {
+            game.undo().unwrap();
+        }
+
+        let fresh = KlondikeGame::new(
+            dealer::KlondikeDealer,
+            rules::KlondikeRules,
+            Default::default(),
+            deck::UnShuffle,
+        );
+        assert_eq!(game.table(), fresh.table());
+        assert!(!game.is_started());
+
+        // Replaying everything should put us back where we were, after the clear.
+        while game.can_redo() {
+            game.redo().unwrap();
+        }
+        assert!(!game.is_started());
+        assert_eq!(game.table(), fresh.table());
+    }
+
+    #[test]
+    fn game_should_expose_seed_of_current_deal() {
+        let mut game = KlondikeGame::new(
+            dealer::KlondikeDealer,
+            rules::KlondikeRules,
+            Default::default(),
+            deck::SeededShuffle::new(11982),
+        );
+        assert_eq!(game.seed(), Some(11982));
+
+        game.apply(GameAction::Clear).unwrap();
+        let next_seed = game.seed().unwrap();
+        assert_ne!(next_seed, 11982);
+
+        // Re-entering the seed should reproduce the same deal.
+        let replayed = KlondikeGame::new(
+            dealer::KlondikeDealer,
+            rules::KlondikeRules,
+            Default::default(),
+            deck::SeededShuffle::new(next_seed),
+        );
+        assert_eq!(game.table(), replayed.table());
+
+        game.undo().unwrap();
+        assert_eq!(game.seed(), Some(11982));
+    }
+
+    #[test]
+    fn rejected_deal_should_not_skip_a_card() {
+        let new_game = || {
+            KlondikeGame::new(
+                dealer::KlondikeDealer,
+                rules::KlondikeRules,
+                Default::default(),
+                deck::UnShuffle,
+            )
+        };
+        let mut game = new_game();
+        game.apply(DealAction).unwrap();
+        game.apply(GameAction::Start).unwrap();
+
+        // The rules don't allow dealing once the game has started.
+        assert!(game.apply(DealAction).is_err());
+        game.undo().unwrap();
+        while !game.is_dealt() {
+            game.apply(DealAction).unwrap();
+        }
+
+        let mut expected = new_game();
+        while !expected.is_dealt() {
+            expected.apply(DealAction).unwrap();
+        }
+        assert_eq!(game.table(), expected.table());
+    }
+
+    #[test]
+    fn history_should_be_bounded() {
+        let mut game = started_game();
+        game.set_history_depth(2);
+
+        for _ in 0..5 {
+            game.apply(TableAction(table::KlondikeTableAction::Draw(3)))
+                .unwrap();
+        }
+
+        assert!(game.undo().is_ok());
+        assert!(game.undo().is_ok());
+        assert!(game.undo().is_err());
+    }
+
+    #[test]
+    fn game_should_deal_from_fixed_order() {
+        let faces = "AS 2S 3S 4S 5S 6S 7S 8S 9S TS JS QS KS \
+                     AH 2H 3H 4H 5H 6H 7H 8H 9H TH JH QH KH \
+                     AD 2D 3D 4D 5D 6D 7D 8D 9D TD JD QD KD \
+                     KC QC JC TC 9C 8C 7C 6C 5C 4C 3C 2C AC"
+            .split_whitespace()
+            .map(|text| text.parse().unwrap());
+        let mut game = KlondikeGame::new(
+            dealer::KlondikeDealer,
+            rules::KlondikeRules,
+            Default::default(),
+            deck::FixedShuffle::new(faces).unwrap(),
+        );
+        while !game.is_dealt() {
+            game.apply(DealAction).unwrap();
+        }
+
+        // The last card in the order is dealt first.
+        assert_eq!(
+            game.table()
+                .pile(table::KlondikePileId::Tableaux(0))
+                .top_card()
+                .map(|card| card.face),
+            Some(model::card::Rank::Ace.of(model::card::Suit::Clubs))
+        );
+    }
+
+    #[test]
+    fn clock_should_run_only_while_playing() {
+        let mut game = KlondikeGame::new(
+            dealer::KlondikeDealer,
+            rules::KlondikeRules,
+            Default::default(),
+            deck::UnShuffle,
+        );
+        let clock = model::clock::FakeClock::new();
+        game.set_clock(Arc::new(clock.clone()));
+        let second = time::Duration::from_secs(1);
+
+        clock.advance(second);
+        assert_eq!(game.elapsed(), time::Duration::ZERO);
+
+        game.apply(GameAction::Start).unwrap();
+        clock.advance(second);
+        game.pause();
+        clock.advance(second);
+        assert!(game.is_paused());
+        assert_eq!(game.elapsed(), second);
+
+        game.resume();
+        clock.advance(second);
+        assert_eq!(game.elapsed(), 2 * second);
+
+        game.apply(GameAction::Clear).unwrap();
+        clock.advance(second);
+        assert_eq!(game.elapsed(), time::Duration::ZERO);
+
+        // Going back to the cleared game picks up its clock where it left off.
+        game.undo().unwrap();
+        clock.advance(second);
+        assert_eq!(game.elapsed(), 3 * second);
+
+        game.undo().unwrap();
+        clock.advance(second);
+        assert!(!game.is_started());
+        assert_eq!(game.elapsed(), 3 * second);
+    }
+
+    #[test]
+    fn moves_while_paused_should_not_restart_clock() {
+        let mut game = KlondikeGame::new(
+            dealer::KlondikeDealer,
+            rules::KlondikeRules,
+            Default::default(),
+            deck::SeededShuffle::new(11982),
+        );
+        let clock = model::clock::FakeClock::new();
+        game.set_clock(Arc::new(clock.clone()));
+        game.deal_and_start().unwrap();
+        let second = time::Duration::from_secs(1);
+
+        clock.advance(second);
+        game.pause();
+        game.apply(TableAction(table::KlondikeTableAction::Draw(3)))
+            .unwrap();
+        clock.advance(second);
+        assert!(game.is_paused());
+        assert_eq!(game.elapsed(), second);
+
+        game.undo().unwrap();
+        clock.advance(second);
+        assert!(game.is_paused());
+        assert_eq!(game.elapsed(), second);
+
+        game.resume();
+        clock.advance(second);
+        assert!(!game.is_paused());
+        assert_eq!(game.elapsed(), 2 * second);
+    }
+
+    #[test]
+    fn status_should_follow_game_to_win() {
+        let settings = settings::KlondikeSettings {
+            draw_count: 1,
+            ..Default::default()
+        };
+        let mut game = KlondikeGame::new(
+            dealer::KlondikeDealer,
+            rules::KlondikeRules,
+            settings,
+            deck::SeededShuffle::new(0),
+        );
+        while !game.is_dealt() {
+            game.apply(DealAction).unwrap();
+        }
+        assert_eq!(game.status(), GameStatus::InProgress);
+        game.apply(GameAction::Start).unwrap();
+        assert_eq!(game.status(), GameStatus::InProgress);
+
+        let actions = match solver::KlondikeSolver::default().solve_game(&game) {
+            solver::SolverOutcome::Solved(actions) => actions,
+            outcome => panic!("Expected a solution, got {:?}", outcome),
+        };
+
+        let mut auto_completable = false;
+        for action in actions {
+            game.apply(TableAction(action)).unwrap();
+            auto_completable |= game.status() == GameStatus::AutoCompletable;
+        }
+
+        assert!(auto_completable);
+        assert_eq!(game.status(), GameStatus::Won);
+        assert!(!game.is_paused());
+    }
+
+    /// A game one move short of winning, timed by the returned clock, along with the winning move.
+    fn game_before_win() -> (
+        KlondikeGame<deck::SeededShuffle>,
+        model::clock::FakeClock,
+        table::KlondikeTableAction,
+    ) {
+        let settings = settings::KlondikeSettings {
+            draw_count: 1,
+            ..Default::default()
+        };
+        let mut game = KlondikeGame::new(
+            dealer::KlondikeDealer,
+            rules::KlondikeRules,
+            settings,
+            deck::SeededShuffle::new(0),
+        );
+        let clock = model::clock::FakeClock::new();
+        game.set_clock(Arc::new(clock.clone()));
+        game.deal_and_start().unwrap();
+
+        let mut actions = match solver::KlondikeSolver::default().solve_game(&game) {
+            solver::SolverOutcome::Solved(actions) => actions,
+            outcome => panic!("Expected a solution, got {:?}", outcome),
+        };
+        let winning_action = actions.pop().unwrap();
+        for action in actions {
+            game.apply(TableAction(action)).unwrap();
+        }
+
+        (game, clock, winning_action)
+    }
+
+    #[test]
+    fn undoing_win_should_restart_clock() {
+        let (mut game, clock, winning_action) = game_before_win();
+        let second = time::Duration::from_secs(1);
+
+        game.apply(TableAction(winning_action)).unwrap();
+        clock.advance(second);
+        assert_eq!(game.elapsed(), time::Duration::ZERO);
+
+        game.undo().unwrap();
+        clock.advance(second);
+        assert_eq!(game.elapsed(), second);
+    }
+
+    #[test]
+    fn redoing_win_should_stop_clock() {
+        let (mut game, clock, winning_action) = game_before_win();
+        let second = time::Duration::from_secs(1);
+
+        game.apply(TableAction(winning_action)).unwrap();
+        game.undo().unwrap();
+        game.redo().unwrap();
+        clock.advance(second);
+
+        assert_eq!(game.status(), GameStatus::Won);
+        assert_eq!(game.elapsed(), time::Duration::ZERO);
+    }
+
+    #[test]
+    fn resume_after_win_should_do_nothing() {
+        let (mut game, clock, winning_action) = game_before_win();
+        let second = time::Duration::from_secs(1);
+
+        game.apply(TableAction(winning_action)).unwrap();
+        game.resume();
+        clock.advance(second);
+
+        assert!(!game.is_paused());
+        assert_eq!(game.elapsed(), time::Duration::ZERO);
+    }
+}
@@ -0,0 +1,381 @@
+//! A cheap hint engine. Unlike the solver, this doesn't look ahead at all; it just ranks the
+//! currently legal actions with a few rules of thumb.
+
+use crate::klondike::{game, rules, settings, solver, table};
+use crate::model;
+use crate::model::table::Table as _;
+
+/// Why an action was suggested. These are stable identifiers, so front ends can match on them (or
+/// on their `Display` form) to explain a hint in their own words.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, derive_more::Display)]
+pub enum HintReason {
+    #[display(fmt = "reveal-card")]
+    RevealCard,
+    #[display(fmt = "to-foundation")]
+    ToFoundation,
+    #[display(fmt = "expose-face-down")]
+    ExposeFaceDown,
+    #[display(fmt = "empty-column")]
+    EmptyColumn,
+    #[display(fmt = "king-to-empty")]
+    KingToEmpty,
+    #[display(fmt = "from-waste")]
+    FromWaste,
+    #[display(fmt = "unblock-foundation")]
+    UnblockFoundation,
+    #[display(fmt = "draw")]
+    Draw,
+    #[display(fmt = "from-foundation")]
+    FromFoundation,
+    #[display(fmt = "pointless")]
+    Pointless,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Hint {
+    pub action: table::KlondikeTableAction,
+    pub reason: HintReason,
+    pub score: i32,
+}
+
+/// All legal actions for the game, best first.
+pub fn hints<SH>(game: &game::KlondikeGame<SH>) -> Vec<Hint> {
+    hints_for_table(game.table(), game.settings(), game.is_started())
+}
+
+pub fn best_hint<SH>(game: &game::KlondikeGame<SH>) -> Option<Hint> {
+    hints(game).into_iter().next()
+}
+
+pub fn hints_for_table(
+    table: &table::KlondikeTable,
+    settings: &settings::KlondikeSettings,
+    started: bool,
+) -> Vec<Hint> {
+    let context = rules::KlondikeRulesContext::new(settings, started, table);
+
+    let mut hints = rules::KlondikeRules
+        .legal_actions(&context)
+        .into_iter()
+        .map(|action| {
+            let (reason, score) = assess(table, settings, action);
+            Hint {
+                action,
+                reason,
+                score,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // The sort is stable, so equally good hints stay in the rules' order.
+    hints.sort_by_key(|hint| -hint.score);
+    hints
+}
+
+fn assess(
+    table: &table::KlondikeTable,
+    settings: &settings::KlondikeSettings,
+    action: table::KlondikeTableAction,
+) -> (HintReason, i32) {
+    if solver::is_pointless(table, action) {
+        return (HintReason::Pointless, 0);
+    }
+
+    match action {
+        table::KlondikeTableAction::Reveal(_) => (HintReason::RevealCard, 100),
+        table::KlondikeTableAction::Move(
+            source_pile_id,
+            table::KlondikePileId::Foundation(_),
+            _,
+        ) => {
+            // Playing to a foundation is even better when it also uncovers a face-down card.
+            let uncovers = face_down_beneath(table, source_pile_id, 1);
+            (HintReason::ToFoundation, 80 + 10 * i32::from(uncovers > 0))
+        }
+        table::KlondikeTableAction::Move(
+            source_pile_id @ table::KlondikePileId::Tableaux(_),
+            target_pile_id,
+            count,
+        ) => {
+            let source = table.pile(source_pile_id);
+            let uncovers = face_down_beneath(table, source_pile_id, count);
+
+            if uncovers > 0 {
+                // Prefer digging into the deepest columns, since they're hardest to clear later.
+                (HintReason::ExposeFaceDown, 60 + uncovers.min(20) as i32)
+            } else if count == source.len() {
+                let score = if king_waiting(table, settings) {
+                    50
+                } else {
+                    20
+                };
+                (HintReason::EmptyColumn, score)
+            } else if table.pile(target_pile_id).is_empty() {
+                (HintReason::KingToEmpty, 45)
+            } else {
+                (HintReason::UnblockFoundation, 35)
+            }
+        }
+        table::KlondikeTableAction::Move(table::KlondikePileId::Waste, target_pile_id, _) => {
+            if table.pile(target_pile_id).is_empty() {
+                (HintReason::KingToEmpty, 45)
+            } else {
+                (HintReason::FromWaste, 40)
+            }
+        }
+        table::KlondikeTableAction::Move(table::KlondikePileId::Foundation(_), _, _) => {
+            (HintReason::FromFoundation, 5)
+        }
+        table::KlondikeTableAction::Draw(_) => (HintReason::Draw, 10),
+        table::KlondikeTableAction::Move(table::KlondikePileId::Stock, _, _)
+        | table::KlondikeTableAction::Deal(_) => (HintReason::Pointless, 0),
+    }
+}
+
+/// How many face-down cards are left under the top `count` cards of the pile.
+fn face_down_beneath(
+    table: &table::KlondikeTable,
+    pile_id: table::KlondikePileId,
+    count: usize,
+) -> usize {
+    let pile = table.pile(pile_id);
+    let remaining = pile.len().saturating_sub(count);
+    pile.iter()
+        .take(remaining)
+        .filter(|card| card.is_face_down())
+        .count()
+}
+
+/// Whether there is a King somewhere that would benefit from an empty column: either on the waste,
+/// or at the bottom of a face-up run with face-down cards beneath it.
+pub(crate) fn king_waiting(
+    table: &table::KlondikeTable,
+    settings: &settings::KlondikeSettings,
+) -> bool {
+    let waste_king = table
+        .pile(table::KlondikePileId::Waste)
+        .top_card()
+        .is_some_and(model::card::Card::is_king);
+
+    let buried_king = (0..settings.tableaux_width).any(|index| {
+        let pile = table.pile(table::KlondikePileId::Tableaux(index));
+        let run = pile.top_face_up_cards();
+        run.len() < pile.len() && run.first().is_some_and(model::card::Card::is_king)
+    });
+
+    waste_king || buried_king
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+    use crate::klondike::{dealer, text};
+    use crate::model::card::Suit;
+    use crate::model::deck;
+
+    const REVEAL_CARD: &str = "\
+Stock: 51
+Waste:
+Foundations: -- -- -- --
+1: [**]
+";
+
+    const TO_FOUNDATION: &str = "\
+Stock: 50
+Waste: AS
+Foundations: -- -- -- --
+1: 5H
+";
+
+    const EXPOSE_FACE_DOWN: &str = "\
+Stock: 49
+Waste:
+Foundations: -- -- -- --
+1: [**] 7S
+2: 8H
+";
+
+    const EMPTY_COLUMN: &str = "\
+Stock: 50
+Waste:
+Foundations: -- -- -- --
+1: 7S
+2: 8H
+";
+
+    const KING_TO_EMPTY: &str = "\
+Stock: 51
+Waste: KS
+Foundations: -- -- -- --
+1:
+";
+
+    const FROM_WASTE: &str = "\
+Stock: 50
+Waste: 7S
+Foundations: -- -- -- --
+1: 8H
+";
+
+    // Moving the 7D lets the 8S go to its foundation.
+    const UNBLOCK_FOUNDATION: &str = "\
+Stock: 41
+Waste:
+Foundations: 7S -- -- --
+1: 9H 8S 7D
+2: 8C
+";
+
+    const DRAW: &str = "\
+Stock: 51
+Waste:
+Foundations: -- -- -- --
+1: 5H
+";
+
+    // The only thing left to do is take the QH back down onto the KC.
+    const FROM_FOUNDATION: &str = "\
+Stock: 0
+Waste:
+Foundations: KS QH JC KD
+1: [**] [**] KC
+";
+
+    // The stock can't be turned over again, so all that's left is to move the KC to the other
+    // column.
+    const POINTLESS: &str = "\
+Stock: 0
+Waste: KS QH KH JC QD KD QC
+Foundations: QS JH TC JD
+1: KC
+2:
+";
+
+    fn dealt_game(seed: u64) -> game::KlondikeGame<deck::SeededShuffle> {
+        let mut game = game::KlondikeGame::new(
+            dealer::KlondikeDealer,
+            rules::KlondikeRules,
+            Default::default(),
+            deck::SeededShuffle::new(seed),
+        );
+        game.deal_and_start().unwrap();
+        game
+    }
+
+    #[test]
+    fn hints_should_be_sorted_best_first() {
+        let game = dealt_game(0);
+        let hints = hints(&game);
+
+        assert!(!hints.is_empty());
+        assert!(hints.windows(2).all(|pair| pair[0].score >= pair[1].score));
+    }
+
+    #[test]
+    fn hints_should_include_draw() {
+        let game = dealt_game(0);
+
+        assert!(hints(&game)
+            .iter()
+            .any(|hint| hint.reason == HintReason::Draw
+                && hint.action == table::KlondikeTableAction::Draw(3)));
+    }
+
+    #[test]
+    fn hints_should_be_empty_before_start() {
+        let game = game::KlondikeGame::new(
+            dealer::KlondikeDealer,
+            rules::KlondikeRules,
+            Default::default(),
+            deck::SeededShuffle::new(0),
+        );
+
+        assert_eq!(best_hint(&game), None);
+    }
+
+    #[test_case(REVEAL_CARD, 1 => (
+        table::KlondikeTableAction::Reveal(table::KlondikePileId::Tableaux(0)),
+        HintReason::RevealCard
+    ); "reveal card")]
+    #[test_case(TO_FOUNDATION, 1 => (
+        table::KlondikeTableAction::Move(
+            table::KlondikePileId::Waste,
+            table::KlondikePileId::Foundation(Suit::Spades),
+            1
+        ),
+        HintReason::ToFoundation
+    ); "to foundation")]
+    #[test_case(EXPOSE_FACE_DOWN, 2 => (
+        table::KlondikeTableAction::Move(
+            table::KlondikePileId::Tableaux(0),
+            table::KlondikePileId::Tableaux(1),
+            1
+        ),
+        HintReason::ExposeFaceDown
+    ); "expose face down")]
+    #[test_case(EMPTY_COLUMN, 2 => (
+        table::KlondikeTableAction::Move(
+            table::KlondikePileId::Tableaux(0),
+            table::KlondikePileId::Tableaux(1),
+            1
+        ),
+        HintReason::EmptyColumn
+    ); "empty column")]
+    #[test_case(KING_TO_EMPTY, 1 => (
+        table::KlondikeTableAction::Move(
+            table::KlondikePileId::Waste,
+            table::KlondikePileId::Tableaux(0),
+            1
+        ),
+        HintReason::KingToEmpty
+    ); "king to empty")]
+    #[test_case(FROM_WASTE, 1 => (
+        table::KlondikeTableAction::Move(
+            table::KlondikePileId::Waste,
+            table::KlondikePileId::Tableaux(0),
+            1
+        ),
+        HintReason::FromWaste
+    ); "from waste")]
+    #[test_case(UNBLOCK_FOUNDATION, 2 => (
+        table::KlondikeTableAction::Move(
+            table::KlondikePileId::Tableaux(0),
+            table::KlondikePileId::Tableaux(1),
+            1
+        ),
+        HintReason::UnblockFoundation
+    ); "unblock foundation")]
+    #[test_case(DRAW, 1 => (table::KlondikeTableAction::Draw(3), HintReason::Draw); "draw")]
+    #[test_case(FROM_FOUNDATION, 1 => (
+        table::KlondikeTableAction::Move(
+            table::KlondikePileId::Foundation(Suit::Hearts),
+            table::KlondikePileId::Tableaux(0),
+            1
+        ),
+        HintReason::FromFoundation
+    ); "from foundation")]
+    #[test_case(POINTLESS, 2 => (
+        table::KlondikeTableAction::Move(
+            table::KlondikePileId::Tableaux(0),
+            table::KlondikePileId::Tableaux(1),
+            1
+        ),
+        HintReason::Pointless
+    ); "pointless")]
+    fn best_hint_for(
+        text: &str,
+        tableaux_width: usize,
+    ) -> (table::KlondikeTableAction, HintReason) {
+        let table = text::parse(text).unwrap();
+        let settings = settings::KlondikeSettings {
+            tableaux_width,
+            max_redeals: Some(0),
+            ..Default::default()
+        };
+
+        let hint = hints_for_table(&table, &settings, true)[0];
+        (hint.action, hint.reason)
+    }
+}
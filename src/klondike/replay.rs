@@ -0,0 +1,393 @@
+//! Recording and replaying whole sessions. A replay starts from a saved game (see `save`), so it
+//! knows the initial deal either by its deal number or by the order of the cards, followed by
+//! every action taken in order. Every so often the recorder also stores the table as it was after
+//! an action, so that replaying can check it ends up in the same place.
+//!
+//! Clearing an unseeded game deals cards that can't be reproduced, so a replay of such a session
+//! will diverge at the first checkpoint after the clear.
+
+use std::{io, time};
+
+use itertools::Itertools as _;
+use snafu::{ResultExt as _, Snafu};
+
+use crate::klondike::{game, save, table};
+use crate::model;
+use crate::model::action::Actionable as _;
+use crate::model::table::Table as _;
+
+/// The version of the replay format written by this release.
+pub const CURRENT_VERSION: u64 = 1;
+/// How many steps are recorded between checkpoints by default.
+pub const DEFAULT_CHECKPOINT_INTERVAL: usize = 10;
+
+/// Anything that can happen to a game during a session.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum ReplayAction {
+    Game(model::game::GameAction),
+    Table(table::KlondikeTableAction),
+    Deal,
+    Undo,
+    Redo,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct ReplayStep {
+    /// Milliseconds since recording began.
+    pub elapsed_ms: u64,
+    pub action: ReplayAction,
+    /// The table after the action was applied, if this step is a checkpoint.
+    pub checkpoint: Option<table::KlondikeTable>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct Replay {
+    version: u64,
+    initial: serde_json::Value,
+    steps: Vec<ReplayStep>,
+}
+
+#[derive(Debug)]
+pub struct ReplayRecorder<SH> {
+    game: game::KlondikeGame<SH>,
+    initial: serde_json::Value,
+    started_at: time::Instant,
+    steps: Vec<ReplayStep>,
+    checkpoint_interval: usize,
+}
+
+/// Steps through a replay, checking the table against each checkpoint along the way.
+#[derive(Debug)]
+pub struct ReplayPlayer {
+    game: game::KlondikeGame<model::deck::NumberedShuffle>,
+    steps: Vec<ReplayStep>,
+    position: usize,
+}
+
+#[derive(Debug, Snafu)]
+pub enum ReplayActionError {
+    #[snafu(display("{}", source))]
+    Game { source: game::KlondikeGameError },
+    #[snafu(display("{}", source))]
+    History {
+        source: game::KlondikeGameHistoryError,
+    },
+}
+
+#[derive(Debug, Snafu)]
+pub enum ReplayError {
+    #[snafu(display("Unable to record the initial game: {}", source))]
+    Record { source: save::SaveError },
+    #[snafu(display("Unable to write replay: {}", source))]
+    Write { source: serde_json::Error },
+    #[snafu(display("Replay is not valid: {}", source))]
+    Parse { source: serde_json::Error },
+    #[snafu(display(
+        "Replay has version {}, but only version {} is supported",
+        version,
+        CURRENT_VERSION
+    ))]
+    UnsupportedVersion { version: u64 },
+    #[snafu(display("Replay has an invalid initial game: {}", source))]
+    Initial { source: save::LoadError },
+    #[snafu(display("Replay failed at step {}: {}", step, source))]
+    StepFailed {
+        step: usize,
+        source: ReplayActionError,
+    },
+    #[snafu(display(
+        "Replay diverged at step {}: the {} differ",
+        step,
+        piles.iter().join(", the ")
+    ))]
+    Diverged {
+        step: usize,
+        piles: Vec<table::KlondikePileId>,
+    },
+}
+
+impl<SH> model::action::Action<game::KlondikeGame<SH>> for ReplayAction
+where
+    SH: model::deck::Shuffle,
+{
+    type Error = ReplayActionError;
+
+    fn apply_to(self, target: &mut game::KlondikeGame<SH>) -> Result<(), Self::Error> {
+        match self {
+            ReplayAction::Game(action) => {
+                target.apply(action).unwrap_or_else(|never| match never {})
+            }
+            ReplayAction::Table(action) => target
+                .apply(model::game::TableAction(action))
+                .context(Game)?,
+            ReplayAction::Deal => target.apply(model::game::DealAction).context(Game)?,
+            ReplayAction::Undo => target.undo().context(History)?,
+            ReplayAction::Redo => target.redo().context(History)?,
+        }
+
+        Ok(())
+    }
+}
+
+impl Replay {
+    pub fn steps(&self) -> &[ReplayStep] {
+        &self.steps
+    }
+
+    pub fn write<W>(&self, writer: W) -> Result<(), ReplayError>
+    where
+        W: io::Write,
+    {
+        serde_json::to_writer(writer, self).context(Write)
+    }
+
+    pub fn read<R>(reader: R) -> Result<Self, ReplayError>
+    where
+        R: io::Read,
+    {
+        let replay: Self = serde_json::from_reader(reader).context(Parse)?;
+
+        snafu::ensure!(
+            replay.version == CURRENT_VERSION,
+            UnsupportedVersion {
+                version: replay.version
+            }
+        );
+
+        Ok(replay)
+    }
+}
+
+impl<SH> ReplayRecorder<SH>
+where
+    SH: model::deck::Shuffle,
+{
+    /// Start recording from the game's current state.
+    pub fn new(game: game::KlondikeGame<SH>) -> Result<Self, ReplayError> {
+        let initial = save::to_value(&game).context(Record)?;
+
+        Ok(Self {
+            game,
+            initial,
+            started_at: time::Instant::now(),
+            steps: Vec::new(),
+            checkpoint_interval: DEFAULT_CHECKPOINT_INTERVAL,
+        })
+    }
+
+    pub fn game(&self) -> &game::KlondikeGame<SH> {
+        &self.game
+    }
+
+    pub fn checkpoint_interval(&self) -> usize {
+        self.checkpoint_interval
+    }
+
+    /// Store a checkpoint every `interval` steps. An interval of zero only checkpoints the final
+    /// step.
+    pub fn set_checkpoint_interval(&mut self, interval: usize) {
+        self.checkpoint_interval = interval;
+    }
+
+    /// Apply the action to the game, recording it if it succeeds.
+    pub fn apply(&mut self, action: ReplayAction) -> Result<(), ReplayActionError> {
+        self.game.apply(action)?;
+
+        let step = self.steps.len() + 1;
+        let checkpoint = if step.is_multiple_of(self.checkpoint_interval) {
+            Some(self.game.table().clone())
+        } else {
+            None
+        };
+
+        self.steps.push(ReplayStep {
+            elapsed_ms: self.started_at.elapsed().as_millis() as u64,
+            action,
+            checkpoint,
+        });
+
+        Ok(())
+    }
+
+    /// Stop recording. The final step is always a checkpoint.
+    pub fn finish(mut self) -> (game::KlondikeGame<SH>, Replay) {
+        if let Some(last_step) = self.steps.last_mut() {
+            last_step
+                .checkpoint
+                .get_or_insert_with(|| self.game.table().clone());
+        }
+
+        let replay = Replay {
+            version: CURRENT_VERSION,
+            initial: self.initial,
+            steps: self.steps,
+        };
+
+        (self.game, replay)
+    }
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> Result<Self, ReplayError> {
+        let game = save::from_value(replay.initial).context(Initial)?;
+
+        Ok(Self {
+            game,
+            steps: replay.steps,
+            position: 0,
+        })
+    }
+
+    pub fn game(&self) -> &game::KlondikeGame<model::deck::NumberedShuffle> {
+        &self.game
+    }
+
+    /// The index of the next step to apply.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position == self.steps.len()
+    }
+
+    /// Apply the next step, returning it, or `None` if the replay is finished.
+    pub fn step(&mut self) -> Result<Option<&ReplayStep>, ReplayError> {
+        let step = self.position;
+        let replay_step = match self.steps.get(step) {
+            Some(replay_step) => replay_step,
+            None => return Ok(None),
+        };
+
+        self.game
+            .apply_all(Some(replay_step.action))
+            .context(StepFailed { step })?;
+
+        if let Some(checkpoint) = &replay_step.checkpoint {
+            let table = self.game.table();
+            let piles = table::KlondikePileId::full_iter(self.game.settings().tableaux_width)
+                .filter(|&pile_id| table.pile(pile_id) != checkpoint.pile(pile_id))
+                .collect::<Vec<_>>();
+
+            snafu::ensure!(piles.is_empty(), Diverged { step, piles });
+        }
+
+        self.position += 1;
+        Ok(Some(replay_step))
+    }
+
+    /// Apply all the remaining steps.
+    pub fn run(&mut self) -> Result<(), ReplayError> {
+        while self.step()?.is_some() {}
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+    use crate::klondike::{dealer, rules};
+    use crate::model::game::GameAction;
+
+    fn recorded_session_with<SH>(shuffle: SH) -> (game::KlondikeGame<SH>, Replay)
+    where
+        SH: model::deck::Shuffle,
+    {
+        let game = game::KlondikeGame::new(
+            dealer::KlondikeDealer,
+            rules::KlondikeRules,
+            Default::default(),
+            shuffle,
+        );
+        let mut recorder = ReplayRecorder::new(game).unwrap();
+        recorder.set_checkpoint_interval(5);
+
+        while !recorder.game().is_dealt() {
+            recorder.apply(ReplayAction::Deal).unwrap();
+        }
+        recorder
+            .apply(ReplayAction::Game(GameAction::Start))
+            .unwrap();
+        for action in [
+            ReplayAction::Table(table::KlondikeTableAction::Draw(3)),
+            ReplayAction::Table(table::KlondikeTableAction::Draw(3)),
+            ReplayAction::Undo,
+            ReplayAction::Redo,
+            ReplayAction::Game(GameAction::Clear),
+        ] {
+            recorder.apply(action).unwrap();
+        }
+        while !recorder.game().is_dealt() {
+            recorder.apply(ReplayAction::Deal).unwrap();
+        }
+
+        recorder.finish()
+    }
+
+    fn recorded_session() -> (game::KlondikeGame<model::deck::SeededShuffle>, Replay) {
+        recorded_session_with(model::deck::SeededShuffle::new(11982))
+    }
+
+    fn round_trip(replay: &Replay) -> Replay {
+        let mut buffer = Vec::new();
+        replay.write(&mut buffer).unwrap();
+        Replay::read(buffer.as_slice()).unwrap()
+    }
+
+    #[test]
+    fn replay_should_reach_recorded_table() {
+        let (game, replay) = recorded_session();
+        let mut player = ReplayPlayer::new(round_trip(&replay)).unwrap();
+
+        player.run().unwrap();
+
+        assert!(player.is_finished());
+        assert_eq!(player.game().table(), game.table());
+        assert_eq!(player.game().seed(), game.seed());
+    }
+
+    #[test]
+    fn replay_should_deal_again_from_microsoft_numbering() {
+        let (game, replay) = recorded_session_with(model::deck::MicrosoftShuffle::new(11982));
+        let mut player = ReplayPlayer::new(round_trip(&replay)).unwrap();
+
+        player.run().unwrap();
+
+        assert_eq!(player.game().table(), game.table());
+        assert_eq!(
+            player.game().deal_number(),
+            Some(model::deck::DealNumber::Microsoft(11983))
+        );
+    }
+
+    #[test]
+    fn replay_should_report_divergence() {
+        let (_, replay) = recorded_session();
+        let step = replay.steps.len() - 1;
+        let mut document = serde_json::to_value(&replay).unwrap();
+        document["steps"][step]["checkpoint"]["stock"] = serde_json::json!([]);
+        let replay: Replay = serde_json::from_value(document).unwrap();
+
+        let mut player = ReplayPlayer::new(replay).unwrap();
+
+        assert_matches!(
+            player.run(),
+            Err(ReplayError::Diverged { step: diverged_step, piles }) => {
+                assert_eq!(diverged_step, step);
+                assert_eq!(piles, vec![table::KlondikePileId::Stock]);
+            }
+        );
+    }
+
+    #[test]
+    fn replay_should_report_failed_step() {
+        let (_, mut replay) = recorded_session();
+        replay.steps[0].action = ReplayAction::Table(table::KlondikeTableAction::Draw(3));
+
+        let mut player = ReplayPlayer::new(replay).unwrap();
+
+        assert_matches!(player.run(), Err(ReplayError::StepFailed { step: 0, .. }));
+    }
+}
@@ -1,37 +1,701 @@
-use std::convert;
+use std::iter;
+
+use enum_like::EnumValues as _;
+use snafu::Snafu;
 
 use crate::klondike::{game, settings, table};
 use crate::model;
+use crate::model::action::Actionable as _;
+use crate::model::rules::Rules as _;
+use crate::model::table::Table as _;
 
 #[derive(Debug, Clone, Default)]
 pub struct KlondikeRules;
 
 #[derive(Debug, Clone, Copy)]
 pub struct KlondikeRulesContext<'a> {
-    _settings: &'a settings::KlondikeSettings,
-    _started: bool,
-    _table: &'a table::KlondikeTable,
+    settings: &'a settings::KlondikeSettings,
+    started: bool,
+    table: &'a table::KlondikeTable,
+}
+
+impl<'a> KlondikeRulesContext<'a> {
+    pub fn new(
+        settings: &'a settings::KlondikeSettings,
+        started: bool,
+        table: &'a table::KlondikeTable,
+    ) -> Self {
+        Self {
+            settings,
+            started,
+            table,
+        }
+    }
 }
 
 impl<'a> From<game::KlondikeGameRulesContext<'a>> for KlondikeRulesContext<'a> {
     fn from(context: game::KlondikeGameRulesContext<'a>) -> Self {
         Self {
-            _settings: context.settings,
-            _started: context.started,
-            _table: context.table,
+            settings: context.settings,
+            started: context.started,
+            table: context.table,
         }
     }
 }
 
+#[derive(Debug, Copy, Clone, Eq, PartialEq, derive_more::Display)]
+pub enum TableauxMismatchType {
+    #[display(fmt = "only a King may start a tableaux")]
+    Start,
+    #[display(fmt = "it does not follow the {}", _0)]
+    Follow(model::card::CardFace),
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, derive_more::Display)]
+pub enum FoundationMismatchType {
+    #[display(fmt = "only the Ace of {} may start the foundation", _0)]
+    Start(model::card::Suit),
+    #[display(fmt = "it does not follow the {}", _0)]
+    Follow(model::card::CardFace),
+}
+
+#[derive(Debug, Snafu)]
+pub enum KlondikeRulesError {
+    #[snafu(display("The game has already started"))]
+    GameAlreadyStarted,
+    #[snafu(display("The game has not started yet"))]
+    GameNotStarted,
+    #[snafu(display("There is no tableaux {}", index + 1))]
+    PileOutOfBounds { index: usize },
+    #[snafu(display("Cannot deal to the {}", pile_id))]
+    IllegalDealTarget { pile_id: table::KlondikePileId },
+    #[snafu(display("Cannot deal onto a card that is {}", facing))]
+    IllegalDealTargetFacing { facing: model::card::Facing },
+    #[snafu(display("Cannot draw zero cards"))]
+    EmptyDraw,
+    #[snafu(display("Must draw {} cards at a time, not {}", expected, count))]
+    DrawCountMismatch { count: usize, expected: usize },
+    #[snafu(display("There are no cards left to draw"))]
+    NothingToDraw,
+    #[snafu(display("The waste may only be turned over {} times", max_redeals))]
+    RedealLimitReached { max_redeals: usize },
+    #[snafu(display("Cannot move zero cards"))]
+    EmptyMove,
+    #[snafu(display("The {} does not have {} cards", pile_id, count))]
+    InsufficientCards {
+        pile_id: table::KlondikePileId,
+        count: usize,
+    },
+    #[snafu(display("Cannot move a card that is {}", facing))]
+    IllegalMoveSourceFacing { facing: model::card::Facing },
+    #[snafu(display("Moving cards out of a foundation is not allowed"))]
+    IllegalMoveFromFoundation,
+    #[snafu(display("May only take a single card from the {}", pile_id))]
+    MayOnlyTakeSingleCard { pile_id: table::KlondikePileId },
+    #[snafu(display("Cannot move cards from the {}", pile_id))]
+    IllegalMoveSource { pile_id: table::KlondikePileId },
+    #[snafu(display("Cannot move onto a card that is {}", facing))]
+    IllegalMoveTargetFacing { facing: model::card::Facing },
+    #[snafu(display("Cannot place the {} on the tableaux: {}", card, mismatch))]
+    TableauxMismatch {
+        card: model::card::CardFace,
+        mismatch: TableauxMismatchType,
+    },
+    #[snafu(display("The {} may only accept a single card", pile_id))]
+    MayOnlyAcceptSingleCard { pile_id: table::KlondikePileId },
+    #[snafu(display("Cannot place the {} on the foundation: {}", card, mismatch))]
+    FoundationMismatch {
+        card: model::card::CardFace,
+        mismatch: FoundationMismatchType,
+    },
+    #[snafu(display("Cannot move cards to the {}", pile_id))]
+    IllegalMoveTarget { pile_id: table::KlondikePileId },
+    #[snafu(display("Cannot reveal a card in the {}", pile_id))]
+    IllegalRevealTarget { pile_id: table::KlondikePileId },
+    #[snafu(display("The {} has no card to reveal", pile_id))]
+    EmptyReveal { pile_id: table::KlondikePileId },
+    #[snafu(display("Cannot reveal a card that is {}", facing))]
+    IllegalRevealTargetFacing { facing: model::card::Facing },
+}
+
 impl model::rules::Rules<table::KlondikeTableAction> for KlondikeRules {
     type Context<'a> = KlondikeRulesContext<'a>;
-    type Error = convert::Infallible;
+    type Error = KlondikeRulesError;
 
     fn validate(
         &self,
-        _action: &table::KlondikeTableAction,
-        _context: &Self::Context<'_>,
+        action: &table::KlondikeTableAction,
+        context: &Self::Context<'_>,
     ) -> Result<(), Self::Error> {
-        todo!()
+        match *action {
+            table::KlondikeTableAction::Deal(target_pile_id) => {
+                self.validate_deal(target_pile_id, context)
+            }
+            table::KlondikeTableAction::Draw(count) => self.validate_draw(count, context),
+            table::KlondikeTableAction::Move(source_pile_id, target_pile_id, count) => {
+                self.validate_move(source_pile_id, target_pile_id, count, context)
+            }
+            table::KlondikeTableAction::Reveal(target_pile_id) => {
+                self.validate_reveal(target_pile_id, context)
+            }
+        }
+    }
+
+    fn is_stuck(&self, context: &Self::Context<'_>) -> bool {
+        context.started && !self.can_move(context)
+    }
+}
+
+impl KlondikeRules {
+    /// List every action a player could legally take in this context. Dealing is the dealer's job,
+    /// so `Deal` actions are never included.
+    pub fn legal_actions(
+        &self,
+        context: &KlondikeRulesContext<'_>,
+    ) -> Vec<table::KlondikeTableAction> {
+        let tableaux_width = context.settings.tableaux_width;
+        let tableaux_ids = (0..tableaux_width).map(table::KlondikePileId::Tableaux);
+        let foundation_ids = model::card::Suit::values().map(table::KlondikePileId::Foundation);
+
+        let draws = iter::once(table::KlondikeTableAction::Draw(
+            context.settings.draw_count,
+        ));
+        let reveals = tableaux_ids.clone().map(table::KlondikeTableAction::Reveal);
+
+        let source_ids = velcro::iter![
+            table::KlondikePileId::Waste,
+            ..foundation_ids.clone(),
+            ..tableaux_ids.clone(),
+        ];
+        let moves = source_ids.flat_map(|source_pile_id| {
+            let max_count = context.table.movable_cards(source_pile_id).len();
+            let target_ids = velcro::iter![..foundation_ids.clone(), ..tableaux_ids.clone()];
+
+            target_ids
+                .filter(move |&target_pile_id| target_pile_id != source_pile_id)
+                .flat_map(move |target_pile_id| {
+                    (1..=max_count).map(move |count| {
+                        table::KlondikeTableAction::Move(source_pile_id, target_pile_id, count)
+                    })
+                })
+        });
+
+        velcro::iter![..draws, ..reveals, ..moves]
+            .filter(|action| self.validate(action, context).is_ok())
+            .collect()
+    }
+
+    /// Whether any action other than a draw is legal, either now or at some point in the cycle of
+    /// positions that drawing alone goes through. Drawing just turns the same cards over and over,
+    /// so once it's the only thing left to do, the game is going nowhere.
+    fn can_move(&self, context: &KlondikeRulesContext<'_>) -> bool {
+        let draw = table::KlondikeTableAction::Draw(context.settings.draw_count);
+        let mut table = context.table.clone();
+        let mut visited = Vec::new();
+
+        loop {
+            let context = KlondikeRulesContext::new(context.settings, true, &table);
+            let actions = self.legal_actions(&context);
+            if actions.iter().any(|&action| action != draw) {
+                return true;
+            }
+            if actions.is_empty() {
+                return false;
+            }
+
+            // The redeal count goes up on every pass, so positions are told apart by their stock
+            // and waste alone.
+            let piles = (
+                table.pile(table::KlondikePileId::Stock).clone(),
+                table.pile(table::KlondikePileId::Waste).clone(),
+            );
+            if visited.contains(&piles) {
+                return false;
+            }
+            visited.push(piles);
+
+            table.apply(draw).unwrap_or_else(|never| match never {});
+        }
+    }
+
+    fn validate_deal(
+        &self,
+        target_pile_id: table::KlondikePileId,
+        context: &KlondikeRulesContext<'_>,
+    ) -> Result<(), KlondikeRulesError> {
+        snafu::ensure!(!context.started, GameAlreadyStarted);
+
+        if let table::KlondikePileId::Tableaux(index) = target_pile_id {
+            self.validate_tableaux_index(index, context)?;
+
+            if let Some(top_card) = context.table.pile(target_pile_id).top_card() {
+                snafu::ensure!(
+                    top_card.is_face_down(),
+                    IllegalDealTargetFacing {
+                        facing: top_card.facing
+                    }
+                );
+            }
+
+            Ok(())
+        } else {
+            IllegalDealTarget {
+                pile_id: target_pile_id,
+            }
+            .fail()
+        }
+    }
+
+    fn validate_draw(
+        &self,
+        count: usize,
+        context: &KlondikeRulesContext<'_>,
+    ) -> Result<(), KlondikeRulesError> {
+        snafu::ensure!(context.started, GameNotStarted);
+        snafu::ensure!(count > 0, EmptyDraw);
+        snafu::ensure!(
+            count == context.settings.draw_count,
+            DrawCountMismatch {
+                count,
+                expected: context.settings.draw_count
+            }
+        );
+
+        let stock = context.table.pile(table::KlondikePileId::Stock);
+        let waste = context.table.pile(table::KlondikePileId::Waste);
+        snafu::ensure!(!(stock.is_empty() && waste.is_empty()), NothingToDraw);
+
+        if let Some(max_redeals) = context.settings.redeal_limit() {
+            snafu::ensure!(
+                !stock.is_empty() || context.table.redeals() < max_redeals,
+                RedealLimitReached { max_redeals }
+            );
+        }
+
+        Ok(())
+    }
+
+    fn validate_move(
+        &self,
+        source_pile_id: table::KlondikePileId,
+        target_pile_id: table::KlondikePileId,
+        count: usize,
+        context: &KlondikeRulesContext<'_>,
+    ) -> Result<(), KlondikeRulesError> {
+        snafu::ensure!(context.started, GameNotStarted);
+        snafu::ensure!(count > 0, EmptyMove);
+
+        match source_pile_id {
+            table::KlondikePileId::Tableaux(index) => {
+                self.validate_tableaux_index(index, context)?;
+            }
+            table::KlondikePileId::Foundation(_) => {
+                snafu::ensure!(
+                    context.settings.allow_move_from_foundation,
+                    IllegalMoveFromFoundation
+                );
+                snafu::ensure!(
+                    count == 1,
+                    MayOnlyTakeSingleCard {
+                        pile_id: source_pile_id
+                    }
+                );
+            }
+            table::KlondikePileId::Waste => {
+                snafu::ensure!(
+                    count == 1,
+                    MayOnlyTakeSingleCard {
+                        pile_id: source_pile_id
+                    }
+                );
+            }
+            table::KlondikePileId::Stock => {
+                return IllegalMoveSource {
+                    pile_id: source_pile_id,
+                }
+                .fail();
+            }
+        }
+
+        let source_top_cards = context.table.pile(source_pile_id).top_cards(count);
+        snafu::ensure!(
+            count == source_top_cards.len(),
+            InsufficientCards {
+                pile_id: source_pile_id,
+                count
+            }
+        );
+
+        // The face-up cards always sit on top of any face-down cards, so it's sufficient to check
+        // the facing of the bottom card being moved.
+        let source_card = &source_top_cards[0];
+        snafu::ensure!(
+            source_card.is_face_up(),
+            IllegalMoveSourceFacing {
+                facing: source_card.facing
+            }
+        );
+
+        match target_pile_id {
+            table::KlondikePileId::Tableaux(index) => {
+                self.validate_tableaux_index(index, context)?;
+
+                if let Some(target_top_card) = context.table.pile(target_pile_id).top_card() {
+                    snafu::ensure!(
+                        target_top_card.is_face_up(),
+                        IllegalMoveTargetFacing {
+                            facing: target_top_card.facing
+                        }
+                    );
+                    snafu::ensure!(
+                        target_top_card.rank().follows(source_card.rank())
+                            && target_top_card.color() != source_card.color(),
+                        TableauxMismatch {
+                            card: source_card.face,
+                            mismatch: TableauxMismatchType::Follow(target_top_card.face)
+                        }
+                    );
+                } else {
+                    snafu::ensure!(
+                        source_card.is_king(),
+                        TableauxMismatch {
+                            card: source_card.face,
+                            mismatch: TableauxMismatchType::Start
+                        }
+                    );
+                }
+            }
+            table::KlondikePileId::Foundation(suit) => {
+                snafu::ensure!(
+                    count == 1,
+                    MayOnlyAcceptSingleCard {
+                        pile_id: target_pile_id
+                    }
+                );
+
+                if let Some(target_top_card) = context.table.pile(target_pile_id).top_card() {
+                    snafu::ensure!(
+                        source_card.suit() == suit
+                            && source_card.rank().follows(target_top_card.rank()),
+                        FoundationMismatch {
+                            card: source_card.face,
+                            mismatch: FoundationMismatchType::Follow(target_top_card.face)
+                        }
+                    );
+                } else {
+                    snafu::ensure!(
+                        source_card.suit() == suit && source_card.is_ace(),
+                        FoundationMismatch {
+                            card: source_card.face,
+                            mismatch: FoundationMismatchType::Start(suit)
+                        }
+                    );
+                }
+            }
+            _ => {
+                return IllegalMoveTarget {
+                    pile_id: target_pile_id,
+                }
+                .fail();
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_reveal(
+        &self,
+        target_pile_id: table::KlondikePileId,
+        context: &KlondikeRulesContext<'_>,
+    ) -> Result<(), KlondikeRulesError> {
+        if let table::KlondikePileId::Tableaux(index) = target_pile_id {
+            self.validate_tableaux_index(index, context)?;
+
+            let top_card = context.table.pile(target_pile_id).top_card().ok_or(
+                KlondikeRulesError::EmptyReveal {
+                    pile_id: target_pile_id,
+                },
+            )?;
+            snafu::ensure!(
+                top_card.is_face_down(),
+                IllegalRevealTargetFacing {
+                    facing: top_card.facing
+                }
+            );
+
+            Ok(())
+        } else {
+            IllegalRevealTarget {
+                pile_id: target_pile_id,
+            }
+            .fail()
+        }
+    }
+
+    fn validate_tableaux_index(
+        &self,
+        index: usize,
+        context: &KlondikeRulesContext<'_>,
+    ) -> Result<(), KlondikeRulesError> {
+        snafu::ensure!(
+            index < context.settings.tableaux_width,
+            PileOutOfBounds { index }
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use itertools::Itertools as _;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::model::card::{Rank, Suit};
+    use crate::test_support::{table_with_tableaux, validate_started};
+
+    #[test_case(Rank::Ace, Rank::Two, &[], false => true; "ace buried")]
+    #[test_case(Rank::Two, Rank::Ace, &[], false => false; "ace on top")]
+    #[test_case(Rank::Ace, Rank::Three, &[Rank::Two], false => true; "only draws left")]
+    #[test_case(Rank::Ace, Rank::Two, &[], true => false; "foundation take-backs left")]
+    fn is_stuck(bottom: Rank, top: Rank, stock: &[Rank], allow_move_from_foundation: bool) -> bool {
+        let foundation = |suit| {
+            model::pile::Pile::new_with_cards(
+                Rank::values().map(|rank| model::card::CardFace::face_up(rank.of(suit))),
+            )
+        };
+        let stock = stock
+            .iter()
+            .map(|rank| rank.of(Suit::Spades).face_down())
+            .collect_vec();
+        let table: table::KlondikeTable = serde_json::from_value(serde_json::json!({
+            "stock": stock,
+            "waste": [],
+            "spades_foundation": [],
+            "hearts_foundation": foundation(Suit::Hearts),
+            "diamonds_foundation": foundation(Suit::Diamonds),
+            "clubs_foundation": foundation(Suit::Clubs),
+            "tableaux": [[bottom.of(Suit::Spades).face_down(), top.of(Suit::Spades).face_up()]],
+        }))
+        .unwrap();
+
+        let settings = settings::KlondikeSettings {
+            allow_move_from_foundation,
+            ..Default::default()
+        };
+        let context = KlondikeRulesContext::new(&settings, true, &table);
+        KlondikeRules.is_stuck(&context)
+    }
+
+    #[test_case(Rank::Six.of(Suit::Hearts), Rank::Seven.of(Suit::Spades) => true; "red on black")]
+    #[test_case(Rank::Six.of(Suit::Clubs), Rank::Seven.of(Suit::Diamonds) => true; "black on red")]
+    #[test_case(Rank::Six.of(Suit::Spades), Rank::Seven.of(Suit::Clubs) => false; "same color")]
+    #[test_case(Rank::Five.of(Suit::Hearts), Rank::Seven.of(Suit::Spades) => false; "skipped rank")]
+    #[test_case(Rank::Eight.of(Suit::Hearts), Rank::Seven.of(Suit::Spades) => false; "ascending")]
+    fn tableaux_build(card: model::card::CardFace, onto: model::card::CardFace) -> bool {
+        let table = table_with_tableaux(&[&[card], &[onto]]);
+        let action = table::KlondikeTableAction::Move(
+            table::KlondikePileId::Tableaux(0),
+            table::KlondikePileId::Tableaux(1),
+            1,
+        );
+        validate_started(&KlondikeRules, &Default::default(), &table, &action).is_ok()
+    }
+
+    #[test_case(Rank::King.of(Suit::Hearts) => true; "king")]
+    #[test_case(Rank::Queen.of(Suit::Hearts) => false; "queen")]
+    fn empty_tableaux_accepts_only_kings(card: model::card::CardFace) -> bool {
+        let table = table_with_tableaux(&[&[card]]);
+        let action = table::KlondikeTableAction::Move(
+            table::KlondikePileId::Tableaux(0),
+            table::KlondikePileId::Tableaux(1),
+            1,
+        );
+        validate_started(&KlondikeRules, &Default::default(), &table, &action).is_ok()
+    }
+
+    #[test_case(&[], Rank::Ace.of(Suit::Hearts), Suit::Hearts => true; "ace starts")]
+    #[test_case(&[], Rank::Ace.of(Suit::Spades), Suit::Hearts => false; "ace of wrong suit")]
+    #[test_case(&[], Rank::Two.of(Suit::Hearts), Suit::Hearts => false; "two cannot start")]
+    #[test_case(&[Rank::Ace.of(Suit::Hearts)], Rank::Two.of(Suit::Hearts), Suit::Hearts => true; "two follows ace")]
+    #[test_case(&[Rank::Ace.of(Suit::Hearts)], Rank::Three.of(Suit::Hearts), Suit::Hearts => false; "three skips two")]
+    fn foundation_build(
+        foundation: &[model::card::CardFace],
+        card: model::card::CardFace,
+        suit: Suit,
+    ) -> bool {
+        let settings = Default::default();
+        let mut table = table_with_tableaux(&[foundation, &[card]]);
+        for _ in foundation {
+            table
+                .apply(table::KlondikeTableAction::Move(
+                    table::KlondikePileId::Tableaux(0),
+                    table::KlondikePileId::Foundation(suit),
+                    1,
+                ))
+                .unwrap();
+        }
+
+        let action = table::KlondikeTableAction::Move(
+            table::KlondikePileId::Tableaux(1),
+            table::KlondikePileId::Foundation(suit),
+            1,
+        );
+        validate_started(&KlondikeRules, &settings, &table, &action).is_ok()
+    }
+
+    #[test]
+    fn move_from_foundation_should_honor_settings() {
+        let mut table = table_with_tableaux(&[&[Rank::Ace.of(Suit::Spades)]]);
+        table
+            .apply(table::KlondikeTableAction::Move(
+                table::KlondikePileId::Tableaux(0),
+                table::KlondikePileId::Foundation(Suit::Spades),
+                1,
+            ))
+            .unwrap();
+
+        let action = table::KlondikeTableAction::Move(
+            table::KlondikePileId::Foundation(Suit::Spades),
+            table::KlondikePileId::Tableaux(0),
+            1,
+        );
+        let settings = settings::KlondikeSettings {
+            allow_move_from_foundation: false,
+            ..Default::default()
+        };
+
+        assert_matches!(
+            validate_started(&KlondikeRules, &settings, &table, &action),
+            Err(KlondikeRulesError::IllegalMoveFromFoundation)
+        );
+    }
+
+    #[test]
+    fn tableaux_out_of_range_should_be_rejected() {
+        let table = table_with_tableaux(&[&[Rank::King.of(Suit::Spades)]]);
+        let action = table::KlondikeTableAction::Move(
+            table::KlondikePileId::Tableaux(0),
+            table::KlondikePileId::Tableaux(7),
+            1,
+        );
+
+        assert_matches!(
+            validate_started(&KlondikeRules, &Default::default(), &table, &action),
+            Err(KlondikeRulesError::PileOutOfBounds { index: 7 })
+        );
+    }
+
+    #[test]
+    fn face_down_cards_should_not_move() {
+        let mut table = table_with_tableaux(&[&[]]);
+        table
+            .apply(table::KlondikeTableAction::Deal(
+                table::KlondikePileId::Tableaux(0),
+            ))
+            .unwrap();
+        let action = table::KlondikeTableAction::Move(
+            table::KlondikePileId::Tableaux(0),
+            table::KlondikePileId::Tableaux(1),
+            1,
+        );
+
+        assert_matches!(
+            validate_started(&KlondikeRules, &Default::default(), &table, &action),
+            Err(KlondikeRulesError::IllegalMoveSourceFacing { .. })
+        );
+    }
+
+    #[test]
+    fn reveal_should_require_face_down_top_card() {
+        let table = table_with_tableaux(&[&[Rank::King.of(Suit::Spades)]]);
+        let action = table::KlondikeTableAction::Reveal(table::KlondikePileId::Tableaux(0));
+
+        assert_matches!(
+            validate_started(&KlondikeRules, &Default::default(), &table, &action),
+            Err(KlondikeRulesError::IllegalRevealTargetFacing { .. })
+        );
+    }
+
+    #[test]
+    fn deal_should_be_rejected_after_start() {
+        let table = table_with_tableaux(&[]);
+        let action = table::KlondikeTableAction::Deal(table::KlondikePileId::Tableaux(0));
+
+        assert_matches!(
+            validate_started(&KlondikeRules, &Default::default(), &table, &action),
+            Err(KlondikeRulesError::GameAlreadyStarted)
+        );
+    }
+
+    #[test_case(1, 1 => true; "draw one")]
+    #[test_case(3, 3 => true; "draw three")]
+    #[test_case(3, 1 => false; "draw one in draw three")]
+    #[test_case(1, 3 => false; "draw three in draw one")]
+    fn draw_should_match_settings(draw_count: usize, count: usize) -> bool {
+        let table = table_with_tableaux(&[]);
+        let settings = settings::KlondikeSettings {
+            draw_count,
+            ..Default::default()
+        };
+
+        validate_started(
+            &KlondikeRules,
+            &settings,
+            &table,
+            &table::KlondikeTableAction::Draw(count),
+        )
+        .is_ok()
+    }
+
+    #[test]
+    fn only_top_waste_card_should_be_playable() {
+        let mut table = table_with_tableaux(&[]);
+        table.apply(table::KlondikeTableAction::Draw(3)).unwrap();
+
+        assert_eq!(
+            table.movable_cards(table::KlondikePileId::Waste),
+            table.pile(table::KlondikePileId::Waste).top_cards(1)
+        );
+        assert!(KlondikeRules
+            .legal_actions(&KlondikeRulesContext::new(
+                &Default::default(),
+                true,
+                &table
+            ))
+            .iter()
+            .all(|action| !matches!(
+                action,
+                table::KlondikeTableAction::Move(table::KlondikePileId::Waste, _, count) if *count > 1
+            )));
+    }
+
+    #[test]
+    fn redeal_should_honor_limit() {
+        let settings = settings::KlondikeSettings {
+            max_redeals: Some(1),
+            ..Default::default()
+        };
+        let mut table = table_with_tableaux(&[]);
+        let draw = table::KlondikeTableAction::Draw(3);
+        let draw_through_stock = |table: &mut table::KlondikeTable| {
+            while !table.pile(table::KlondikePileId::Stock).is_empty() {
+                table.apply(draw).unwrap();
+            }
+        };
+
+        draw_through_stock(&mut table);
+        assert_matches!(
+            validate_started(&KlondikeRules, &settings, &table, &draw),
+            Ok(())
+        );
+        table.apply(draw).unwrap();
+
+        draw_through_stock(&mut table);
+        assert_matches!(
+            validate_started(&KlondikeRules, &settings, &table, &draw),
+            Err(KlondikeRulesError::RedealLimitReached { max_redeals: 1 })
+        );
     }
 }
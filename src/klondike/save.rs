@@ -0,0 +1,581 @@
+//! Saving and loading Klondike games as JSON. Every saved game records the version of the format
+//! it was written with. When loading, older documents are brought up to date one version at a
+//! time by the functions in `MIGRATIONS` before being read.
+
+use std::{io, time};
+
+use snafu::{OptionExt as _, ResultExt as _, Snafu};
+
+use crate::klondike::{dealer, game, rules, scoring, settings, table};
+use crate::model;
+
+/// The version of the format written by this release.
+pub const CURRENT_VERSION: u64 = 4;
+
+type MigrationFn = fn(serde_json::Value) -> Result<serde_json::Value, LoadError>;
+
+/// `MIGRATIONS[n]` upgrades a version `n + 1` document to version `n + 2`.
+const MIGRATIONS: &[MigrationFn] = &[add_draw_count, add_redeals_and_progress, number_deals];
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+struct SavedGame {
+    version: u64,
+    settings: settings::KlondikeSettings,
+    moves: usize,
+    score: i64,
+    /// Milliseconds spent playing.
+    elapsed_ms: u64,
+    paused: bool,
+    deal: Option<model::deck::DealNumber>,
+    started: bool,
+    dealer: Option<dealer::KlondikeDealerIter>,
+    table: table::KlondikeTable,
+}
+
+#[derive(Debug, Snafu)]
+pub enum SaveError {
+    #[snafu(display("Unable to write saved game: {}", source))]
+    Write { source: serde_json::Error },
+}
+
+#[derive(Debug, Snafu)]
+pub enum LoadError {
+    #[snafu(display("Saved game is not valid JSON: {}", source))]
+    Parse { source: serde_json::Error },
+    #[snafu(display("Saved game does not have a version"))]
+    MissingVersion,
+    #[snafu(display(
+        "Saved game has version {}, but only versions up to {} are supported",
+        version,
+        CURRENT_VERSION
+    ))]
+    UnsupportedVersion { version: u64 },
+    #[snafu(display("Unable to upgrade saved game from version {}: {}", version, message))]
+    Migration { version: u64, message: String },
+    #[snafu(display("Saved game is not a valid version {} game: {}", version, source))]
+    Read {
+        version: u64,
+        source: serde_json::Error,
+    },
+    #[snafu(display("Saved game has an impossible table: {}", source))]
+    InvalidTable { source: table::KlondikeTableError },
+    #[snafu(display("Saved game has an impossible deal: {}", source))]
+    InvalidDeal { source: dealer::KlondikeDealerError },
+    #[snafu(display("Saved game was started before it was dealt"))]
+    StartedBeforeDealt,
+}
+
+pub fn save<SH, W>(game: &game::KlondikeGame<SH>, writer: W) -> Result<(), SaveError>
+where
+    SH: model::deck::Shuffle,
+    W: io::Write,
+{
+    serde_json::to_writer_pretty(writer, &saved_game(game)).context(Write)
+}
+
+pub fn to_string<SH>(game: &game::KlondikeGame<SH>) -> Result<String, SaveError>
+where
+    SH: model::deck::Shuffle,
+{
+    serde_json::to_string_pretty(&saved_game(game)).context(Write)
+}
+
+pub fn load<R>(reader: R) -> Result<game::KlondikeGame<model::deck::NumberedShuffle>, LoadError>
+where
+    R: io::Read,
+{
+    let document = serde_json::from_reader(reader).context(Parse)?;
+    restore(document)
+}
+
+pub fn from_str(
+    input: &str,
+) -> Result<game::KlondikeGame<model::deck::NumberedShuffle>, LoadError> {
+    let document = serde_json::from_str(input).context(Parse)?;
+    restore(document)
+}
+
+/// Save the game as a JSON value, e.g., to embed it in another document.
+pub fn to_value<SH>(game: &game::KlondikeGame<SH>) -> Result<serde_json::Value, SaveError>
+where
+    SH: model::deck::Shuffle,
+{
+    serde_json::to_value(saved_game(game)).context(Write)
+}
+
+/// Load a game from a JSON value produced by `to_value`.
+pub fn from_value(
+    document: serde_json::Value,
+) -> Result<game::KlondikeGame<model::deck::NumberedShuffle>, LoadError> {
+    restore(document)
+}
+
+fn saved_game<SH>(game: &game::KlondikeGame<SH>) -> SavedGame
+where
+    SH: model::deck::Shuffle,
+{
+    let snapshot = game.snapshot();
+
+    SavedGame {
+        version: CURRENT_VERSION,
+        settings: snapshot.settings,
+        moves: snapshot.moves,
+        score: snapshot.score,
+        elapsed_ms: snapshot.elapsed.as_millis() as u64,
+        paused: snapshot.paused,
+        deal: snapshot.deal_number,
+        started: snapshot.started,
+        dealer: snapshot.dealer_iter,
+        table: snapshot.table,
+    }
+}
+
+fn restore(
+    document: serde_json::Value,
+) -> Result<game::KlondikeGame<model::deck::NumberedShuffle>, LoadError> {
+    let document = migrate(document)?;
+    let saved_game: SavedGame = serde_json::from_value(document).context(Read {
+        version: CURRENT_VERSION,
+    })?;
+
+    saved_game
+        .table
+        .validate(saved_game.settings.tableaux_width)
+        .context(InvalidTable)?;
+
+    // A game that hasn't begun dealing has the same layout as one whose dealer is at the start.
+    let dealer_iter = saved_game
+        .dealer
+        .clone()
+        .unwrap_or_else(|| dealer::KlondikeDealerIter::new(saved_game.settings.tableaux_width));
+    dealer_iter
+        .validate(saved_game.settings.tableaux_width, &saved_game.table)
+        .context(InvalidDeal)?;
+    snafu::ensure!(
+        !saved_game.started || dealer_iter.is_finished(),
+        StartedBeforeDealt
+    );
+
+    // Without a deal number we can't continue the original sequence of deals, so start a new one.
+    let shuffle = saved_game
+        .deal
+        .map(model::deck::NumberedShuffle::resume)
+        .unwrap_or_else(model::deck::NumberedShuffle::new_random);
+
+    let snapshot = model::game::GameSnapshot {
+        deal_number: saved_game.deal,
+        dealer_iter: saved_game.dealer,
+        elapsed: time::Duration::from_millis(saved_game.elapsed_ms),
+        moves: saved_game.moves,
+        paused: saved_game.paused,
+        score: saved_game.score,
+        settings: saved_game.settings,
+        started: saved_game.started,
+        table: saved_game.table,
+    };
+
+    Ok(game::KlondikeGame::from_snapshot(
+        dealer::KlondikeDealer,
+        rules::KlondikeRules,
+        shuffle,
+        snapshot,
+    ))
+}
+
+/// Version 1 predates the draw count setting, when every game drew three cards at a time.
+fn add_draw_count(mut document: serde_json::Value) -> Result<serde_json::Value, LoadError> {
+    let settings = document
+        .get_mut("settings")
+        .and_then(serde_json::Value::as_object_mut)
+        .context(Migration {
+            version: 1u64,
+            message: "settings are missing",
+        })?;
+    settings.insert("draw_count".to_owned(), 3.into());
+
+    Ok(document)
+}
+
+/// Version 2 predates the redeal limit, scoring, the clock, and the move count. Games saved then
+/// start with none of them used up, and weren't paused. Some version 2 documents were written with
+/// these fields already, so any that are there are kept.
+fn add_redeals_and_progress(
+    mut document: serde_json::Value,
+) -> Result<serde_json::Value, LoadError> {
+    let default_scoring =
+        serde_json::to_value(scoring::KlondikeScoring::default()).map_err(|error| {
+            LoadError::Migration {
+                version: 2,
+                message: error.to_string(),
+            }
+        })?;
+
+    let settings = document
+        .get_mut("settings")
+        .and_then(serde_json::Value::as_object_mut)
+        .context(Migration {
+            version: 2u64,
+            message: "settings are missing",
+        })?;
+    settings
+        .entry("max_redeals")
+        .or_insert(serde_json::Value::Null);
+    settings.entry("scoring").or_insert(default_scoring);
+    settings.entry("timed").or_insert(false.into());
+
+    let table = document
+        .get_mut("table")
+        .and_then(serde_json::Value::as_object_mut)
+        .context(Migration {
+            version: 2u64,
+            message: "table is missing",
+        })?;
+    table.entry("redeals").or_insert(0.into());
+
+    let game = document.as_object_mut().context(Migration {
+        version: 2u64,
+        message: "saved game is not an object",
+    })?;
+    for field in ["moves", "score", "elapsed_ms"] {
+        game.entry(field).or_insert(0.into());
+    }
+    game.entry("paused").or_insert(false.into());
+
+    Ok(document)
+}
+
+/// Version 3 saved only a seed, and only seeded deals were saved then. Version 4 also records
+/// which numbering the deal came from, so Microsoft deals can be told apart.
+fn number_deals(mut document: serde_json::Value) -> Result<serde_json::Value, LoadError> {
+    let game = document.as_object_mut().context(Migration {
+        version: 3u64,
+        message: "saved game is not an object",
+    })?;
+    let deal = match game.remove("seed") {
+        Some(serde_json::Value::Null) | None => serde_json::Value::Null,
+        Some(seed) => serde_json::json!({ "Seeded": seed }),
+    };
+    game.insert("deal".to_owned(), deal);
+
+    Ok(document)
+}
+
+fn migrate(mut document: serde_json::Value) -> Result<serde_json::Value, LoadError> {
+    let mut version = document
+        .get("version")
+        .and_then(serde_json::Value::as_u64)
+        .context(MissingVersion)?;
+
+    snafu::ensure!(
+        (1..=CURRENT_VERSION).contains(&version),
+        UnsupportedVersion { version }
+    );
+
+    while version < CURRENT_VERSION {
+        let migration = MIGRATIONS[version as usize - 1];
+        document = migration(document)?;
+        version += 1;
+        document["version"] = version.into();
+    }
+
+    Ok(document)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+    use crate::model::action::Actionable as _;
+    use crate::model::game::{GameAction, TableAction};
+
+    fn dealt_game() -> game::KlondikeGame<model::deck::SeededShuffle> {
+        let mut game = game::KlondikeGame::new(
+            dealer::KlondikeDealer,
+            rules::KlondikeRules,
+            Default::default(),
+            model::deck::SeededShuffle::new(11982),
+        );
+        game.deal_and_start().unwrap();
+        game.apply(TableAction(table::KlondikeTableAction::Draw(3)))
+            .unwrap();
+        game
+    }
+
+    #[test]
+    fn load_should_round_trip() {
+        let game = dealt_game();
+        let loaded = from_str(&to_string(&game).unwrap()).unwrap();
+
+        assert_eq!(loaded.table(), game.table());
+        assert_eq!(loaded.settings(), game.settings());
+        assert_eq!(loaded.seed(), game.seed());
+        assert_eq!(loaded.is_started(), game.is_started());
+        assert_eq!(loaded.is_dealt(), game.is_dealt());
+    }
+
+    #[test]
+    fn load_should_keep_elapsed_time() {
+        let mut game = dealt_game();
+        let clock = model::clock::FakeClock::new();
+        game.set_clock(std::sync::Arc::new(clock.clone()));
+        clock.advance(time::Duration::from_secs(95));
+        game.pause();
+
+        let loaded = from_str(&to_string(&game).unwrap()).unwrap();
+
+        assert!(loaded.elapsed() >= time::Duration::from_secs(95));
+        assert!(loaded.is_paused());
+    }
+
+    #[test]
+    fn load_should_keep_clock_running() {
+        let game = dealt_game();
+        let loaded = from_str(&to_string(&game).unwrap()).unwrap();
+
+        assert!(!loaded.is_paused());
+    }
+
+    #[test]
+    fn load_should_continue_deal_sequence() {
+        let mut game = dealt_game();
+        let mut loaded = from_str(&to_string(&game).unwrap()).unwrap();
+
+        game.apply(GameAction::Clear).unwrap();
+        loaded.apply(GameAction::Clear).unwrap();
+
+        assert_eq!(loaded.seed(), game.seed());
+        assert_eq!(loaded.table(), game.table());
+    }
+
+    #[test]
+    fn load_should_continue_microsoft_deal_sequence() {
+        let mut game = game::KlondikeGame::new(
+            dealer::KlondikeDealer,
+            rules::KlondikeRules,
+            Default::default(),
+            model::deck::MicrosoftShuffle::new(11982),
+        );
+        game.deal_and_start().unwrap();
+        let mut loaded = from_str(&to_string(&game).unwrap()).unwrap();
+
+        assert_eq!(
+            loaded.deal_number(),
+            Some(model::deck::DealNumber::Microsoft(11982))
+        );
+
+        game.apply(GameAction::Clear).unwrap();
+        loaded.apply(GameAction::Clear).unwrap();
+
+        assert_eq!(loaded.deal_number(), game.deal_number());
+        assert_eq!(loaded.table(), game.table());
+    }
+
+    fn tampered(edit: impl FnOnce(&mut serde_json::Value)) -> Result<(), LoadError> {
+        let mut document: serde_json::Value =
+            serde_json::from_str(&to_string(&dealt_game()).unwrap()).unwrap();
+        edit(&mut document);
+        from_str(&document.to_string()).map(|_| ())
+    }
+
+    #[test]
+    fn load_should_reject_duplicate_cards() {
+        let result = tampered(|document| {
+            let card = document["table"]["stock"][0].clone();
+            document["table"]["stock"][1] = card;
+        });
+
+        assert_matches!(
+            result,
+            Err(LoadError::InvalidTable {
+                source: table::KlondikeTableError::DuplicateCard { .. }
+            })
+        );
+    }
+
+    #[test]
+    fn load_should_reject_missing_cards() {
+        let result = tampered(|document| {
+            document["table"]["stock"].as_array_mut().unwrap().pop();
+        });
+
+        assert_matches!(
+            result,
+            Err(LoadError::InvalidTable {
+                source: table::KlondikeTableError::MissingCard { .. }
+            })
+        );
+    }
+
+    #[test]
+    fn load_should_reject_impossible_facing() {
+        let result = tampered(|document| {
+            document["table"]["stock"][0]["facing"] = "FaceUp".into();
+        });
+
+        assert_matches!(
+            result,
+            Err(LoadError::InvalidTable {
+                source: table::KlondikeTableError::IllegalFacing { .. }
+            })
+        );
+    }
+
+    #[test]
+    fn load_should_reject_unreachable_dealer_position() {
+        let result = tampered(|document| {
+            document["dealer"]["state"] = serde_json::json!({"Deal": {"column": 0, "row": 9}});
+        });
+
+        assert_matches!(
+            result,
+            Err(LoadError::InvalidDeal {
+                source: dealer::KlondikeDealerError::UnreachablePosition
+            })
+        );
+    }
+
+    #[test]
+    fn load_should_reject_dealer_for_other_width() {
+        let result = tampered(|document| {
+            document["dealer"]["tableaux_width"] = 6.into();
+        });
+
+        assert_matches!(
+            result,
+            Err(LoadError::InvalidDeal {
+                source: dealer::KlondikeDealerError::WidthMismatch { .. }
+            })
+        );
+    }
+
+    #[test]
+    fn load_should_reject_dealer_behind_table() {
+        let result = tampered(|document| {
+            document["started"] = false.into();
+            document["dealer"]["state"] = serde_json::json!({"Reveal": {"index": 3}});
+        });
+
+        assert_matches!(
+            result,
+            Err(LoadError::InvalidDeal {
+                source: dealer::KlondikeDealerError::LayoutMismatch { .. }
+            })
+        );
+    }
+
+    #[test]
+    fn load_should_reject_start_before_deal() {
+        let mut game = game::KlondikeGame::new(
+            dealer::KlondikeDealer,
+            rules::KlondikeRules,
+            Default::default(),
+            model::deck::SeededShuffle::new(11982),
+        );
+        game.apply(GameAction::Start).unwrap();
+
+        assert_matches!(
+            from_str(&to_string(&game).unwrap()),
+            Err(LoadError::StartedBeforeDealt)
+        );
+    }
+
+    #[test]
+    fn load_should_accept_partly_dealt_game() {
+        let mut game = game::KlondikeGame::new(
+            dealer::KlondikeDealer,
+            rules::KlondikeRules,
+            Default::default(),
+            model::deck::SeededShuffle::new(11982),
+        );
+        for _ in 0..30 {
+            game.apply(model::game::DealAction).unwrap();
+        }
+
+        let mut loaded = from_str(&to_string(&game).unwrap()).unwrap();
+        loaded.deal_and_start().unwrap();
+        game.deal_and_start().unwrap();
+
+        assert_eq!(loaded.table(), game.table());
+    }
+
+    #[test]
+    fn load_should_upgrade_version_1() {
+        let game = dealt_game();
+        let mut document: serde_json::Value =
+            serde_json::from_str(&to_string(&game).unwrap()).unwrap();
+        document["version"] = 1.into();
+        document["settings"]
+            .as_object_mut()
+            .unwrap()
+            .remove("draw_count");
+
+        let loaded = from_str(&document.to_string()).unwrap();
+
+        assert_eq!(loaded.settings().draw_count, 3);
+        assert_eq!(loaded.table(), game.table());
+    }
+
+    #[test]
+    fn load_should_upgrade_version_2() {
+        let game = dealt_game();
+        let mut document: serde_json::Value =
+            serde_json::from_str(&to_string(&game).unwrap()).unwrap();
+        document["version"] = 2.into();
+        for field in ["moves", "score", "elapsed_ms"] {
+            document.as_object_mut().unwrap().remove(field);
+        }
+        for field in ["max_redeals", "scoring", "timed"] {
+            document["settings"].as_object_mut().unwrap().remove(field);
+        }
+        document["table"].as_object_mut().unwrap().remove("redeals");
+
+        let loaded = from_str(&document.to_string()).unwrap();
+
+        assert_eq!(loaded.settings(), &settings::KlondikeSettings::default());
+        assert_eq!(loaded.table().redeals(), 0);
+        assert_eq!(loaded.moves(), 0);
+        assert_eq!(loaded.score(), 0);
+    }
+
+    #[test]
+    fn load_should_keep_redeals_from_version_2() {
+        let mut document: serde_json::Value =
+            serde_json::from_str(&to_string(&dealt_game()).unwrap()).unwrap();
+        document["version"] = 2.into();
+        document["settings"]["max_redeals"] = 1.into();
+        document["table"]["redeals"] = 1.into();
+
+        let loaded = from_str(&document.to_string()).unwrap();
+
+        assert_eq!(loaded.settings().max_redeals, Some(1));
+        assert_eq!(loaded.table().redeals(), 1);
+    }
+
+    #[test]
+    fn load_should_upgrade_version_3() {
+        let game = dealt_game();
+        let mut document: serde_json::Value =
+            serde_json::from_str(&to_string(&game).unwrap()).unwrap();
+        document["version"] = 3.into();
+        let game_document = document.as_object_mut().unwrap();
+        game_document.remove("deal");
+        game_document.insert("seed".to_owned(), 11982.into());
+
+        let loaded = from_str(&document.to_string()).unwrap();
+
+        assert_eq!(
+            loaded.deal_number(),
+            Some(model::deck::DealNumber::Seeded(11982))
+        );
+    }
+
+    #[test]
+    fn load_should_reject_future_versions() {
+        let result = tampered(|document| {
+            document["version"] = (CURRENT_VERSION + 1).into();
+        });
+
+        assert_matches!(result, Err(LoadError::UnsupportedVersion { .. }));
+    }
+}
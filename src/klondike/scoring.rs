@@ -0,0 +1,352 @@
+//! Scoring for Klondike. Which scheme a game uses is part of its settings, so it's saved along
+//! with everything else.
+
+use std::time;
+
+use crate::klondike::{settings, table};
+use crate::model;
+use crate::model::table::Table as _;
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    derive_more::Display,
+    serde::Deserialize,
+    serde::Serialize,
+)]
+pub enum KlondikeScoring {
+    #[display(fmt = "none")]
+    None,
+    #[default]
+    #[display(fmt = "standard")]
+    Standard,
+    #[display(fmt = "vegas")]
+    Vegas,
+}
+
+impl KlondikeScoring {
+    /// The most times the waste may be turned over under this scoring, if it imposes a limit.
+    pub fn redeal_limit(self, draw_count: usize) -> Option<usize> {
+        match self {
+            KlondikeScoring::None | KlondikeScoring::Standard => None,
+            KlondikeScoring::Vegas => Some(VegasScoring::redeal_limit(draw_count)),
+        }
+    }
+}
+
+/// The scoring used by Windows Solitaire.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct StandardScoring;
+
+impl StandardScoring {
+    pub const WASTE_TO_TABLEAUX: i64 = 5;
+    pub const TO_FOUNDATION: i64 = 10;
+    pub const REVEAL: i64 = 5;
+    pub const FOUNDATION_TO_TABLEAUX: i64 = -15;
+    /// Turning the waste over when drawing one card at a time.
+    pub const REDEAL_DRAW_ONE: i64 = -100;
+    /// Turning the waste over when drawing several cards at a time. The first few passes are free.
+    pub const REDEAL: i64 = -20;
+    pub const FREE_REDEALS: usize = 2;
+}
+
+impl model::scoring::ScoringScheme<table::KlondikeTableAction, table::KlondikeTable>
+    for StandardScoring
+{
+    fn points(&self, action: &table::KlondikeTableAction, table: &table::KlondikeTable) -> i64 {
+        match *action {
+            table::KlondikeTableAction::Move(_, table::KlondikePileId::Foundation(_), _) => {
+                Self::TO_FOUNDATION
+            }
+            table::KlondikeTableAction::Move(
+                table::KlondikePileId::Waste,
+                table::KlondikePileId::Tableaux(_),
+                _,
+            ) => Self::WASTE_TO_TABLEAUX,
+            table::KlondikeTableAction::Move(
+                table::KlondikePileId::Foundation(_),
+                table::KlondikePileId::Tableaux(_),
+                _,
+            ) => Self::FOUNDATION_TO_TABLEAUX,
+            table::KlondikeTableAction::Reveal(_) => Self::REVEAL,
+            table::KlondikeTableAction::Draw(count)
+                if table.pile(table::KlondikePileId::Stock).is_empty() =>
+            {
+                if count == 1 {
+                    Self::REDEAL_DRAW_ONE
+                } else if table.redeals() >= Self::FREE_REDEALS {
+                    Self::REDEAL
+                } else {
+                    0
+                }
+            }
+            _ => 0,
+        }
+    }
+}
+
+/// Vegas scoring: the deck is bought up front, and every card played to a foundation earns some of
+/// that back. The stock may only be gone through once when drawing one card at a time, or three
+/// times when drawing several.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct VegasScoring;
+
+impl VegasScoring {
+    pub const DECK_COST: i64 = 52;
+    pub const PER_CARD: i64 = 5;
+
+    pub fn redeal_limit(draw_count: usize) -> usize {
+        if draw_count == 1 {
+            0
+        } else {
+            2
+        }
+    }
+}
+
+impl model::scoring::ScoringScheme<table::KlondikeTableAction, table::KlondikeTable>
+    for VegasScoring
+{
+    fn points(&self, action: &table::KlondikeTableAction, _table: &table::KlondikeTable) -> i64 {
+        match *action {
+            table::KlondikeTableAction::Move(_, table::KlondikePileId::Foundation(_), _) => {
+                Self::PER_CARD
+            }
+            table::KlondikeTableAction::Move(table::KlondikePileId::Foundation(_), _, _) => {
+                -Self::PER_CARD
+            }
+            _ => 0,
+        }
+    }
+
+    fn initial_score(&self) -> i64 {
+        -Self::DECK_COST
+    }
+}
+
+/// The time modifier from Windows Solitaire's timed games, which applies on top of any scheme:
+/// points are taken away for every ten seconds played, and a win earns a bonus that is bigger the
+/// faster it was.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct TimedScoring;
+
+impl TimedScoring {
+    pub const PENALTY: i64 = -2;
+    pub const PENALTY_INTERVAL: time::Duration = time::Duration::from_secs(10);
+    /// The bonus for a win is this divided by the number of seconds played.
+    pub const BONUS: i64 = 700_000;
+    /// Wins faster than this earn no bonus at all.
+    pub const MIN_BONUS_TIME: time::Duration = time::Duration::from_secs(30);
+
+    pub fn points(elapsed: time::Duration, won: bool) -> i64 {
+        let intervals = elapsed.as_secs() / Self::PENALTY_INTERVAL.as_secs();
+        let penalty = intervals as i64 * Self::PENALTY;
+
+        if won && elapsed >= Self::MIN_BONUS_TIME {
+            penalty + Self::BONUS / elapsed.as_secs() as i64
+        } else {
+            penalty
+        }
+    }
+}
+
+impl model::scoring::ScoringScheme<table::KlondikeTableAction, table::KlondikeTable>
+    for KlondikeScoring
+{
+    fn points(&self, action: &table::KlondikeTableAction, table: &table::KlondikeTable) -> i64 {
+        match self {
+            KlondikeScoring::None => 0,
+            KlondikeScoring::Standard => StandardScoring.points(action, table),
+            KlondikeScoring::Vegas => VegasScoring.points(action, table),
+        }
+    }
+
+    fn initial_score(&self) -> i64 {
+        match self {
+            KlondikeScoring::None => 0,
+            KlondikeScoring::Standard => StandardScoring.initial_score(),
+            KlondikeScoring::Vegas => VegasScoring.initial_score(),
+        }
+    }
+}
+
+impl model::scoring::ScoringScheme<table::KlondikeTableAction, table::KlondikeTable>
+    for settings::KlondikeSettings
+{
+    fn points(&self, action: &table::KlondikeTableAction, table: &table::KlondikeTable) -> i64 {
+        self.scoring.points(action, table)
+    }
+
+    fn initial_score(&self) -> i64 {
+        self.scoring.initial_score()
+    }
+
+    fn time_points(&self, elapsed: time::Duration, finished: bool) -> i64 {
+        if self.timed {
+            TimedScoring::points(elapsed, finished)
+        } else {
+            0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+    use crate::klondike::{dealer, game, rules, solver};
+    use crate::model::action::Actionable as _;
+    use crate::model::card::Suit;
+    use crate::model::deck;
+    use crate::model::game::{GameAction, TableAction};
+    use crate::model::scoring::ScoringScheme as _;
+
+    #[test_case(
+        table::KlondikeTableAction::Move(table::KlondikePileId::Waste, table::KlondikePileId::Tableaux(0), 1)
+        => StandardScoring::WASTE_TO_TABLEAUX; "waste to tableaux")]
+    #[test_case(
+        table::KlondikeTableAction::Move(table::KlondikePileId::Waste, table::KlondikePileId::Foundation(Suit::Spades), 1)
+        => StandardScoring::TO_FOUNDATION; "waste to foundation")]
+    #[test_case(
+        table::KlondikeTableAction::Move(table::KlondikePileId::Tableaux(0), table::KlondikePileId::Foundation(Suit::Spades), 1)
+        => StandardScoring::TO_FOUNDATION; "tableaux to foundation")]
+    #[test_case(
+        table::KlondikeTableAction::Move(table::KlondikePileId::Foundation(Suit::Spades), table::KlondikePileId::Tableaux(0), 1)
+        => StandardScoring::FOUNDATION_TO_TABLEAUX; "foundation to tableaux")]
+    #[test_case(
+        table::KlondikeTableAction::Move(table::KlondikePileId::Tableaux(0), table::KlondikePileId::Tableaux(1), 1)
+        => 0; "tableaux to tableaux")]
+    #[test_case(
+        table::KlondikeTableAction::Reveal(table::KlondikePileId::Tableaux(0))
+        => StandardScoring::REVEAL; "reveal")]
+    #[test_case(table::KlondikeTableAction::Draw(3) => 0; "draw")]
+    fn standard_points(action: table::KlondikeTableAction) -> i64 {
+        let table = table::KlondikeTable::new_with_cards(model::card::Card::values_face_down());
+        StandardScoring.points(&action, &table)
+    }
+
+    #[test_case(1, 0 => StandardScoring::REDEAL_DRAW_ONE; "draw one")]
+    #[test_case(3, 0 => 0; "first draw three redeal")]
+    #[test_case(3, StandardScoring::FREE_REDEALS => StandardScoring::REDEAL; "later draw three redeal")]
+    fn standard_redeal_points(draw_count: usize, redeals: usize) -> i64 {
+        let mut table = table::KlondikeTable::new_with_cards(model::card::Card::values_face_down());
+        let draw = table::KlondikeTableAction::Draw(draw_count);
+        for _ in 0..=redeals {
+            while !table.pile(table::KlondikePileId::Stock).is_empty() {
+                table.apply(draw).unwrap();
+            }
+            if table.redeals() < redeals {
+                table.apply(draw).unwrap();
+            }
+        }
+
+        StandardScoring.points(&draw, &table)
+    }
+
+    #[test]
+    fn score_should_be_recomputable_from_actions() {
+        let settings = settings::KlondikeSettings {
+            draw_count: 1,
+            ..Default::default()
+        };
+        let mut game = game::KlondikeGame::new(
+            dealer::KlondikeDealer,
+            rules::KlondikeRules,
+            settings,
+            deck::SeededShuffle::new(0),
+        );
+        game.deal_and_start().unwrap();
+        let dealt_table = game.table().clone();
+
+        let actions = match solver::KlondikeSolver::default().solve_game(&game) {
+            solver::SolverOutcome::Solved(actions) => actions,
+            outcome => panic!("Expected a solution, got {:?}", outcome),
+        };
+        game.apply_all(actions.iter().copied().map(TableAction))
+            .unwrap();
+
+        assert!(game.score() > 0);
+        assert_eq!(
+            model::scoring::score_actions(&settings, dealt_table, actions),
+            Ok(game.score())
+        );
+
+        // The winning move is always to a foundation.
+        let score = game.score();
+        game.undo().unwrap();
+        assert_eq!(game.score(), score - StandardScoring::TO_FOUNDATION);
+    }
+
+    #[test]
+    fn vegas_game_should_start_by_buying_the_deck() {
+        let settings = settings::KlondikeSettings {
+            scoring: KlondikeScoring::Vegas,
+            ..Default::default()
+        };
+        let mut game = game::KlondikeGame::new(
+            dealer::KlondikeDealer,
+            rules::KlondikeRules,
+            settings,
+            deck::SeededShuffle::new(0),
+        );
+        assert_eq!(game.score(), -VegasScoring::DECK_COST);
+
+        game.apply(GameAction::Clear).unwrap();
+        assert_eq!(game.score(), -VegasScoring::DECK_COST);
+    }
+
+    #[test_case(5, false => 0; "first interval")]
+    #[test_case(25, false => 2 * TimedScoring::PENALTY; "penalty")]
+    #[test_case(20, true => 2 * TimedScoring::PENALTY; "too fast for bonus")]
+    #[test_case(100, true => 10 * TimedScoring::PENALTY + 7000; "bonus")]
+    fn timed_points(seconds: u64, won: bool) -> i64 {
+        TimedScoring::points(time::Duration::from_secs(seconds), won)
+    }
+
+    #[test]
+    fn timed_game_should_score_its_clock() {
+        let settings = settings::KlondikeSettings {
+            timed: true,
+            ..Default::default()
+        };
+        let mut game = game::KlondikeGame::new(
+            dealer::KlondikeDealer,
+            rules::KlondikeRules,
+            settings,
+            deck::SeededShuffle::new(0),
+        );
+        let clock = model::clock::FakeClock::new();
+        game.set_clock(std::sync::Arc::new(clock.clone()));
+
+        clock.advance(time::Duration::from_secs(60));
+        assert_eq!(game.total_score(), game.score());
+
+        game.apply(GameAction::Start).unwrap();
+        clock.advance(time::Duration::from_secs(60));
+        assert_eq!(game.total_score(), game.score() + 6 * TimedScoring::PENALTY);
+    }
+
+    #[test_case(KlondikeScoring::Standard, 1, None => None; "standard")]
+    #[test_case(KlondikeScoring::Vegas, 1, None => Some(0); "vegas draw one")]
+    #[test_case(KlondikeScoring::Vegas, 3, None => Some(2); "vegas draw three")]
+    #[test_case(KlondikeScoring::Vegas, 3, Some(1) => Some(1); "stricter setting")]
+    #[test_case(KlondikeScoring::Vegas, 3, Some(5) => Some(2); "looser setting")]
+    fn redeal_limit(
+        scoring: KlondikeScoring,
+        draw_count: usize,
+        max_redeals: Option<usize>,
+    ) -> Option<usize> {
+        settings::KlondikeSettings {
+            draw_count,
+            max_redeals,
+            scoring,
+            ..Default::default()
+        }
+        .redeal_limit()
+    }
+}
@@ -1,14 +1,47 @@
-#[derive(Debug, Clone, Copy)]
+use crate::klondike::scoring;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct KlondikeSettings {
     pub allow_move_from_foundation: bool,
+    /// Move cards to the foundations automatically once they can't be of any more use on the
+    /// tableaux.
+    #[serde(default)]
+    pub auto_move_to_foundation: bool,
+    /// How many cards are turned over from the stock at a time.
+    pub draw_count: usize,
+    /// How many times the waste may be turned back over to form a new stock, if there is a limit.
+    /// Some scoring schemes impose a stricter limit; see `redeal_limit`.
+    #[serde(default)]
+    pub max_redeals: Option<usize>,
+    #[serde(default)]
+    pub scoring: scoring::KlondikeScoring,
     pub tableaux_width: usize,
+    /// Add points for time on top of the scoring scheme; see `scoring::TimedScoring`.
+    #[serde(default)]
+    pub timed: bool,
+}
+
+impl KlondikeSettings {
+    /// The most times the waste may be turned over, taking into account any limit the scoring
+    /// imposes as well as `max_redeals`.
+    pub fn redeal_limit(&self) -> Option<usize> {
+        match (self.max_redeals, self.scoring.redeal_limit(self.draw_count)) {
+            (Some(max_redeals), Some(scoring_limit)) => Some(max_redeals.min(scoring_limit)),
+            (max_redeals, scoring_limit) => max_redeals.or(scoring_limit),
+        }
+    }
 }
 
 impl Default for KlondikeSettings {
     fn default() -> Self {
         Self {
             allow_move_from_foundation: true,
+            auto_move_to_foundation: false,
+            draw_count: 3,
+            max_redeals: None,
+            scoring: Default::default(),
             tableaux_width: 7,
+            timed: false,
         }
     }
 }
@@ -0,0 +1,376 @@
+//! A depth-first solver for Klondike. The solver only ever takes actions the rules allow, so any
+//! solution it finds can be replayed through a `KlondikeGame`.
+
+use std::collections::HashSet;
+
+use crate::klondike::{game, rules, settings, table};
+use crate::model;
+use crate::model::action::Actionable as _;
+use crate::model::table::Table as _;
+
+pub const DEFAULT_MAX_NODES: usize = 200_000;
+
+#[derive(Debug, Clone, Copy)]
+pub struct KlondikeSolver {
+    pub max_nodes: usize,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SolverOutcome {
+    /// The actions that take the table from its current position to a win.
+    Solved(Vec<table::KlondikeTableAction>),
+    /// Every reachable position was searched without finding a win.
+    Unwinnable,
+    /// The search visited its maximum number of positions without reaching a conclusion.
+    GaveUp { nodes: usize },
+}
+
+struct Frame {
+    table: table::KlondikeTable,
+    actions: Vec<table::KlondikeTableAction>,
+}
+
+impl Default for KlondikeSolver {
+    fn default() -> Self {
+        Self {
+            max_nodes: DEFAULT_MAX_NODES,
+        }
+    }
+}
+
+impl KlondikeSolver {
+    pub fn solve_game<SH>(&self, game: &game::KlondikeGame<SH>) -> SolverOutcome {
+        self.solve(game.table(), game.settings())
+    }
+
+    pub fn solve(
+        &self,
+        table: &table::KlondikeTable,
+        settings: &settings::KlondikeSettings,
+    ) -> SolverOutcome {
+        if table.is_won() {
+            return SolverOutcome::Solved(Vec::new());
+        }
+
+        // Searching without the moves that are rarely worth making is much faster, but running out
+        // of moves after skipping some of them doesn't prove anything, so in that case the search
+        // starts over and tries everything.
+        let mut search = Search::new(self.max_nodes, true);
+        match search.run(table, settings) {
+            SolverOutcome::Unwinnable if search.pruned => {
+                let mut retry = Search::new(self.max_nodes, false);
+                retry.nodes = search.nodes;
+                retry.run(table, settings)
+            }
+            outcome => outcome,
+        }
+    }
+}
+
+/// A single depth-first search, with its own record of the positions it has seen.
+struct Search {
+    max_nodes: usize,
+    nodes: usize,
+    /// Whether to skip the moves that `is_pointless` rules out.
+    prune: bool,
+    /// Whether any move has actually been skipped.
+    pruned: bool,
+}
+
+impl Search {
+    fn new(max_nodes: usize, prune: bool) -> Self {
+        Self {
+            max_nodes,
+            nodes: 0,
+            prune,
+            pruned: false,
+        }
+    }
+
+    fn run(
+        &mut self,
+        table: &table::KlondikeTable,
+        settings: &settings::KlondikeSettings,
+    ) -> SolverOutcome {
+        let mut visited = HashSet::new();
+        visited.insert(state_key(table, settings));
+
+        let mut path = Vec::new();
+        let mut stack = vec![Frame {
+            actions: self.ordered_actions(table, settings),
+            table: table.clone(),
+        }];
+
+        while let Some(frame) = stack.last_mut() {
+            let action = match frame.actions.pop() {
+                Some(action) => action,
+                None => {
+                    stack.pop();
+                    path.pop();
+                    continue;
+                }
+            };
+
+            let mut next_table = frame.table.clone();
+            next_table
+                .apply(action)
+                .unwrap_or_else(|never| match never {});
+
+            if !visited.insert(state_key(&next_table, settings)) {
+                continue;
+            }
+
+            if self.nodes == self.max_nodes {
+                return SolverOutcome::GaveUp {
+                    nodes: self.max_nodes,
+                };
+            }
+            self.nodes += 1;
+
+            path.push(action);
+
+            if next_table.is_won() {
+                return SolverOutcome::Solved(path);
+            }
+
+            stack.push(Frame {
+                actions: self.ordered_actions(&next_table, settings),
+                table: next_table,
+            });
+        }
+
+        SolverOutcome::Unwinnable
+    }
+
+    /// The legal actions from this position, ordered so that the most promising action is at the
+    /// *end* of the list (since the search pops from the end).
+    fn ordered_actions(
+        &mut self,
+        table: &table::KlondikeTable,
+        settings: &settings::KlondikeSettings,
+    ) -> Vec<table::KlondikeTableAction> {
+        let context = rules::KlondikeRulesContext::new(settings, true, table);
+        let mut actions = rules::KlondikeRules.legal_actions(&context);
+
+        // Nothing can be moved onto or off a face-down card, so revealing it first can't rule out
+        // anything the other moves could have done. If we can, that's the only thing worth trying.
+        if let Some(&reveal) = actions
+            .iter()
+            .find(|action| matches!(action, table::KlondikeTableAction::Reveal(_)))
+        {
+            return vec![reveal];
+        }
+
+        actions.retain(|&action| !is_swap(table, action));
+        if self.prune {
+            let count = actions.len();
+            actions.retain(|&action| !is_pointless(table, action));
+            self.pruned |= actions.len() < count;
+        }
+        actions.sort_by_key(|&action| priority(action));
+        actions
+    }
+}
+
+/// Higher priorities are tried first.
+fn priority(action: table::KlondikeTableAction) -> u8 {
+    match action {
+        table::KlondikeTableAction::Move(_, table::KlondikePileId::Foundation(_), _) => 5,
+        table::KlondikeTableAction::Move(table::KlondikePileId::Tableaux(_), _, _) => 4,
+        table::KlondikeTableAction::Move(table::KlondikePileId::Waste, _, _) => 3,
+        table::KlondikeTableAction::Draw(_) => 2,
+        table::KlondikeTableAction::Move(table::KlondikePileId::Foundation(_), _, _) => 1,
+        _ => 0,
+    }
+}
+
+/// Some moves rarely make progress, and trying them just lets the search wander back and forth
+/// between similar positions:
+///
+/// * Moving an entire tableaux onto an empty tableaux just swaps two columns.
+/// * Splitting a run of face-up cards between tableaux usually only helps if it frees up the card
+///   underneath to go to a foundation. It can also make room for another card on the one exposed,
+///   though, so the solver doesn't take this as proof that a position can't be won.
+pub(super) fn is_pointless(
+    table: &table::KlondikeTable,
+    action: table::KlondikeTableAction,
+) -> bool {
+    match action {
+        table::KlondikeTableAction::Move(
+            source_pile_id @ table::KlondikePileId::Tableaux(_),
+            table::KlondikePileId::Tableaux(_),
+            count,
+        ) => {
+            let source = table.pile(source_pile_id);
+
+            if is_swap(table, action) {
+                true
+            } else if count == source.len() || count == source.top_face_up_cards().len() {
+                false
+            } else {
+                let exposed_card = &source.top_cards(count + 1)[0];
+                !fits_foundation(table, exposed_card)
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Whether an action moves an entire tableaux onto an empty one, which leads to the same position
+/// with two columns trading places.
+fn is_swap(table: &table::KlondikeTable, action: table::KlondikeTableAction) -> bool {
+    match action {
+        table::KlondikeTableAction::Move(
+            source_pile_id @ table::KlondikePileId::Tableaux(_),
+            target_pile_id @ table::KlondikePileId::Tableaux(_),
+            count,
+        ) => count == table.pile(source_pile_id).len() && table.pile(target_pile_id).is_empty(),
+        _ => false,
+    }
+}
+
+pub(super) fn fits_foundation(table: &table::KlondikeTable, card: &model::card::Card) -> bool {
+    match table
+        .pile(table::KlondikePileId::Foundation(card.suit()))
+        .top_card()
+    {
+        Some(top_card) => card.rank().follows(top_card.rank()),
+        None => card.is_ace(),
+    }
+}
+
+/// A compact key identifying a position. The order of the tableaux doesn't affect what can happen
+/// next, so they are sorted to let the search recognize positions that only differ by columns
+/// trading places.
+fn state_key(table: &table::KlondikeTable, settings: &settings::KlondikeSettings) -> Vec<u8> {
+    let mut tableaux = (0..settings.tableaux_width)
+        .map(|index| encode_pile(table.pile(table::KlondikePileId::Tableaux(index))))
+        .collect::<Vec<_>>();
+    tableaux.sort();
+
+    let mut key = Vec::new();
+    for pile_id in table::KlondikePileId::standard_iter() {
+        key.extend(encode_pile(table.pile(pile_id)));
+    }
+    for pile in tableaux {
+        key.extend(pile);
+    }
+    // Only bother telling passes through the stock apart when they're limited. Otherwise, how many
+    // have been made so far makes no difference to what can happen next.
+    if settings.redeal_limit().is_some() {
+        key.push(table.redeals().min(u8::MAX.into()) as u8);
+    }
+    key
+}
+
+fn encode_pile(pile: &model::pile::Pile) -> Vec<u8> {
+    const SEPARATOR: u8 = u8::MAX;
+    const FACE_UP: u8 = 0x80;
+
+    pile.iter()
+        .map(|card| {
+            let index = card.suit() as u8 * 13 + card.rank() as u8;
+            if card.is_face_up() {
+                index | FACE_UP
+            } else {
+                index
+            }
+        })
+        .chain(Some(SEPARATOR))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+    use crate::klondike::{dealer, text};
+    use crate::model::deck;
+    use crate::model::game::TableAction;
+
+    // The only move is to split the 7H off onto the 8C, which exposes a card that can't go to its
+    // foundation.
+    const ONLY_A_SPLIT: &str = "\
+Stock: 0
+Waste:
+Foundations: 6S 5H KD 6C
+1: [**] [**] [**] [**] [**] [**] [**] [**] [**] [**] 8S 7H
+2: [**] [**] [**] [**] [**] [**] [**] [**] [**] 8C
+";
+
+    fn dealt_game(
+        settings: settings::KlondikeSettings,
+        seed: u64,
+    ) -> game::KlondikeGame<deck::SeededShuffle> {
+        let mut game = game::KlondikeGame::new(
+            dealer::KlondikeDealer,
+            rules::KlondikeRules,
+            settings,
+            deck::SeededShuffle::new(seed),
+        );
+        game.deal_and_start().unwrap();
+        game
+    }
+
+    #[test]
+    fn solution_should_win_when_replayed() {
+        let settings = settings::KlondikeSettings {
+            draw_count: 1,
+            ..Default::default()
+        };
+        let mut game = dealt_game(settings, 0);
+
+        assert_matches!(KlondikeSolver::default().solve_game(&game), SolverOutcome::Solved(actions) => {
+            game.apply_all(actions.into_iter().map(TableAction)).unwrap();
+        });
+        assert!(game.table().is_won());
+    }
+
+    #[test]
+    fn solver_should_prove_unwinnable() {
+        // With no tableaux, only cards that happen to come up on the waste in order can be played.
+        let settings = settings::KlondikeSettings {
+            draw_count: 3,
+            tableaux_width: 0,
+            ..Default::default()
+        };
+        let game = dealt_game(settings, 1);
+
+        assert_eq!(
+            KlondikeSolver::default().solve_game(&game),
+            SolverOutcome::Unwinnable
+        );
+    }
+
+    #[test]
+    fn solver_should_give_up() {
+        let game = dealt_game(Default::default(), 1);
+        let solver = KlondikeSolver { max_nodes: 10 };
+
+        assert_eq!(
+            solver.solve_game(&game),
+            SolverOutcome::GaveUp { nodes: 10 }
+        );
+    }
+
+    #[test]
+    fn solver_should_not_rule_out_skipped_moves() {
+        let table = text::parse(ONLY_A_SPLIT).unwrap();
+        let settings = settings::KlondikeSettings {
+            allow_move_from_foundation: false,
+            tableaux_width: 2,
+            ..Default::default()
+        };
+
+        // Without trying the split, there'd be nothing to search at all.
+        assert_eq!(
+            KlondikeSolver { max_nodes: 0 }.solve(&table, &settings),
+            SolverOutcome::GaveUp { nodes: 0 }
+        );
+        assert_eq!(
+            KlondikeSolver::default().solve(&table, &settings),
+            SolverOutcome::Unwinnable
+        );
+    }
+}
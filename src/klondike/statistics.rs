@@ -0,0 +1,353 @@
+//! A record of every finished game, kept in a local file with one JSON record per line. Records are
+//! only ever appended, so a crash can at worst leave a partial line at the end. Lines that can't be
+//! read are skipped when loading rather than losing the whole history.
+
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::{BufRead as _, Read as _, Seek as _, Write as _};
+use std::path::Path;
+use std::{fmt, fs, io, time};
+
+use snafu::{ResultExt as _, Snafu};
+
+use crate::klondike::{game, settings};
+use crate::model;
+use crate::model::table::Table as _;
+
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, derive_more::Display, serde::Deserialize, serde::Serialize,
+)]
+pub enum GameOutcome {
+    #[display(fmt = "won")]
+    Won,
+    #[display(fmt = "lost")]
+    Lost,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct GameRecord {
+    /// The seed of a seeded deal.
+    pub seed: Option<u64>,
+    /// The game number of a deal from Microsoft's numbering. Records from before these could be
+    /// played don't have one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub microsoft_game: Option<u32>,
+    pub settings: settings::KlondikeSettings,
+    pub outcome: GameOutcome,
+    pub moves: usize,
+    pub score: i64,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Statistics {
+    records: Vec<GameRecord>,
+    skipped: usize,
+}
+
+/// A printable overview of the statistics.
+#[derive(Debug, Clone, Copy)]
+pub struct Summary<'a>(&'a Statistics);
+
+#[derive(Debug, Snafu)]
+pub enum StatisticsError {
+    #[snafu(display("Unable to read statistics: {}", source))]
+    Read { source: io::Error },
+    #[snafu(display("Unable to write statistics: {}", source))]
+    Write { source: io::Error },
+    #[snafu(display("Unable to encode game record: {}", source))]
+    Encode { source: serde_json::Error },
+}
+
+impl GameRecord {
+    /// Describe the game as it stands. Any game that isn't won when it's recorded counts as lost.
+    pub fn from_game<SH>(game: &game::KlondikeGame<SH>) -> Self {
+        let outcome = if game.table().is_won() {
+            GameOutcome::Won
+        } else {
+            GameOutcome::Lost
+        };
+
+        let (seed, microsoft_game) = match game.deal_number() {
+            Some(model::deck::DealNumber::Seeded(seed)) => (Some(seed), None),
+            Some(model::deck::DealNumber::Microsoft(game)) => (None, Some(game)),
+            None => (None, None),
+        };
+
+        Self {
+            seed,
+            microsoft_game,
+            settings: *game.settings(),
+            outcome,
+            moves: game.moves(),
+            score: game.total_score(),
+            duration_ms: game.elapsed().as_millis() as u64,
+        }
+    }
+
+    pub fn deal_number(&self) -> Option<model::deck::DealNumber> {
+        self.microsoft_game
+            .map(model::deck::DealNumber::Microsoft)
+            .or_else(|| self.seed.map(model::deck::DealNumber::Seeded))
+    }
+
+    pub fn duration(&self) -> time::Duration {
+        time::Duration::from_millis(self.duration_ms)
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.outcome == GameOutcome::Won
+    }
+}
+
+impl Statistics {
+    pub fn new(records: Vec<GameRecord>) -> Self {
+        Self {
+            records,
+            skipped: 0,
+        }
+    }
+
+    /// Load every readable record from a statistics file. A missing file has no records.
+    pub fn load(path: &Path) -> Result<Self, StatisticsError> {
+        let file = match fs::File::open(path) {
+            Ok(file) => file,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(error) => return Err(error).context(Read),
+        };
+
+        let mut statistics = Self::default();
+        for line in io::BufReader::new(file).split(b'\n') {
+            let line = line.context(Read)?;
+            if line.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+
+            match serde_json::from_slice(&line) {
+                Ok(record) => statistics.records.push(record),
+                Err(_) => statistics.skipped += 1,
+            }
+        }
+
+        Ok(statistics)
+    }
+
+    /// Add a record to the end of a statistics file, creating it (and its directory) if needed.
+    pub fn append(path: &Path, record: &GameRecord) -> Result<(), StatisticsError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context(Write)?;
+        }
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)
+            .context(Write)?;
+
+        // If an earlier write was cut off, start on a fresh line so only that record is lost.
+        let mut contents = serde_json::to_vec(record).context(Encode)?;
+        contents.push(b'\n');
+        if file.seek(io::SeekFrom::End(0)).context(Write)? > 0 {
+            let mut last_byte = [0];
+            file.seek(io::SeekFrom::End(-1)).context(Write)?;
+            file.read_exact(&mut last_byte).context(Write)?;
+            if last_byte[0] != b'\n' {
+                contents.insert(0, b'\n');
+            }
+        }
+
+        file.write_all(&contents).context(Write)
+    }
+
+    pub fn records(&self) -> &[GameRecord] {
+        &self.records
+    }
+
+    /// How many lines of the file couldn't be read.
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    /// The games played and won for each draw count, ordered by draw count.
+    pub fn results_by_draw_count(&self) -> BTreeMap<usize, (usize, usize)> {
+        let mut results = BTreeMap::new();
+        for record in &self.records {
+            let (played, won) = results.entry(record.settings.draw_count).or_insert((0, 0));
+            *played += 1;
+            if record.is_won() {
+                *won += 1;
+            }
+        }
+        results
+    }
+
+    /// The percentage of games won when drawing this many cards, if any such games were played.
+    pub fn win_percentage(&self, draw_count: usize) -> Option<f64> {
+        self.results_by_draw_count()
+            .get(&draw_count)
+            .map(|&(played, won)| 100.0 * won as f64 / played as f64)
+    }
+
+    /// How many of the most recent games were won in a row.
+    pub fn current_streak(&self) -> usize {
+        self.records
+            .iter()
+            .rev()
+            .take_while(|record| record.is_won())
+            .count()
+    }
+
+    /// The most games ever won in a row.
+    pub fn longest_streak(&self) -> usize {
+        self.records
+            .split(|record| !record.is_won())
+            .map(<[_]>::len)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn fastest_win(&self) -> Option<&GameRecord> {
+        self.records
+            .iter()
+            .filter(|record| record.is_won())
+            .min_by_key(|record| record.duration_ms)
+    }
+
+    /// The average number of moves per game, if any games were played.
+    pub fn average_moves(&self) -> Option<f64> {
+        if self.records.is_empty() {
+            return None;
+        }
+
+        let total: usize = self.records.iter().map(|record| record.moves).sum();
+        Some(total as f64 / self.records.len() as f64)
+    }
+
+    pub fn summary(&self) -> Summary<'_> {
+        Summary(self)
+    }
+}
+
+impl fmt::Display for Summary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Summary(statistics) = *self;
+
+        writeln!(f, "Games played: {}", statistics.records.len())?;
+        for (draw_count, (played, won)) in statistics.results_by_draw_count() {
+            writeln!(
+                f,
+                "Draw {}: won {} of {} ({:.1}%)",
+                draw_count,
+                won,
+                played,
+                100.0 * won as f64 / played as f64
+            )?;
+        }
+        writeln!(f, "Current streak: {}", statistics.current_streak())?;
+        writeln!(f, "Longest streak: {}", statistics.longest_streak())?;
+        if let Some(record) = statistics.fastest_win() {
+            let seconds = record.duration().as_secs();
+            write!(f, "Fastest win: {}:{:02}", seconds / 60, seconds % 60)?;
+            match record.deal_number() {
+                Some(deal_number) => writeln!(f, " (game {})", deal_number)?,
+                None => writeln!(f)?,
+            }
+        }
+        if let Some(average_moves) = statistics.average_moves() {
+            writeln!(f, "Average moves: {:.1}", average_moves)?;
+        }
+        if statistics.skipped > 0 {
+            writeln!(f, "Skipped {} unreadable records", statistics.skipped)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use test_case::test_case;
+
+    use super::*;
+
+    fn record(outcome: GameOutcome, draw_count: usize, duration_secs: u64) -> GameRecord {
+        GameRecord {
+            seed: Some(duration_secs),
+            microsoft_game: None,
+            settings: settings::KlondikeSettings {
+                draw_count,
+                ..Default::default()
+            },
+            outcome,
+            moves: 100,
+            score: 0,
+            duration_ms: duration_secs * 1000,
+        }
+    }
+
+    fn statistics(outcomes: &str) -> Statistics {
+        Statistics::new(
+            outcomes
+                .chars()
+                .enumerate()
+                .map(|(index, outcome)| {
+                    let outcome = if outcome == 'W' {
+                        GameOutcome::Won
+                    } else {
+                        GameOutcome::Lost
+                    };
+                    record(outcome, 1 + index % 2 * 2, 60 + index as u64)
+                })
+                .collect(),
+        )
+    }
+
+    #[test_case("" => (0, 0); "no games")]
+    #[test_case("WWLWWWL" => (0, 3); "ends with loss")]
+    #[test_case("LWWLWW" => (2, 2); "ends with wins")]
+    fn streaks(outcomes: &str) -> (usize, usize) {
+        let statistics = statistics(outcomes);
+        (statistics.current_streak(), statistics.longest_streak())
+    }
+
+    #[test]
+    fn queries_should_summarize_records() {
+        // Even games draw one, odd games draw three.
+        let statistics = statistics("LWWWLL");
+
+        assert_eq!(statistics.win_percentage(1), Some(100.0 / 3.0));
+        assert_eq!(statistics.win_percentage(3), Some(200.0 / 3.0));
+        assert_eq!(statistics.win_percentage(2), None);
+        assert_eq!(statistics.fastest_win().unwrap().duration_ms, 61_000);
+        assert_eq!(statistics.average_moves(), Some(100.0));
+    }
+
+    #[test]
+    fn load_should_skip_corrupt_lines() {
+        let path = env::temp_dir()
+            .join(format!("klondike2-test-{}", process::id()))
+            .join("statistics.jsonl");
+        let _ = fs::remove_file(&path);
+
+        Statistics::append(&path, &record(GameOutcome::Won, 1, 60)).unwrap();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"not a record\n{\"seed\": 1, \"sett")
+            .unwrap();
+        Statistics::append(&path, &record(GameOutcome::Lost, 3, 90)).unwrap();
+
+        let statistics = Statistics::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            statistics.records(),
+            [
+                record(GameOutcome::Won, 1, 60),
+                record(GameOutcome::Lost, 3, 90)
+            ]
+        );
+        assert_eq!(statistics.skipped(), 2);
+    }
+}
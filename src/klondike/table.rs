@@ -1,10 +1,15 @@
+use std::collections::BTreeSet;
 use std::convert;
 
 use enum_like::EnumValues as _;
+use snafu::Snafu;
 
 use crate::model;
+use crate::model::table::Table as _;
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, derive_more::Display)]
+#[derive(
+    Debug, Copy, Clone, Eq, PartialEq, derive_more::Display, serde::Deserialize, serde::Serialize,
+)]
 pub enum KlondikePileId {
     #[display(fmt = "Stock")]
     Stock,
@@ -33,7 +38,7 @@ impl KlondikePileId {
     }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, serde::Deserialize, serde::Serialize)]
 pub struct KlondikeTable {
     stock: model::pile::Pile,
     waste: model::pile::Pile,
@@ -44,6 +49,10 @@ pub struct KlondikeTable {
     clubs_foundation: model::pile::Pile,
 
     tableaux: Vec<model::pile::Pile>,
+
+    /// How many times the waste has been turned back over to form a new stock.
+    #[serde(default)]
+    redeals: usize,
 }
 
 impl model::table::Table for KlondikeTable {
@@ -66,6 +75,8 @@ impl model::table::Table for KlondikeTable {
             clubs_foundation: model::pile::Pile::new(),
 
             tableaux: Vec::new(),
+
+            redeals: 0,
         }
     }
 
@@ -84,10 +95,175 @@ impl model::table::Table for KlondikeTable {
             KlondikePileId::Tableaux(index) => self.tableaux.get(index).unwrap_or(&EMPTY),
         }
     }
+
+    fn is_won(&self) -> bool {
+        model::card::Suit::values()
+            .all(|suit| self.pile(KlondikePileId::Foundation(suit)).len() == 13)
+    }
+
+    /// Once every card is face up on the tableaux, with no card on top of a higher one, the lowest
+    /// card can always go to its foundation, so the rest of the game plays itself. Klondike's
+    /// rules never let a higher card on top, but Yukon's do.
+    fn is_auto_completable(&self) -> bool {
+        self.stock.is_empty()
+            && self.waste.is_empty()
+            && self.tableaux.iter().all(|pile| {
+                pile.is_face_up()
+                    && pile
+                        .iter()
+                        .zip(pile.iter().skip(1))
+                        .all(|(below, above)| below.rank() >= above.rank())
+            })
+    }
+
+    /// The cards that could be picked up from the top of a pile, bottom to top. Only the face-up
+    /// run of a tableaux can be moved as a group, and nothing can be moved from the stock. The
+    /// waste is entirely face up, but only its top card (the top card of the most recent draw) is
+    /// in play.
+    fn movable_cards(&self, pile_id: KlondikePileId) -> &[model::card::Card] {
+        match pile_id {
+            KlondikePileId::Stock => &[],
+            KlondikePileId::Waste | KlondikePileId::Foundation(_) => {
+                self.pile(pile_id).top_cards(1)
+            }
+            KlondikePileId::Tableaux(_) => self.pile(pile_id).top_face_up_cards(),
+        }
+    }
+}
+
+// Tableaux are created on demand, so two tables may differ only in how many trailing empty tableaux
+// they have. We don't consider that a meaningful difference.
+impl PartialEq for KlondikeTable {
+    fn eq(&self, other: &Self) -> bool {
+        let tableaux_width = self.tableaux.len().max(other.tableaux.len());
+
+        self.redeals == other.redeals
+            && KlondikePileId::full_iter(tableaux_width)
+                .all(|pile_id| self.pile(pile_id) == other.pile(pile_id))
+    }
+}
+
+impl Eq for KlondikeTable {}
+
+#[derive(Debug, Snafu)]
+pub enum KlondikeTableError {
+    #[snafu(display("The {} appears more than once", card))]
+    DuplicateCard { card: model::card::CardFace },
+    #[snafu(display("The {} is missing", card))]
+    MissingCard { card: model::card::CardFace },
+    #[snafu(display("The {} in the {} should not be {}", card, pile_id, facing))]
+    IllegalFacing {
+        pile_id: KlondikePileId,
+        card: model::card::CardFace,
+        facing: model::card::Facing,
+    },
+    #[snafu(display("The {} is out of order in the {}", card, pile_id))]
+    OutOfOrder {
+        pile_id: KlondikePileId,
+        card: model::card::CardFace,
+    },
+    #[snafu(display("There is no tableaux {}", index + 1))]
+    PileOutOfBounds { index: usize },
 }
 
 impl KlondikeTable {
-    fn pile_mut(&mut self, pile_id: KlondikePileId) -> &mut model::pile::Pile {
+    /// How many times the waste has been turned back over to form a new stock, i.e., how many
+    /// passes through the stock have been completed.
+    pub fn redeals(&self) -> usize {
+        self.redeals
+    }
+
+    /// Check that the table holds a full deck, laid out in a way that could have come about by
+    /// playing. This is meant for tables that came from outside, e.g., a saved game, since tables
+    /// built by applying actions through the rules are always valid.
+    pub fn validate(&self, tableaux_width: usize) -> Result<(), KlondikeTableError> {
+        if let Some(index) =
+            (tableaux_width..self.tableaux.len()).find(|&index| !self.tableaux[index].is_empty())
+        {
+            return PileOutOfBounds { index }.fail();
+        }
+
+        let mut seen = BTreeSet::new();
+        for card in
+            KlondikePileId::full_iter(tableaux_width).flat_map(|pile_id| self.pile(pile_id).iter())
+        {
+            snafu::ensure!(seen.insert(card.face), DuplicateCard { card: card.face });
+        }
+        if let Some(card) = model::card::CardFace::values().find(|face| !seen.contains(face)) {
+            return MissingCard { card }.fail();
+        }
+
+        for pile_id in KlondikePileId::full_iter(tableaux_width) {
+            self.validate_pile(pile_id)?;
+        }
+
+        Ok(())
+    }
+
+    fn validate_pile(&self, pile_id: KlondikePileId) -> Result<(), KlondikeTableError> {
+        let pile = self.pile(pile_id);
+
+        let illegal_facing = |card: &model::card::Card| IllegalFacing {
+            pile_id,
+            card: card.face,
+            facing: card.facing,
+        };
+
+        match pile_id {
+            KlondikePileId::Stock => {
+                if let Some(card) = pile.iter().find(|card| card.is_face_up()) {
+                    return illegal_facing(card).fail();
+                }
+            }
+            KlondikePileId::Waste => {
+                if let Some(card) = pile.iter().find(|card| card.is_face_down()) {
+                    return illegal_facing(card).fail();
+                }
+            }
+            KlondikePileId::Foundation(suit) => {
+                for (card, rank) in pile.iter().zip(model::card::Rank::values()) {
+                    snafu::ensure!(card.is_face_up(), illegal_facing(card));
+                    snafu::ensure!(
+                        card.face == rank.of(suit),
+                        OutOfOrder {
+                            pile_id,
+                            card: card.face
+                        }
+                    );
+                }
+            }
+            KlondikePileId::Tableaux(_) => {
+                // Face-down cards may only appear underneath all the face-up cards.
+                let face_up_cards = pile.top_face_up_cards();
+                if let Some(card) = pile
+                    .iter()
+                    .take(pile.len() - face_up_cards.len())
+                    .find(|card| card.is_face_up())
+                {
+                    return illegal_facing(card).fail();
+                }
+
+                for pair in face_up_cards.windows(2) {
+                    snafu::ensure!(
+                        pair[0].rank().follows(pair[1].rank())
+                            && pair[0].color() != pair[1].color(),
+                        OutOfOrder {
+                            pile_id,
+                            card: pair[1].face
+                        }
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn set_redeals(&mut self, redeals: usize) {
+        self.redeals = redeals;
+    }
+
+    pub(crate) fn pile_mut(&mut self, pile_id: KlondikePileId) -> &mut model::pile::Pile {
         match pile_id {
             KlondikePileId::Stock => &mut self.stock,
             KlondikePileId::Waste => &mut self.waste,
@@ -107,15 +283,21 @@ impl KlondikeTable {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, derive_more::Display, serde::Deserialize, serde::Serialize,
+)]
 pub enum KlondikeTableAction {
+    #[display(fmt = "Deal to the {}", _0)]
     Deal(KlondikePileId),
+    #[display(fmt = "Draw {} from the Stock", _0)]
     Draw(usize),
+    #[display(fmt = "Move {} from the {} to the {}", _2, _0, _1)]
     Move(KlondikePileId, KlondikePileId, usize),
+    #[display(fmt = "Reveal the top of the {}", _0)]
     Reveal(KlondikePileId),
 }
 
-impl<'a> model::action::Action<KlondikeTable> for KlondikeTableAction {
+impl model::action::Action<KlondikeTable> for KlondikeTableAction {
     type Error = convert::Infallible;
 
     fn apply_to(self, table: &mut KlondikeTable) -> Result<(), Self::Error> {
@@ -130,6 +312,7 @@ impl<'a> model::action::Action<KlondikeTable> for KlondikeTableAction {
                 if empty {
                     let replacement_cards = table.waste.take_all().flipped();
                     table.stock.place(replacement_cards);
+                    table.redeals += 1;
                 } else {
                     let drawn_cards = table.stock.take(count).flipped();
                     table.waste.place(drawn_cards);
@@ -150,173 +333,80 @@ impl<'a> model::action::Action<KlondikeTable> for KlondikeTableAction {
     }
 }
 
-// impl rules::Rules<Table, Action> for Rules {
-//     type Error = RulesError;
-//
-//     fn check_rules(&self, target: &Table, action: &Action) -> Result<(), Self::Error> {
-//         match action {
-//             &model::action::Deal(target_pile_id, _) => {
-//                 if let PileId::Tableaux(index) = target_pile_id {
-//                     snafu::ensure!(index < self.tableaux_width, PileOutOfBounds { index });
-//
-//                     if let Some(top_card) = target.pile(target_pile_id).top_card() {
-//                         snafu::ensure!(
-//                             top_card.facing == model::card::Facing::FaceDown,
-//                             IllegalDealTargetFacing {
-//                                 facing: top_card.facing
-//                             }
-//                         );
-//                     }
-//                 } else {
-//                     return IllegalDealTarget {
-//                         pile_id: target_pile_id,
-//                     }
-//                     .fail();
-//                 }
-//             }
-//             &model::action::Move(source_pile_id, target_pile_id, count) => {
-//                 snafu::ensure!(count > 0, EmptyMove);
-//
-//                 let source_top_cards = target.pile(source_pile_id).top_cards(count);
-//                 snafu::ensure!(count == source_top_cards.len(), InsufficientCards);
-//
-//                 // We assume it's sufficient to check the facing of the bottom card of the pile.
-//                 snafu::ensure!(
-//                     source_top_cards[0].is_face_up(),
-//                     IllegalMoveSourceFacing {
-//                         facing: source_top_cards[0].facing
-//                     }
-//                 );
-//
-//                 match source_pile_id {
-//                     PileId::Tableaux(index) => {
-//                         snafu::ensure!(index < self.tableaux_width, PileOutOfBounds { index });
-//                     }
-//                     PileId::Foundation(_) => {
-//                         snafu::ensure!(self.can_move_from_foundation, IllegalMoveFromFoundation);
-//                         snafu::ensure!(
-//                             count == 1,
-//                             MayOnlyTakeSingleCard {
-//                                 pile_id: source_pile_id
-//                             }
-//                         );
-//                     }
-//                     PileId::Waste => {
-//                         snafu::ensure!(
-//                             count == 1,
-//                             MayOnlyTakeSingleCard {
-//                                 pile_id: source_pile_id
-//                             }
-//                         );
-//                     }
-//                     _ => {
-//                         return IllegalMoveSource {
-//                             pile_id: source_pile_id,
-//                         }
-//                         .fail()
-//                     }
-//                 }
-//
-//                 match target_pile_id {
-//                     PileId::Tableaux(index) => {
-//                         snafu::ensure!(index < self.tableaux_width, PileOutOfBounds { index });
-//
-//                         if let Some(target_top_card) = target.pile(target_pile_id).top_card() {
-//                             snafu::ensure!(
-//                                 target_top_card.is_face_up(),
-//                                 IllegalMoveTargetFacing {
-//                                     facing: target_top_card.facing
-//                                 }
-//                             );
-//                             snafu::ensure!(
-//                                 target_top_card.rank().follows(source_top_cards[0].rank()),
-//                                 TableauxMismatch {
-//                                     card: source_top_cards[0].face,
-//                                     mismatch: TableauxMismatchType::Follow(target_top_card.face)
-//                                 }
-//                             );
-//                             snafu::ensure!(
-//                                 target_top_card.color() != source_top_cards[0].color(),
-//                                 TableauxMismatch {
-//                                     card: target_top_card.face,
-//                                     mismatch: TableauxMismatchType::Follow(target_top_card.face)
-//                                 }
-//                             );
-//                         } else {
-//                             snafu::ensure!(
-//                                 source_top_cards[0].is_king(),
-//                                 TableauxMismatch {
-//                                     card: source_top_cards[0].face,
-//                                     mismatch: TableauxMismatchType::Start
-//                                 }
-//                             );
-//                         }
-//                     }
-//                     PileId::Foundation(suit) => {
-//                         snafu::ensure!(
-//                             count == 1,
-//                             MayOnlyAcceptSingleCard {
-//                                 pile_id: target_pile_id,
-//                             }
-//                         );
-//
-//                         // We can expect this because we checked the source had sufficient cards
-//                         // above.
-//                         let source_card = &source_top_cards[0];
-//
-//                         if let Some(target_top_card) = target.pile(target_pile_id).top_card() {
-//                             snafu::ensure!(
-//                                 target_top_card.color() == source_card.color(),
-//                                 FoundationMismatch {
-//                                     card: target_top_card.face,
-//                                     mismatch: FoundationMismatchType::Follow(target_top_card.face)
-//                                 }
-//                             );
-//                             snafu::ensure!(
-//                                 target_top_card.rank().follows(source_card.rank()),
-//                                 FoundationMismatch {
-//                                     card: source_card.face,
-//                                     mismatch: FoundationMismatchType::Follow(target_top_card.face)
-//                                 }
-//                             );
-//                         } else {
-//                             snafu::ensure!(
-//                                 source_card.suit() == suit,
-//                                 FoundationMismatch {
-//                                     card: source_card.face,
-//                                     mismatch: FoundationMismatchType::Start(suit)
-//                                 }
-//                             );
-//                             snafu::ensure!(
-//                                 source_card.is_ace(),
-//                                 FoundationMismatch {
-//                                     card: source_card.face,
-//                                     mismatch: FoundationMismatchType::Start(suit)
-//                                 }
-//                             );
-//                         }
-//                     }
-//                     _ => {
-//                         return IllegalMoveTarget {
-//                             pile_id: target_pile_id,
-//                         }
-//                         .fail();
-//                     }
-//                 }
-//             }
-//             &model::action::Reveal(target_pile_id) => {
-//                 if let PileId::Tableaux(index) = target_pile_id {
-//                     snafu::ensure!(index < self.tableaux_width, PileOutOfBounds { index });
-//                 } else {
-//                     return IllegalRevealTarget {
-//                         pile_id: target_pile_id,
-//                     }
-//                     .fail();
-//                 }
-//             }
-//             _ => {}
-//         }
-//
-//         Ok(())
-//     }
-// }
+/// The actions needed to undo a `KlondikeTableAction`. These are never validated by the rules, so
+/// they aren't suitable as player actions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KlondikeTableInverseAction {
+    /// Move cards from one pile to another, flipping them over as a group (so both their order
+    /// and facing are reversed).
+    Flip(KlondikePileId, KlondikePileId, usize),
+    /// Move cards from one pile to another as they are.
+    Move(KlondikePileId, KlondikePileId, usize),
+    /// Turn the top card of a pile to a particular facing.
+    Turn(KlondikePileId, model::card::Facing),
+    /// Turn the stock back over onto the waste, taking back a redeal.
+    Unredeal,
+}
+
+impl model::action::Action<KlondikeTable> for KlondikeTableInverseAction {
+    type Error = convert::Infallible;
+
+    fn apply_to(self, table: &mut KlondikeTable) -> Result<(), Self::Error> {
+        match self {
+            Self::Flip(source_pile_id, target_pile_id, count) => {
+                let flipped_cards = table.pile_mut(source_pile_id).take(count).flipped();
+                table.pile_mut(target_pile_id).place(flipped_cards);
+            }
+            Self::Move(source_pile_id, target_pile_id, count) => {
+                let moved_cards = table.pile_mut(source_pile_id).take(count);
+                table.pile_mut(target_pile_id).place(moved_cards);
+            }
+            Self::Turn(target_pile_id, facing) => {
+                table.pile_mut(target_pile_id).flip_top_to(facing);
+            }
+            Self::Unredeal => {
+                let waste_cards = table.stock.take_all().flipped();
+                table.waste.place(waste_cards);
+                table.redeals = table.redeals.saturating_sub(1);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl model::action::Invertible<KlondikeTable> for KlondikeTableAction {
+    type Inverse = KlondikeTableInverseAction;
+
+    fn inverse(&self, table: &KlondikeTable) -> Self::Inverse {
+        match *self {
+            Self::Deal(target_pile_id) => {
+                let count = table.stock.len().min(1);
+                KlondikeTableInverseAction::Move(target_pile_id, KlondikePileId::Stock, count)
+            }
+            Self::Draw(count) => {
+                if table.stock.is_empty() {
+                    KlondikeTableInverseAction::Unredeal
+                } else {
+                    KlondikeTableInverseAction::Flip(
+                        KlondikePileId::Waste,
+                        KlondikePileId::Stock,
+                        table.stock.len().min(count),
+                    )
+                }
+            }
+            Self::Move(source_pile_id, target_pile_id, count) => {
+                let count = table.pile(source_pile_id).len().min(count);
+                KlondikeTableInverseAction::Move(target_pile_id, source_pile_id, count)
+            }
+            Self::Reveal(target_pile_id) => {
+                let facing = table
+                    .pile(target_pile_id)
+                    .top_card()
+                    .map(|card| card.facing)
+                    .unwrap_or(model::card::Facing::FaceUp);
+                KlondikeTableInverseAction::Turn(target_pile_id, facing)
+            }
+        }
+    }
+}
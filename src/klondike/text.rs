@@ -0,0 +1,357 @@
+//! A compact plain-text form of a Klondike table, for logs, bug reports, and test fixtures. For
+//! example:
+//!
+//! ```text
+//! Stock: 20
+//! Waste: 3D 8H KC
+//! Foundations: AS 2H -- --
+//! 1: 7S
+//! 2: [**] 9C 8D
+//! 3:
+//! ```
+//!
+//! Each foundation is shown by its top card, and face-down cards (including the whole stock) are
+//! masked. A `Redeals:` line follows the foundations once the waste has been turned over. When
+//! parsing, masked cards are filled in with the cards that don't appear anywhere else, in deck
+//! order, so a parsed table renders back to exactly the same text.
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use enum_like::EnumValues as _;
+use snafu::{OptionExt as _, ResultExt as _, Snafu};
+
+use crate::klondike::table;
+use crate::model;
+use crate::model::table::Table as _;
+
+const FACE_DOWN: &str = "[**]";
+const EMPTY_FOUNDATION: &str = "--";
+const DECK_SIZE: usize = 52;
+
+/// Displays a table in the plain-text form.
+#[derive(Debug, Clone, Copy)]
+pub struct TableText<'a> {
+    table: &'a table::KlondikeTable,
+    tableaux_width: usize,
+}
+
+#[derive(Debug, Snafu)]
+pub enum ParseTableError {
+    #[snafu(display("Line {}: expected \"{}:\"", line, label))]
+    MissingLabel { line: usize, label: String },
+    #[snafu(display("Line {}: expected no more lines", line))]
+    UnexpectedLine { line: usize },
+    #[snafu(display("Line {}: {:?} is not a number", line, text))]
+    InvalidNumber { line: usize, text: String },
+    #[snafu(display("Line {}: {:?} is not a card: {}", line, text, source))]
+    InvalidCard {
+        line: usize,
+        text: String,
+        source: model::card::ParseCardError,
+    },
+    #[snafu(display("Line {}: a face-down card can't go here", line))]
+    UnexpectedFaceDown { line: usize },
+    #[snafu(display("The {} appears more than once", card))]
+    DuplicateCard { card: model::card::CardFace },
+    #[snafu(display("The table has {} cards, but a deck has 52", count))]
+    CardCount { count: usize },
+    #[snafu(display("The table is impossible: {}", source))]
+    InvalidTable { source: table::KlondikeTableError },
+}
+
+/// The lines of the text that have something on them, with their line numbers.
+struct Lines<'a> {
+    lines: Vec<(usize, &'a str)>,
+    position: usize,
+    end: usize,
+}
+
+pub fn display(table: &table::KlondikeTable, tableaux_width: usize) -> TableText<'_> {
+    TableText {
+        table,
+        tableaux_width,
+    }
+}
+
+/// Parse a table from the plain-text form. The number of tableaux lines sets the tableaux width.
+pub fn parse(text: &str) -> Result<table::KlondikeTable, ParseTableError> {
+    let mut lines = Lines::new(text);
+
+    // Masked cards are `None` until we know which cards are left over to fill them.
+    let mut piles: Vec<(table::KlondikePileId, Vec<Option<model::card::CardFace>>)> = Vec::new();
+
+    let (line, stock) = lines.expect("Stock")?;
+    // Check the size before making room for it, since it could be anything.
+    let stock_size = parse_number(line, stock)?;
+    snafu::ensure!(stock_size <= DECK_SIZE, CardCount { count: stock_size });
+    piles.push((table::KlondikePileId::Stock, vec![None; stock_size]));
+
+    let (line, waste) = lines.expect("Waste")?;
+    let waste = parse_cards(line, waste)?;
+    snafu::ensure!(
+        waste.iter().all(Option::is_some),
+        UnexpectedFaceDown { line }
+    );
+    piles.push((table::KlondikePileId::Waste, waste));
+
+    let (line, foundations) = lines.expect("Foundations")?;
+    for token in foundations.split_whitespace() {
+        if token == EMPTY_FOUNDATION {
+            continue;
+        }
+        let top_card: model::card::CardFace =
+            token.parse().context(InvalidCard { line, text: token })?;
+        piles.push((
+            table::KlondikePileId::Foundation(top_card.suit),
+            model::card::Rank::values()
+                .take_while(|&rank| rank <= top_card.rank)
+                .map(|rank| Some(rank.of(top_card.suit)))
+                .collect(),
+        ));
+    }
+
+    let redeals = match lines.take("Redeals") {
+        Some((line, redeals)) => parse_number(line, redeals)?,
+        None => 0,
+    };
+
+    let mut tableaux_width = 0;
+    while let Some((line, tableaux)) = lines.take(&(tableaux_width + 1).to_string()) {
+        piles.push((
+            table::KlondikePileId::Tableaux(tableaux_width),
+            parse_cards(line, tableaux)?,
+        ));
+        tableaux_width += 1;
+    }
+    if let Some(line) = lines.next_line_number() {
+        return UnexpectedLine { line }.fail();
+    }
+
+    let mut seen = BTreeSet::new();
+    let mut count = 0usize;
+    for card in piles.iter().flat_map(|(_, cards)| cards) {
+        if let Some(face) = *card {
+            snafu::ensure!(seen.insert(face), DuplicateCard { card: face });
+        }
+        count += 1;
+    }
+    snafu::ensure!(count == DECK_SIZE, CardCount { count });
+
+    let mut hidden_cards = model::card::CardFace::values().filter(|face| !seen.contains(face));
+    let mut table = table::KlondikeTable::default();
+    for (pile_id, cards) in piles {
+        *table.pile_mut(pile_id) = cards
+            .into_iter()
+            .map(|card| match card {
+                Some(face) => face.face_up(),
+                None => hidden_cards
+                    .next()
+                    .expect("there should be a hidden card for every masked card")
+                    .face_down(),
+            })
+            .collect();
+    }
+    table.set_redeals(redeals);
+
+    table.validate(tableaux_width).context(InvalidTable)?;
+    Ok(table)
+}
+
+impl<'a> Lines<'a> {
+    fn new(text: &'a str) -> Self {
+        let lines = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty())
+            .collect::<Vec<_>>();
+
+        Self {
+            lines,
+            position: 0,
+            end: text.lines().count() + 1,
+        }
+    }
+
+    fn next_line_number(&self) -> Option<usize> {
+        self.lines.get(self.position).map(|&(line, _)| line)
+    }
+
+    /// The rest of the next line after the label, if it has that label.
+    fn take(&mut self, label: &str) -> Option<(usize, &'a str)> {
+        let (line, text) = *self.lines.get(self.position)?;
+        let rest = text.strip_prefix(label)?.strip_prefix(':')?;
+
+        self.position += 1;
+        Some((line, rest.trim()))
+    }
+
+    fn expect(&mut self, label: &str) -> Result<(usize, &'a str), ParseTableError> {
+        let line = self.next_line_number().unwrap_or(self.end);
+        self.take(label).context(MissingLabel { line, label })
+    }
+}
+
+fn parse_number(line: usize, text: &str) -> Result<usize, ParseTableError> {
+    text.parse().ok().context(InvalidNumber { line, text })
+}
+
+fn parse_cards(
+    line: usize,
+    text: &str,
+) -> Result<Vec<Option<model::card::CardFace>>, ParseTableError> {
+    text.split_whitespace()
+        .map(|token| {
+            if token == FACE_DOWN {
+                Ok(None)
+            } else {
+                token
+                    .parse()
+                    .map(Some)
+                    .context(InvalidCard { line, text: token })
+            }
+        })
+        .collect()
+}
+
+impl fmt::Display for TableText<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let write_cards = |f: &mut fmt::Formatter<'_>, pile_id| {
+            for card in self.table.pile(pile_id).iter() {
+                if card.is_face_down() {
+                    write!(f, " {}", FACE_DOWN)?;
+                } else {
+                    write!(f, " {}", card.face.notated(model::card::Notation::Letters))?;
+                }
+            }
+            writeln!(f)
+        };
+
+        writeln!(
+            f,
+            "Stock: {}",
+            self.table.pile(table::KlondikePileId::Stock).len()
+        )?;
+
+        write!(f, "Waste:")?;
+        write_cards(f, table::KlondikePileId::Waste)?;
+
+        write!(f, "Foundations:")?;
+        for suit in model::card::Suit::values() {
+            match self
+                .table
+                .pile(table::KlondikePileId::Foundation(suit))
+                .top_card()
+            {
+                Some(card) => write!(f, " {}", card.face.notated(model::card::Notation::Letters))?,
+                None => write!(f, " {}", EMPTY_FOUNDATION)?,
+            }
+        }
+        writeln!(f)?;
+
+        if self.table.redeals() > 0 {
+            writeln!(f, "Redeals: {}", self.table.redeals())?;
+        }
+
+        for index in 0..self.tableaux_width {
+            write!(f, "{}:", index + 1)?;
+            write_cards(f, table::KlondikePileId::Tableaux(index))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::klondike::{dealer, game, rules};
+
+    const MID_GAME: &str = "\
+Stock: 20
+Waste: 3D 8H KC
+Foundations: AS 2H -- AC
+Redeals: 1
+1: 7S
+2: [**] 9C 8D
+3:
+4: [**] [**] KH QS JH
+5: [**] [**] [**] 5S
+6: [**] [**] [**] [**] 4D
+7: [**] [**] [**] [**] [**] [**] 9S
+";
+
+    #[test]
+    fn text_should_round_trip() {
+        let table = parse(MID_GAME).unwrap();
+
+        assert_eq!(table.redeals(), 1);
+        assert_eq!(
+            table
+                .pile(table::KlondikePileId::Foundation(model::card::Suit::Hearts))
+                .len(),
+            2
+        );
+        assert_eq!(display(&table, 7).to_string(), MID_GAME);
+    }
+
+    #[test]
+    fn dealt_table_should_round_trip() {
+        let mut game = game::KlondikeGame::new(
+            dealer::KlondikeDealer,
+            rules::KlondikeRules,
+            Default::default(),
+            model::deck::SeededShuffle::new(11982),
+        );
+        game.deal_and_start().unwrap();
+
+        let text = display(game.table(), 7).to_string();
+
+        assert_eq!(display(&parse(&text).unwrap(), 7).to_string(), text);
+    }
+
+    #[test_case("Stock: 20\n" => 2; "missing line")]
+    #[test_case("Stock: 20\nWaste: 3D 8X\n" => 2; "bad card")]
+    #[test_case("Stock: 20\nWaste:\nFoundations: -- -- -- --\nfoo\n" => 4; "unexpected line")]
+    fn errors_should_point_at_line(text: &str) -> usize {
+        match parse(text).unwrap_err() {
+            ParseTableError::MissingLabel { line, .. }
+            | ParseTableError::UnexpectedLine { line }
+            | ParseTableError::InvalidCard { line, .. } => line,
+            error => panic!("Unexpected error: {}", error),
+        }
+    }
+
+    #[test]
+    fn parse_should_reject_duplicate_cards() {
+        let text = MID_GAME.replace("1: 7S", "1: 8H");
+
+        assert_matches!(
+            parse(&text),
+            Err(ParseTableError::DuplicateCard { card }) => {
+                assert_eq!(card, model::card::Rank::Eight.of(model::card::Suit::Hearts));
+            }
+        );
+    }
+
+    #[test]
+    fn parse_should_reject_wrong_card_count() {
+        let text = MID_GAME.replace("Stock: 20", "Stock: 21");
+
+        assert_matches!(parse(&text), Err(ParseTableError::CardCount { count: 53 }));
+    }
+
+    #[test]
+    fn parse_should_reject_huge_stock() {
+        let text = MID_GAME.replace("Stock: 20", &format!("Stock: {}", usize::MAX));
+
+        assert_matches!(
+            parse(&text),
+            Err(ParseTableError::CardCount { count }) if count == usize::MAX
+        );
+    }
+}
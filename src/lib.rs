@@ -1,4 +1,8 @@
-#![feature(generic_associated_types)]
-
+pub mod freecell;
 pub mod klondike;
 pub mod model;
+pub mod pyramid;
+pub mod spider;
+#[cfg(test)]
+mod test_support;
+pub mod yukon;
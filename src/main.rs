@@ -1,3 +1,14 @@
+use std::{env, process};
+
+mod cli;
+mod paths;
+mod ui;
+
 fn main() {
-    println!("Hello, world!");
+    let args = env::args().skip(1).collect::<Vec<_>>();
+
+    if let Err(error) = cli::run(&args) {
+        eprintln!("klondike2: {}", error);
+        process::exit(1);
+    }
 }
@@ -1,11 +1,14 @@
-//! The core model of the Klondike game. The types in this module represent the state of the game,
-//! but not the display of the game.
+//! The core model shared by every solitaire variant. The types in this module represent the state of
+//! a game, but not the display of the game.
 
 pub mod action;
 pub mod card;
+pub mod clock;
 pub mod dealer;
 pub mod deck;
 pub mod game;
+pub mod history;
 pub mod pile;
 pub mod rules;
+pub mod scoring;
 pub mod table;
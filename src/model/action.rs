@@ -7,6 +7,15 @@ pub trait Action<T>: Debug + Clone + 'static {
     fn apply_to(self, target: &mut T) -> Result<(), Self::Error>;
 }
 
+/// An action that knows how to undo itself. The inverse must be computed against the target
+/// *before* the action is applied, since applying the action may lose the information needed to
+/// reverse it.
+pub trait Invertible<T>: Action<T> {
+    type Inverse: Action<T>;
+
+    fn inverse(&self, target: &T) -> Self::Inverse;
+}
+
 pub trait Actionable<A>: Sized {
     type Error: Error + 'static;
 
@@ -1,6 +1,60 @@
-use std::cmp;
+use std::{cmp, fmt, iter, str};
 
 use enum_like::{EnumLike, EnumValues};
+use snafu::Snafu;
+
+/// The first of the Unicode playing card code points, which is the back of a card. The faces
+/// follow in blocks of 16, one block per suit.
+const PLAYING_CARD_BACK: u32 = 0x1F0A0;
+
+/// A compact way of writing cards, for formatting and parsing. Parsing accepts any notation.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum Notation {
+    /// Plain ASCII, e.g., `AS`, `TH`, or `[KC]` for a face-down card.
+    #[default]
+    Letters,
+    /// Unicode suit symbols, e.g., `A♠`, `10♥`, or `[K♣]` for a face-down card.
+    Symbols,
+    /// The Unicode playing card characters, e.g., `🂡`. Face-down cards are shown by their back,
+    /// which hides which card they are.
+    PlayingCards,
+}
+
+/// Formats a value in one of the compact notations; see `Notation`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Notated<T> {
+    value: T,
+    notation: Notation,
+}
+
+/// Where parsing a card (or part of one) went wrong. Positions count characters from zero.
+#[derive(Debug, Snafu, Eq, PartialEq)]
+pub enum ParseCardError {
+    #[snafu(display("Expected {} at position {}, but found the end", expected, position))]
+    UnexpectedEnd {
+        position: usize,
+        expected: &'static str,
+    },
+    #[snafu(display(
+        "Expected {} at position {}, but found {:?}",
+        expected,
+        position,
+        character
+    ))]
+    UnexpectedCharacter {
+        position: usize,
+        character: char,
+        expected: &'static str,
+    },
+}
+
+/// Reads through the text being parsed, keeping track of the position for errors.
+struct Cursor<'a> {
+    chars: iter::Peekable<iter::Enumerate<str::Chars<'a>>>,
+    /// The position of the character most recently returned by `next`.
+    position: usize,
+    end: usize,
+}
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, derive_more::Display)]
 pub enum Color {
@@ -10,7 +64,17 @@ pub enum Color {
     Red,
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, derive_more::Display, enum_like_derive::EnumLike)]
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    derive_more::Display,
+    enum_like_derive::EnumLike,
+    serde::Deserialize,
+    serde::Serialize,
+)]
 pub enum Facing {
     #[display(fmt = "Face down")]
     FaceDown,
@@ -40,6 +104,8 @@ impl Facing {
     PartialEq,
     derive_more::Display,
     enum_like_derive::EnumLike,
+    serde::Deserialize,
+    serde::Serialize,
 )]
 #[repr(u8)]
 pub enum Rank {
@@ -92,6 +158,33 @@ impl Rank {
             .map(|other_next| self == other_next)
             .unwrap_or_default()
     }
+
+    pub fn notated(self, notation: Notation) -> Notated<Self> {
+        Notated {
+            value: self,
+            notation,
+        }
+    }
+
+    fn parse_from(cursor: &mut Cursor<'_>) -> Result<Self, ParseCardError> {
+        const EXPECTED: &str = "a rank";
+
+        let rank = match cursor.next(EXPECTED)? {
+            'A' | 'a' => Self::Ace,
+            'T' | 't' => Self::Ten,
+            'J' | 'j' => Self::Jack,
+            'Q' | 'q' => Self::Queen,
+            'K' | 'k' => Self::King,
+            '1' => {
+                cursor.expect('0')?;
+                Self::Ten
+            }
+            digit @ '2'..='9' => Self::from_discr(digit as usize - '1' as usize),
+            character => return cursor.unexpected(character, EXPECTED),
+        };
+
+        Ok(rank)
+    }
 }
 
 #[derive(
@@ -104,6 +197,8 @@ impl Rank {
     PartialEq,
     derive_more::Display,
     enum_like_derive::EnumLike,
+    serde::Deserialize,
+    serde::Serialize,
 )]
 #[repr(u8)]
 pub enum Suit {
@@ -124,6 +219,45 @@ impl Suit {
             Self::Hearts | Self::Diamonds => Color::Red,
         }
     }
+
+    pub fn letter(self) -> char {
+        match self {
+            Self::Spades => 'S',
+            Self::Hearts => 'H',
+            Self::Diamonds => 'D',
+            Self::Clubs => 'C',
+        }
+    }
+
+    pub fn symbol(self) -> char {
+        match self {
+            Self::Spades => '♠',
+            Self::Hearts => '♥',
+            Self::Diamonds => '♦',
+            Self::Clubs => '♣',
+        }
+    }
+
+    pub fn notated(self, notation: Notation) -> Notated<Self> {
+        Notated {
+            value: self,
+            notation,
+        }
+    }
+
+    fn parse_from(cursor: &mut Cursor<'_>) -> Result<Self, ParseCardError> {
+        const EXPECTED: &str = "a suit";
+
+        let suit = match cursor.next(EXPECTED)? {
+            'S' | 's' | '♠' | '♤' => Self::Spades,
+            'H' | 'h' | '♥' | '♡' => Self::Hearts,
+            'D' | 'd' | '♦' | '♢' => Self::Diamonds,
+            'C' | 'c' | '♣' | '♧' => Self::Clubs,
+            character => return cursor.unexpected(character, EXPECTED),
+        };
+
+        Ok(suit)
+    }
 }
 
 #[derive(
@@ -136,6 +270,8 @@ impl Suit {
     PartialEq,
     derive_more::Display,
     enum_like_derive::EnumLike,
+    serde::Deserialize,
+    serde::Serialize,
 )]
 #[display(fmt = "{} of {}", rank, suit)]
 pub struct CardFace {
@@ -171,9 +307,53 @@ impl CardFace {
     pub fn next_in_suit(self) -> Option<Self> {
         self.rank.next().map(|rank| Self { rank, ..self })
     }
+
+    pub fn notated(self, notation: Notation) -> Notated<Self> {
+        Notated {
+            value: self,
+            notation,
+        }
+    }
+
+    /// The Unicode playing card character for this card. Unicode also has Knights, which sit
+    /// between the Jacks and Queens, so we skip over them.
+    pub fn playing_card(self) -> char {
+        let rank = match self.rank {
+            Rank::Queen | Rank::King => self.rank as u32 + 2,
+            rank => rank as u32 + 1,
+        };
+        let code_point = PLAYING_CARD_BACK + 16 * self.suit as u32 + rank;
+
+        char::from_u32(code_point).expect("playing card code points should be valid characters")
+    }
+
+    fn from_playing_card(character: char) -> Option<Self> {
+        let offset = (character as u32).checked_sub(PLAYING_CARD_BACK)?;
+        let (suit, rank) = (offset / 16, offset % 16);
+
+        let suit = Suit::values().nth(suit as usize)?;
+        let rank = match rank {
+            1..=11 => Rank::from_discr(rank as usize - 1),
+            13 | 14 => Rank::from_discr(rank as usize - 2),
+            _ => return None,
+        };
+
+        Some(rank.of(suit))
+    }
+
+    fn parse_from(cursor: &mut Cursor<'_>) -> Result<Self, ParseCardError> {
+        if let Some(face) = cursor.peek().and_then(Self::from_playing_card) {
+            cursor.next("a card")?;
+            return Ok(face);
+        }
+
+        let rank = Rank::parse_from(cursor)?;
+        let suit = Suit::parse_from(cursor)?;
+        Ok(rank.of(suit))
+    }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct Card {
     pub face: CardFace,
     pub facing: Facing,
@@ -241,11 +421,195 @@ impl Card {
     pub fn values_face_up() -> impl Iterator<Item = Self> {
         Self::values_with_facing(Facing::FaceUp)
     }
+
+    pub fn notated(&self, notation: Notation) -> Notated<&Self> {
+        Notated {
+            value: self,
+            notation,
+        }
+    }
+
+    /// A face-up card is written as its face, and a face-down card as its face in brackets.
+    fn parse_from(cursor: &mut Cursor<'_>) -> Result<Self, ParseCardError> {
+        if cursor.peek() == char::from_u32(PLAYING_CARD_BACK) {
+            // The back of a card doesn't say which card it is.
+            let character = cursor.next("a card")?;
+            return cursor.unexpected(character, "a card face");
+        }
+
+        if cursor.peek() == Some('[') {
+            cursor.next("\"[\"")?;
+            let face = CardFace::parse_from(cursor)?;
+            cursor.expect(']')?;
+            return Ok(face.face_down());
+        }
+
+        CardFace::parse_from(cursor).map(CardFace::face_up)
+    }
+}
+
+impl<'a> Cursor<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            chars: text.chars().enumerate().peekable(),
+            position: 0,
+            end: text.chars().count(),
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().map(|&(_, character)| character)
+    }
+
+    fn next(&mut self, expected: &'static str) -> Result<char, ParseCardError> {
+        match self.chars.next() {
+            Some((position, character)) => {
+                self.position = position;
+                Ok(character)
+            }
+            None => UnexpectedEnd {
+                position: self.end,
+                expected,
+            }
+            .fail(),
+        }
+    }
+
+    fn expect(&mut self, expected_character: char) -> Result<(), ParseCardError> {
+        let expected = match expected_character {
+            '0' => "\"0\"",
+            ']' => "\"]\"",
+            _ => "another character",
+        };
+
+        match self.next(expected)? {
+            character if character == expected_character => Ok(()),
+            character => self.unexpected(character, expected),
+        }
+    }
+
+    /// Fail on the character just returned by `next`.
+    fn unexpected<T>(&self, character: char, expected: &'static str) -> Result<T, ParseCardError> {
+        UnexpectedCharacter {
+            position: self.position,
+            character,
+            expected,
+        }
+        .fail()
+    }
+
+    /// Parse the whole text as one value.
+    fn parse_all<T>(
+        text: &'a str,
+        parse: impl FnOnce(&mut Self) -> Result<T, ParseCardError>,
+    ) -> Result<T, ParseCardError> {
+        let mut cursor = Self::new(text);
+        let value = parse(&mut cursor)?;
+
+        match cursor.chars.next() {
+            Some((position, character)) => UnexpectedCharacter {
+                position,
+                character,
+                expected: "the end",
+            }
+            .fail(),
+            None => Ok(value),
+        }
+    }
+}
+
+impl str::FromStr for Rank {
+    type Err = ParseCardError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Cursor::parse_all(text, Self::parse_from)
+    }
+}
+
+impl str::FromStr for Suit {
+    type Err = ParseCardError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Cursor::parse_all(text, Self::parse_from)
+    }
+}
+
+impl str::FromStr for CardFace {
+    type Err = ParseCardError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Cursor::parse_all(text, Self::parse_from)
+    }
+}
+
+impl str::FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Cursor::parse_all(text, Self::parse_from)
+    }
+}
+
+impl fmt::Display for Notated<Rank> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match (self.value, self.notation) {
+            (Rank::Ace, _) => "A",
+            (Rank::Ten, Notation::Letters) => "T",
+            (Rank::Ten, _) => "10",
+            (Rank::Jack, _) => "J",
+            (Rank::Queen, _) => "Q",
+            (Rank::King, _) => "K",
+            (rank, _) => return f.pad(&(rank as u8 + 1).to_string()),
+        };
+
+        f.pad(text)
+    }
+}
+
+impl fmt::Display for Notated<Suit> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let character = match self.notation {
+            Notation::Letters => self.value.letter(),
+            Notation::Symbols | Notation::PlayingCards => self.value.symbol(),
+        };
+
+        f.pad(character.encode_utf8(&mut [0; 4]))
+    }
+}
+
+impl fmt::Display for Notated<CardFace> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self.notation {
+            Notation::PlayingCards => self.value.playing_card().to_string(),
+            notation => format!(
+                "{}{}",
+                self.value.rank.notated(notation),
+                self.value.suit.notated(notation)
+            ),
+        };
+
+        f.pad(&text)
+    }
+}
+
+impl fmt::Display for Notated<&Card> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let face = self.value.face.notated(self.notation);
+        let text = match (self.value.facing, self.notation) {
+            (Facing::FaceUp, _) => face.to_string(),
+            (Facing::FaceDown, Notation::PlayingCards) => char::from_u32(PLAYING_CARD_BACK)
+                .expect("playing card code points should be valid characters")
+                .to_string(),
+            (Facing::FaceDown, _) => format!("[{}]", face),
+        };
+
+        f.pad(&text)
+    }
 }
 
 impl PartialOrd for Card {
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-        self.face.partial_cmp(&other.face)
+        Some(self.cmp(other))
     }
 }
 
@@ -254,3 +618,58 @@ impl Ord for Card {
         self.face.cmp(&other.face)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case("AS" => Ok(Rank::Ace.of(Suit::Spades)); "letters")]
+    #[test_case("th" => Ok(Rank::Ten.of(Suit::Hearts)); "lowercase")]
+    #[test_case("10D" => Ok(Rank::Ten.of(Suit::Diamonds)); "ten")]
+    #[test_case("K♣" => Ok(Rank::King.of(Suit::Clubs)); "symbol")]
+    #[test_case("🂡" => Ok(Rank::Ace.of(Suit::Spades)); "playing card")]
+    #[test_case("🃞" => Ok(Rank::King.of(Suit::Clubs)); "playing card king")]
+    #[test_case("1XD" => Err(ParseCardError::UnexpectedCharacter { position: 1, character: 'X', expected: "\"0\"" }); "bad ten")]
+    #[test_case("QX" => Err(ParseCardError::UnexpectedCharacter { position: 1, character: 'X', expected: "a suit" }); "bad suit")]
+    #[test_case("ZS" => Err(ParseCardError::UnexpectedCharacter { position: 0, character: 'Z', expected: "a rank" }); "bad rank")]
+    #[test_case("JSS" => Err(ParseCardError::UnexpectedCharacter { position: 2, character: 'S', expected: "the end" }); "trailing")]
+    #[test_case("J" => Err(ParseCardError::UnexpectedEnd { position: 1, expected: "a suit" }); "missing suit")]
+    #[test_case("🂬" => Err(ParseCardError::UnexpectedCharacter { position: 0, character: '🂬', expected: "a rank" }); "knight")]
+    fn parse_card_face(text: &str) -> Result<CardFace, ParseCardError> {
+        text.parse()
+    }
+
+    #[test_case(Notation::Letters => "AS 2H TD KC [QS]"; "letters")]
+    #[test_case(Notation::Symbols => "A♠ 2♥ 10♦ K♣ [Q♠]"; "symbols")]
+    #[test_case(Notation::PlayingCards => "🂡 🂲 🃊 🃞 🂠"; "playing cards")]
+    fn format_cards(notation: Notation) -> String {
+        [
+            Rank::Ace.of(Suit::Spades).face_up(),
+            Rank::Two.of(Suit::Hearts).face_up(),
+            Rank::Ten.of(Suit::Diamonds).face_up(),
+            Rank::King.of(Suit::Clubs).face_up(),
+            Rank::Queen.of(Suit::Spades).face_down(),
+        ]
+        .iter()
+        .map(|card| card.notated(notation).to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+    }
+
+    #[test]
+    fn notation_should_round_trip() {
+        for notation in [Notation::Letters, Notation::Symbols, Notation::PlayingCards] {
+            for face in CardFace::values() {
+                assert_eq!(face.notated(notation).to_string().parse(), Ok(face));
+            }
+        }
+        for card in Card::values_face_down() {
+            assert_eq!(
+                card.notated(Notation::Letters).to_string().parse(),
+                Ok(card)
+            );
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use std::fmt::Debug;
+use std::sync::{atomic, Arc};
+use std::time::{Duration, Instant};
+
+pub trait Clock: Debug + Send + Sync {
+    /// The time since some fixed point of the clock's choosing. Only differences between readings
+    /// are meaningful.
+    fn now(&self) -> Duration;
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    origin: Instant,
+}
+
+/// A clock that only moves when told to, for tests. Clones share the same time.
+#[derive(Debug, Clone, Default)]
+pub struct FakeClock {
+    millis: Arc<atomic::AtomicU64>,
+}
+
+/// Adds up time while it's running.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct Stopwatch {
+    elapsed: Duration,
+    running_since: Option<Duration>,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.origin.elapsed()
+    }
+}
+
+impl FakeClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.millis
+            .fetch_add(duration.as_millis() as u64, atomic::Ordering::SeqCst);
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> Duration {
+        Duration::from_millis(self.millis.load(atomic::Ordering::SeqCst))
+    }
+}
+
+impl Stopwatch {
+    /// A stopped stopwatch that has already counted some time.
+    pub fn new(elapsed: Duration) -> Self {
+        Self {
+            elapsed,
+            running_since: None,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running_since.is_some()
+    }
+
+    pub fn start(&mut self, now: Duration) {
+        self.running_since.get_or_insert(now);
+    }
+
+    pub fn stop(&mut self, now: Duration) {
+        self.elapsed = self.elapsed(now);
+        self.running_since = None;
+    }
+
+    pub fn elapsed(&self, now: Duration) -> Duration {
+        match self.running_since {
+            Some(running_since) => self.elapsed + now.saturating_sub(running_since),
+            None => self.elapsed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stopwatch_should_only_count_while_running() {
+        let clock = FakeClock::new();
+        let mut stopwatch = Stopwatch::default();
+
+        clock.advance(Duration::from_secs(5));
+        stopwatch.start(clock.now());
+        clock.advance(Duration::from_secs(10));
+        stopwatch.stop(clock.now());
+        clock.advance(Duration::from_secs(100));
+        stopwatch.start(clock.now());
+        clock.advance(Duration::from_secs(1));
+
+        assert_eq!(stopwatch.elapsed(clock.now()), Duration::from_secs(11));
+    }
+}
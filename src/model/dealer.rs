@@ -1,9 +1,16 @@
 use std::fmt::Debug;
 
+use crate::model::deck;
+
 pub trait Dealer: Debug + Clone {
     type Action;
     type Context<'a>;
     type Iter: Iterator<Item = Self::Action> + Clone;
 
     fn deal(&self, context: Self::Context<'_>) -> Self::Iter;
+
+    /// The cards to shuffle and deal. Most games are played with a single standard deck.
+    fn deck(&self, _context: Self::Context<'_>) -> deck::Deck {
+        deck::Deck::new()
+    }
 }
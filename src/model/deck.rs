@@ -1,7 +1,11 @@
-use std::fmt;
+use std::collections::BTreeMap;
+use std::{cmp, fmt, num, str};
 
+use enum_like::EnumValues as _;
 use itertools::Itertools as _;
 use rand::seq::SliceRandom as _;
+use rand::Rng as _;
+use snafu::{ResultExt as _, Snafu};
 
 use crate::model::card;
 
@@ -9,6 +13,16 @@ pub type IntoIter = <Vec<card::Card> as IntoIterator>::IntoIter;
 
 pub trait Shuffle: fmt::Debug {
     fn shuffle(&mut self, cards: &mut [card::Card]);
+
+    /// The seed used by the most recent shuffle, if that shuffle can be reproduced.
+    fn seed(&self) -> Option<u64> {
+        None
+    }
+
+    /// The most recent deal, named in the numbering this shuffle follows.
+    fn deal_number(&self) -> Option<DealNumber> {
+        self.seed().map(DealNumber::Seeded)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -16,12 +30,49 @@ pub struct Deck {
     cards: Vec<card::Card>,
 }
 
+#[derive(Debug, Snafu)]
+pub enum DeckError {
+    #[snafu(display("The deck has more than one {}", cards.iter().join(", ")))]
+    DuplicateCards { cards: Vec<card::CardFace> },
+    #[snafu(display("The deck is missing the {}", cards.iter().join(", the ")))]
+    MissingCards { cards: Vec<card::CardFace> },
+}
+
 impl Deck {
     pub fn new() -> Self {
         let cards = card::Card::values_face_down().collect_vec();
         Self { cards }
     }
 
+    /// A deck in exactly the given order, which must include every card once. The cards are face
+    /// down, and in the same order a shuffle would leave them in, so the last card is dealt first.
+    pub fn from_faces<I>(faces: I) -> Result<Self, DeckError>
+    where
+        I: IntoIterator<Item = card::CardFace>,
+    {
+        let faces = faces.into_iter().collect_vec();
+        check_faces(&faces)?;
+
+        Ok(Self {
+            cards: faces.into_iter().map(card::CardFace::face_down).collect(),
+        })
+    }
+
+    /// Several standard decks together, e.g., for games like Spider that are played with two.
+    pub fn new_multi(count: usize) -> Self {
+        Self::new_with_suits(&card::Suit::values().collect_vec(), count)
+    }
+
+    /// Every card of the given suits, each `copies` times over. Games played with fewer suits use
+    /// more copies to make up the numbers, e.g., one-suit Spider is eight copies of the Spades.
+    pub fn new_with_suits(suits: &[card::Suit], copies: usize) -> Self {
+        let cards = (0..copies)
+            .flat_map(|_| card::Card::values_face_down())
+            .filter(|card| suits.contains(&card.suit()))
+            .collect();
+        Self { cards }
+    }
+
     pub fn new_shuffled(shuffle: &mut dyn Shuffle) -> Self {
         let mut deck = Self::new();
         deck.shuffle(shuffle);
@@ -31,6 +82,14 @@ impl Deck {
     pub fn shuffle(&mut self, shuffle: &mut dyn Shuffle) {
         shuffle.shuffle(&mut self.cards);
     }
+
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
 }
 
 impl IntoIterator for Deck {
@@ -48,6 +107,28 @@ impl Default for Deck {
     }
 }
 
+/// Every card must appear exactly once.
+fn check_faces(faces: &[card::CardFace]) -> Result<(), DeckError> {
+    let mut counts = BTreeMap::new();
+    for &face in faces {
+        *counts.entry(face).or_insert(0) += 1;
+    }
+
+    let duplicates = counts
+        .iter()
+        .filter(|&(_, &count)| count > 1)
+        .map(|(&face, _)| face)
+        .collect_vec();
+    snafu::ensure!(duplicates.is_empty(), DuplicateCards { cards: duplicates });
+
+    let missing = card::CardFace::values()
+        .filter(|face| !counts.contains_key(face))
+        .collect_vec();
+    snafu::ensure!(missing.is_empty(), MissingCards { cards: missing });
+
+    Ok(())
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct NoShuffle;
 
@@ -72,3 +153,555 @@ impl Shuffle for UnShuffle {
         cards.sort();
     }
 }
+
+/// A shuffle that always puts the cards in the same order, e.g., to replay a deal from a puzzle or
+/// a bug report. Every shuffle produces the same deal.
+#[derive(Debug, Clone)]
+pub struct FixedShuffle {
+    /// The final position of each card.
+    positions: BTreeMap<card::CardFace, usize>,
+}
+
+impl FixedShuffle {
+    /// A shuffle that puts the cards in the given order, which must include every card once. As
+    /// with `Deck::from_faces`, the last card is dealt first.
+    pub fn new<I>(faces: I) -> Result<Self, DeckError>
+    where
+        I: IntoIterator<Item = card::CardFace>,
+    {
+        let faces = faces.into_iter().collect_vec();
+        check_faces(&faces)?;
+
+        let positions = faces
+            .into_iter()
+            .enumerate()
+            .map(|(position, face)| (face, position))
+            .collect();
+        Ok(Self { positions })
+    }
+}
+
+impl Shuffle for FixedShuffle {
+    fn shuffle(&mut self, cards: &mut [card::Card]) {
+        cards.sort_by_key(|card| self.positions[&card.face]);
+    }
+}
+
+/// A shuffle that can be reproduced from a 64-bit seed. The card order for a given seed is defined
+/// entirely by this module (a SplitMix64 generator driving a Fisher-Yates shuffle), so it will not
+/// change with dependency upgrades or across platforms.
+///
+/// Each shuffle advances to a new seed, so reusing a `SeededShuffle` produces a sequence of
+/// different (but still reproducible) deals. Use `seed` to find out which deal was last produced.
+#[derive(Debug, Clone, Copy)]
+pub struct SeededShuffle {
+    next_seed: u64,
+    seed: Option<u64>,
+}
+
+impl SeededShuffle {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            next_seed: seed,
+            seed: None,
+        }
+    }
+
+    pub fn new_random() -> Self {
+        Self::new(rand::thread_rng().gen())
+    }
+
+    /// Pick up where a shuffle with the given seed left off, i.e., as if `seed` had just been used
+    /// to shuffle.
+    pub fn resume(seed: u64) -> Self {
+        Self {
+            next_seed: following_seed(seed),
+            seed: Some(seed),
+        }
+    }
+}
+
+impl Shuffle for SeededShuffle {
+    fn shuffle(&mut self, cards: &mut [card::Card]) {
+        let mut rng = SplitMix64::new(self.next_seed);
+
+        for index in (1..cards.len()).rev() {
+            let other_index = rng.next_below(index as u64 + 1) as usize;
+            cards.swap(index, other_index);
+        }
+
+        self.seed = Some(self.next_seed);
+        self.next_seed = following_seed(self.next_seed);
+    }
+
+    fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+}
+
+/// Reproduces the deals of Microsoft FreeCell (and the other Microsoft games that share its
+/// numbering), so that well-known deals such as game #11982 can be played by number. The deal
+/// depends only on the game number, which, as in the original, is taken modulo 2^32. The original
+/// only went up to 2^31, since higher numbers repeat the lower deals; above that, the numbering
+/// follows FreeCell Pro's extension, which gives every number a deal of its own.
+///
+/// Each shuffle moves on to the next game number, the way the original's "next game" did.
+#[derive(Debug, Clone, Copy)]
+pub struct MicrosoftShuffle {
+    next_game: u32,
+    game: Option<u32>,
+}
+
+impl MicrosoftShuffle {
+    pub fn new(game: u32) -> Self {
+        Self {
+            next_game: game,
+            game: None,
+        }
+    }
+
+    /// Pick up where a shuffle of the given game left off.
+    pub fn resume(game: u32) -> Self {
+        Self {
+            next_game: game.wrapping_add(1),
+            game: Some(game),
+        }
+    }
+}
+
+impl Shuffle for MicrosoftShuffle {
+    fn shuffle(&mut self, cards: &mut [card::Card]) {
+        // Microsoft numbers the cards rank by rank, with the suits in the order Clubs, Diamonds,
+        // Hearts, Spades within each rank.
+        cards.sort_by_key(|card| (card.rank(), cmp::Reverse(card.suit())));
+
+        // The original picks a card at random from those left, deals it, and fills the gap with
+        // the last card, which is exactly `swap_remove`.
+        let mut rng = MicrosoftRng::new(self.next_game);
+        let mut remaining = cards.to_vec();
+        let mut dealt = Vec::with_capacity(cards.len());
+        while !remaining.is_empty() {
+            let index = rng.next_value() as usize % remaining.len();
+            dealt.push(remaining.swap_remove(index));
+        }
+
+        // Our dealers take cards from the end of the deck, so the first card dealt goes last.
+        dealt.reverse();
+        cards.clone_from_slice(&dealt);
+
+        self.game = Some(self.next_game);
+        self.next_game = self.next_game.wrapping_add(1);
+    }
+
+    fn seed(&self) -> Option<u64> {
+        self.game.map(u64::from)
+    }
+
+    fn deal_number(&self) -> Option<DealNumber> {
+        self.game.map(DealNumber::Microsoft)
+    }
+}
+
+/// Either of the shuffles that can be resumed from a deal number, so a saved game carries on with
+/// the same numbering it was dealt from.
+#[derive(Debug, Clone, Copy)]
+pub enum NumberedShuffle {
+    Seeded(SeededShuffle),
+    Microsoft(MicrosoftShuffle),
+}
+
+impl NumberedShuffle {
+    pub fn new_random() -> Self {
+        Self::Seeded(SeededShuffle::new_random())
+    }
+
+    /// Pick up where the shuffle that dealt the given deal left off.
+    pub fn resume(deal_number: DealNumber) -> Self {
+        match deal_number {
+            DealNumber::Seeded(seed) => Self::Seeded(SeededShuffle::resume(seed)),
+            DealNumber::Microsoft(game) => Self::Microsoft(MicrosoftShuffle::resume(game)),
+        }
+    }
+}
+
+/// A shuffle whose next deal is the given one.
+impl From<DealNumber> for NumberedShuffle {
+    fn from(deal_number: DealNumber) -> Self {
+        match deal_number {
+            DealNumber::Seeded(seed) => Self::Seeded(SeededShuffle::new(seed)),
+            DealNumber::Microsoft(game) => Self::Microsoft(MicrosoftShuffle::new(game)),
+        }
+    }
+}
+
+impl Shuffle for NumberedShuffle {
+    fn shuffle(&mut self, cards: &mut [card::Card]) {
+        match self {
+            Self::Seeded(shuffle) => shuffle.shuffle(cards),
+            Self::Microsoft(shuffle) => shuffle.shuffle(cards),
+        }
+    }
+
+    fn seed(&self) -> Option<u64> {
+        match self {
+            Self::Seeded(shuffle) => shuffle.seed(),
+            Self::Microsoft(shuffle) => shuffle.seed(),
+        }
+    }
+
+    fn deal_number(&self) -> Option<DealNumber> {
+        match self {
+            Self::Seeded(shuffle) => shuffle.deal_number(),
+            Self::Microsoft(shuffle) => shuffle.deal_number(),
+        }
+    }
+}
+
+/// The linear congruential generator behind the Microsoft C runtime's `rand`.
+#[derive(Debug, Clone, Copy)]
+struct MicrosoftRng {
+    state: u32,
+    /// Set on every value for games above 2^31. Without it, the top bit of the seed would never
+    /// make any difference to the values.
+    extension: u32,
+}
+
+impl MicrosoftRng {
+    fn new(seed: u32) -> Self {
+        Self {
+            state: seed,
+            extension: if seed >= 1 << 31 { 0x8000 } else { 0 },
+        }
+    }
+
+    fn next_value(&mut self) -> u32 {
+        self.state = self.state.wrapping_mul(214_013).wrapping_add(2_531_011);
+        ((self.state >> 16) & 0x7FFF) | self.extension
+    }
+}
+
+/// The seed for the deal after the one with the given seed.
+fn following_seed(seed: u64) -> u64 {
+    SplitMix64::new(!seed).next_u64()
+}
+
+/// The SplitMix64 generator, as published by Steele, Lea, and Flood. It's tiny, fast, and (most
+/// importantly for us) completely specified, so we can promise stable output forever.
+#[derive(Debug, Clone, Copy)]
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Generate a uniformly distributed value in `0..bound`. We reject values from the incomplete
+    /// final block of the `u64` range to avoid modulo bias.
+    fn next_below(&mut self, bound: u64) -> u64 {
+        let zone = u64::MAX - (u64::MAX % bound);
+
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+}
+
+/// A shareable name for a reproducible deal. Seeded deals display as `#` followed by the seed in
+/// decimal, and Microsoft's as `MS #` followed by the game number, so the two numberings can't be
+/// mistaken for each other. Both parse with or without the `#`.
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
+    derive_more::Display,
+    serde::Deserialize,
+    serde::Serialize,
+)]
+pub enum DealNumber {
+    #[display(fmt = "#{}", _0)]
+    Seeded(u64),
+    #[display(fmt = "MS #{}", _0)]
+    Microsoft(u32),
+}
+
+impl DealNumber {
+    /// The seed or game number, without saying which numbering it belongs to.
+    pub fn seed(self) -> u64 {
+        match self {
+            Self::Seeded(seed) => seed,
+            Self::Microsoft(game) => game.into(),
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum DealNumberError {
+    #[snafu(display("Invalid deal number {:?}: {}", input, source))]
+    InvalidDealNumber {
+        input: String,
+        source: num::ParseIntError,
+    },
+}
+
+impl str::FromStr for DealNumber {
+    type Err = DealNumberError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let trimmed = input.trim();
+        let without_hash = |text: &str| {
+            let text = text.trim_start();
+            text.strip_prefix('#').unwrap_or(text).to_owned()
+        };
+
+        match trimmed.get(..2) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("MS") => without_hash(&trimmed[2..])
+                .parse()
+                .map(Self::Microsoft)
+                .context(InvalidDealNumber { input }),
+            _ => without_hash(trimmed)
+                .parse()
+                .map(Self::Seeded)
+                .context(InvalidDealNumber { input }),
+        }
+    }
+}
+
+impl From<u64> for DealNumber {
+    fn from(seed: u64) -> Self {
+        Self::Seeded(seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::model::card::{Rank, Suit};
+
+    fn first_cards(seed: u64, count: usize) -> Vec<card::CardFace> {
+        Deck::new_shuffled(&mut SeededShuffle::new(seed))
+            .into_iter()
+            .take(count)
+            .map(|card| card.face)
+            .collect_vec()
+    }
+
+    #[test]
+    fn seeded_shuffle_should_be_reproducible() {
+        let first = Deck::new_shuffled(&mut SeededShuffle::new(42))
+            .into_iter()
+            .collect_vec();
+        let second = Deck::new_shuffled(&mut SeededShuffle::new(42))
+            .into_iter()
+            .collect_vec();
+
+        assert_eq!(first, second);
+    }
+
+    // These pin the card order so any change to the algorithm (which would break every deal
+    // number anyone has ever shared) fails loudly.
+    #[test_case(0, vec![
+        Rank::Queen.of(Suit::Diamonds),
+        Rank::Ten.of(Suit::Spades),
+        Rank::Three.of(Suit::Spades),
+        Rank::Jack.of(Suit::Spades),
+        Rank::Four.of(Suit::Diamonds),
+    ]; "seed 0")]
+    #[test_case(11982, vec![
+        Rank::Three.of(Suit::Hearts),
+        Rank::Four.of(Suit::Diamonds),
+        Rank::Seven.of(Suit::Clubs),
+        Rank::Five.of(Suit::Diamonds),
+        Rank::King.of(Suit::Spades),
+    ]; "seed 11982")]
+    fn seeded_shuffle_should_be_stable(seed: u64, expected: Vec<card::CardFace>) {
+        assert_eq!(first_cards(seed, 5), expected);
+    }
+
+    #[test]
+    fn split_mix_should_match_reference() {
+        let mut rng = SplitMix64::new(0);
+        assert_eq!(rng.next_u64(), 0xE220_A839_7B1D_CDAF);
+        assert_eq!(rng.next_u64(), 0x6E78_9E6A_A1B9_65F4);
+    }
+
+    #[test]
+    fn seeded_shuffle_should_advance_seed() {
+        let mut shuffle = SeededShuffle::new(7);
+        assert_eq!(shuffle.seed(), None);
+
+        Deck::new_shuffled(&mut shuffle);
+        assert_eq!(shuffle.seed(), Some(7));
+
+        Deck::new_shuffled(&mut shuffle);
+        assert_ne!(shuffle.seed(), Some(7));
+    }
+
+    #[test]
+    fn seeded_shuffle_should_resume() {
+        let mut shuffle = SeededShuffle::new(7);
+        Deck::new_shuffled(&mut shuffle);
+        let mut resumed = SeededShuffle::resume(7);
+
+        assert_eq!(
+            Deck::new_shuffled(&mut shuffle).into_iter().collect_vec(),
+            Deck::new_shuffled(&mut resumed).into_iter().collect_vec()
+        );
+    }
+
+    #[test]
+    fn deck_should_keep_given_order() {
+        let mut faces = card::CardFace::values().collect_vec();
+        faces.reverse();
+
+        let deck = Deck::from_faces(faces.iter().copied()).unwrap();
+        let shuffled = Deck::new_shuffled(&mut FixedShuffle::new(faces.iter().copied()).unwrap());
+
+        assert!(deck.clone().into_iter().all(|card| card.is_face_down()));
+        assert_eq!(deck.into_iter().map(|card| card.face).collect_vec(), faces);
+        assert_eq!(
+            shuffled.into_iter().map(|card| card.face).collect_vec(),
+            faces
+        );
+    }
+
+    #[test]
+    fn deck_should_report_duplicate_cards() {
+        let mut faces = card::CardFace::values().collect_vec();
+        faces[1] = faces[0];
+
+        assert_matches!(
+            Deck::from_faces(faces),
+            Err(DeckError::DuplicateCards { cards }) => {
+                assert_eq!(cards, vec![Rank::Ace.of(Suit::Spades)]);
+            }
+        );
+    }
+
+    #[test]
+    fn deck_should_report_missing_cards() {
+        let faces = card::CardFace::values().skip(2);
+
+        assert_matches!(
+            FixedShuffle::new(faces),
+            Err(DeckError::MissingCards { cards }) => {
+                assert_eq!(cards, vec![Rank::Ace.of(Suit::Spades), Rank::Ace.of(Suit::Hearts)]);
+            }
+        );
+    }
+
+    #[test_case(&[Suit::Spades], 8; "one suit")]
+    #[test_case(&[Suit::Spades, Suit::Hearts], 4; "two suits")]
+    #[test_case(&[Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs], 2; "four suits")]
+    fn deck_with_suits_should_have_copies_of_each_card(suits: &[Suit], copies: usize) {
+        let faces = Deck::new_with_suits(suits, copies)
+            .into_iter()
+            .map(|card| card.face)
+            .collect_vec();
+
+        assert_eq!(faces.len(), 13 * suits.len() * copies);
+        assert!(card::CardFace::values()
+            .filter(|face| suits.contains(&face.suit))
+            .all(|face| faces.iter().filter(|&&other| other == face).count() == copies));
+    }
+
+    // Microsoft's own layouts, read row by row across the eight FreeCell cascades.
+    #[test_case(1, "JD 2D 9H JC 5D 7H 7C 5H KD KC 9S 5S AD QC KH 3H 2S KS 9D QD JS AS AH 3C \
+                    4C 5C TS QH 4H AC 4D 7S 3S TD 4S TH 8H 2C JH 7D 6D 8S 8D QS 6C 3D 8C TC \
+                    6S 9C 2H 6H"; "game 1")]
+    #[test_case(617, "7D AD 5C 3S 5S 8C 2D AH TD 7S QD AC 6D 8H AS KH TH QC 3H 9D 6S 8D 3D TC \
+                      KD 5H 9S 3C 8S 7H 4D JS 4C QS 9C 9H 7C 6H 2C 2S 4S TS 2H 5D JC 6C JH QH \
+                      JD KS KC 4H"; "game 617")]
+    #[test_case(11982, "AH AS 4H AC 2D 6S TS JS 3D 3H QS QC 8S 7H AD KS KD 6H 5S 4D 9H JH 9S 3C \
+                        JC 5D 5C 8C 9D TD KH 7C 6C 2C TH QH 6D TC 4S 7S JD 7D 8H 9C 2H QD 4C 5H \
+                        KC 8D 2S 3S"; "game 11982")]
+    #[test_case(3_000_000_000, "8D 4D 9H 9D 6H 9C 6C 8C TS QS KH 5D 2S 7C 3H AH JS TH QH 8S 7H QC \
+                                8H 2H TD AD 4C 4H 3D 7S AC 5H JH 4S 5C KS KC QD 6D 2D JD TC KD 6S \
+                                2C 7D 3S 5S JC 3C AS 9S"; "game above 2^31")]
+    fn microsoft_shuffle_should_match_known_deals(game: u32, expected: &str) {
+        let expected = expected
+            .split_whitespace()
+            .map(|text| text.parse::<card::CardFace>().unwrap())
+            .collect_vec();
+
+        let mut dealt = Deck::new_shuffled(&mut MicrosoftShuffle::new(game))
+            .into_iter()
+            .map(|card| card.face)
+            .collect_vec();
+        dealt.reverse();
+
+        assert_eq!(dealt, expected);
+    }
+
+    #[test]
+    fn microsoft_shuffle_should_advance_game() {
+        let mut shuffle = MicrosoftShuffle::new(11982);
+        Deck::new_shuffled(&mut shuffle);
+        assert_eq!(shuffle.seed(), Some(11982));
+
+        let next = Deck::new_shuffled(&mut shuffle).into_iter().collect_vec();
+        let resumed = Deck::new_shuffled(&mut MicrosoftShuffle::resume(11982))
+            .into_iter()
+            .collect_vec();
+        assert_eq!(shuffle.seed(), Some(11983));
+        assert_eq!(next, resumed);
+    }
+
+    #[test]
+    fn numbered_shuffle_should_resume_microsoft_deals() {
+        let mut shuffle = MicrosoftShuffle::new(11982);
+        Deck::new_shuffled(&mut shuffle);
+        let mut resumed = NumberedShuffle::resume(shuffle.deal_number().unwrap());
+
+        assert_eq!(
+            Deck::new_shuffled(&mut resumed).into_iter().collect_vec(),
+            Deck::new_shuffled(&mut shuffle).into_iter().collect_vec()
+        );
+        assert_eq!(resumed.deal_number(), Some(DealNumber::Microsoft(11983)));
+    }
+
+    #[test_case("12345" => DealNumber::Seeded(12345); "bare")]
+    #[test_case("#12345" => DealNumber::Seeded(12345); "with hash")]
+    #[test_case(" #0 " => DealNumber::Seeded(0); "with whitespace")]
+    #[test_case("MS #11982" => DealNumber::Microsoft(11982); "microsoft")]
+    #[test_case("ms11982" => DealNumber::Microsoft(11982); "microsoft without hash")]
+    fn deal_number_should_parse(input: &str) -> DealNumber {
+        input.parse().unwrap()
+    }
+
+    #[test_case(DealNumber::Seeded(u64::MAX); "seeded")]
+    #[test_case(DealNumber::Microsoft(u32::MAX); "microsoft")]
+    fn deal_number_should_round_trip(deal_number: DealNumber) {
+        assert_eq!(
+            deal_number.to_string().parse::<DealNumber>().unwrap(),
+            deal_number
+        );
+    }
+
+    #[test_case("#12a"; "not a number")]
+    #[test_case("MS #4294967296"; "microsoft game too big")]
+    fn deal_number_should_reject_garbage(input: &str) {
+        assert!(input.parse::<DealNumber>().is_err());
+    }
+}
@@ -1,18 +1,32 @@
-use std::convert;
+use std::error::Error;
+use std::sync::Arc;
+use std::{convert, mem, time};
+
+use snafu::{OptionExt as _, Snafu};
 
 use crate::model::action::Actionable as _;
-use crate::model::{action, dealer, deck, rules, table};
+use crate::model::{action, clock, dealer, deck, history, rules, scoring, table};
 
 #[derive(Debug, Clone)]
 pub struct Game<D, R, S, SH, T>
 where
     D: dealer::Dealer,
+    D::Action: action::Invertible<T>,
 {
+    clock: Arc<dyn clock::Clock>,
+    deal_number: Option<deck::DealNumber>,
     dealer: D,
     dealer_iter: Option<D::Iter>,
+    history: history::History<GameHistoryEntry<D, T>>,
+    moves: usize,
+    /// Whether the player has stopped the clock. It stays stopped until they resume, whatever else
+    /// happens to the game.
+    paused: bool,
+    score: i64,
     settings: S,
     shuffle: SH,
     started: bool,
+    stopwatch: clock::Stopwatch,
     table_guard: rules::RulesGuard<R, T>,
 }
 
@@ -29,30 +43,217 @@ pub struct GameRulesContext<'a, S, T> {
     pub table: &'a T,
 }
 
+/// Everything needed to recreate a game in its current position, apart from the parts (dealer,
+/// rules, and shuffle) that have no state of their own. The undo history is not included.
 #[derive(Debug, Clone)]
+pub struct GameSnapshot<DI, S, T> {
+    pub deal_number: Option<deck::DealNumber>,
+    pub dealer_iter: Option<DI>,
+    /// The time spent playing so far.
+    pub elapsed: time::Duration,
+    pub moves: usize,
+    /// Whether the clock was stopped on a game that was still being played.
+    pub paused: bool,
+    pub score: i64,
+    pub settings: S,
+    pub started: bool,
+    pub table: T,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, derive_more::Display)]
+pub enum GameStatus {
+    #[display(fmt = "In progress")]
+    InProgress,
+    #[display(fmt = "Ready to finish")]
+    AutoCompletable,
+    #[display(fmt = "Won")]
+    Won,
+    #[display(fmt = "Stuck")]
+    Stuck,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
 pub enum GameAction {
     Clear,
     Start,
 }
 
 #[derive(Debug, Clone)]
-pub struct TableAction<A>(A);
+pub struct TableAction<A>(pub A);
+
+/// A table action taken on the player's behalf because of the action before it, e.g., playing a
+/// card to a foundation automatically. It's undone and redone along with that action.
+#[derive(Debug, Clone)]
+pub struct FollowUpAction<A>(pub A);
 
 #[derive(Debug, Clone)]
 pub struct DealAction;
 
+/// A record of something that happened to the game, with enough information to both revert and
+/// replay it. Entries that need to restore game state (rather than apply an inverse action) hold
+/// on to the *other* version of that state, so reverting and replaying are both just a swap.
+#[derive(Debug, Clone)]
+enum GameHistoryEntry<D, T>
+where
+    D: dealer::Dealer,
+    D::Action: action::Invertible<T>,
+{
+    Table {
+        action: D::Action,
+        inverse: <D::Action as action::Invertible<T>>::Inverse,
+        points: i64,
+        follow_up: bool,
+    },
+    Deal {
+        action: D::Action,
+        inverse: <D::Action as action::Invertible<T>>::Inverse,
+        dealer_iter: Option<D::Iter>,
+    },
+    Clear {
+        table: T,
+        dealer_iter: Option<D::Iter>,
+        moves: usize,
+        score: i64,
+        deal_number: Option<deck::DealNumber>,
+        paused: bool,
+        started: bool,
+        stopwatch: clock::Stopwatch,
+    },
+    Start {
+        started: bool,
+    },
+}
+
+impl<D, T> GameHistoryEntry<D, T>
+where
+    D: dealer::Dealer,
+    D::Action: action::Invertible<T>,
+{
+    fn is_follow_up(&self) -> bool {
+        matches!(
+            self,
+            GameHistoryEntry::Table {
+                follow_up: true,
+                ..
+            }
+        )
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum GameHistoryError<AE, IE>
+where
+    AE: Error + 'static,
+    IE: Error + 'static,
+{
+    #[snafu(display("There is nothing to undo"))]
+    NothingToUndo,
+    #[snafu(display("There is nothing to redo"))]
+    NothingToRedo,
+    #[snafu(display("Unable to undo: {}", source))]
+    UndoFailed { source: IE },
+    #[snafu(display("Unable to redo: {}", source))]
+    RedoFailed { source: AE },
+}
+
+pub type GameHistoryResult<D, T> =
+    Result<
+        (),
+        GameHistoryError<
+            <<D as dealer::Dealer>::Action as action::Action<T>>::Error,
+            <<<D as dealer::Dealer>::Action as action::Invertible<T>>::Inverse as action::Action<
+                T,
+            >>::Error,
+        >,
+    >;
+
+type GameTableResult<D, R, T> = Result<
+    (),
+    rules::RulesGuardError<
+        <R as rules::Rules<<D as dealer::Dealer>::Action>>::Error,
+        <<D as dealer::Dealer>::Action as action::Action<T>>::Error,
+        <D as dealer::Dealer>::Action,
+    >,
+>;
+
 impl<D, R, S, SH, T> Game<D, R, S, SH, T>
 where
     D: dealer::Dealer,
+    D::Action: action::Invertible<T>,
+    T: table::Table,
 {
     pub fn is_started(&self) -> bool {
         self.started
     }
 
+    /// Whether the dealer has finished dealing. This is false until dealing has begun.
+    pub fn is_dealt(&self) -> bool {
+        self.dealer_iter
+            .as_ref()
+            .map(|dealer_iter| dealer_iter.clone().next().is_none())
+            .unwrap_or_default()
+    }
+
+    /// How many table actions have been taken since the deal, not counting any that were undone.
+    pub fn moves(&self) -> usize {
+        self.moves
+    }
+
+    /// The points earned by the actions taken so far, without any for time (see `total_score`).
+    pub fn score(&self) -> i64 {
+        self.score
+    }
+
+    /// The time spent playing, which only counts while the game is started and not paused or won.
+    pub fn elapsed(&self) -> time::Duration {
+        self.stopwatch.elapsed(self.clock.now())
+    }
+
+    /// Whether the clock has been stopped on a game that's still being played. A won game isn't
+    /// paused; its clock has simply stopped for good.
+    pub fn is_paused(&self) -> bool {
+        self.paused && self.started && !self.table().is_won()
+    }
+
+    /// Stop the clock until the game is resumed. Actions taken in the meantime don't restart it.
+    /// A game that isn't being played has no clock running to pause.
+    pub fn pause(&mut self) {
+        if self.started && !self.table().is_won() {
+            self.paused = true;
+            self.sync_stopwatch();
+        }
+    }
+
+    /// Restart the clock, if the game has started and hasn't been won.
+    pub fn resume(&mut self) {
+        self.paused = false;
+        self.sync_stopwatch();
+    }
+
+    /// Measure time with a different clock from now on, keeping the time counted so far.
+    pub fn set_clock(&mut self, clock: Arc<dyn clock::Clock>) {
+        let running = self.stopwatch.is_running();
+        self.stopwatch = clock::Stopwatch::new(self.elapsed());
+        self.clock = clock;
+        if running {
+            self.stopwatch.start(self.clock.now());
+        }
+    }
+
     pub fn rules(&self) -> &R {
         self.table_guard.rules()
     }
 
+    /// The seed of the current deal, if the shuffle that produced it can be reproduced. This is in
+    /// whichever numbering the shuffle follows; see `deal_number` to tell them apart.
+    pub fn seed(&self) -> Option<u64> {
+        self.deal_number.map(deck::DealNumber::seed)
+    }
+
+    pub fn deal_number(&self) -> Option<deck::DealNumber> {
+        self.deal_number
+    }
+
     pub fn settings(&self) -> &S {
         &self.settings
     }
@@ -60,35 +261,362 @@ where
     pub fn table(&self) -> &T {
         self.table_guard.target()
     }
+
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    /// The maximum number of actions that may be undone.
+    pub fn history_depth(&self) -> usize {
+        self.history.depth()
+    }
+
+    pub fn set_history_depth(&mut self, depth: usize) {
+        self.history.set_depth(depth);
+    }
+
+    /// Revert the most recently applied action, along with any follow-up actions taken after it.
+    /// If reverting fails, the action stays in the history.
+    pub fn undo(&mut self) -> GameHistoryResult<D, T> {
+        let mut entry = self.history.take_undo().context(NothingToUndo)?;
+
+        loop {
+            if let Err(error) = self.revert(&mut entry) {
+                self.history.push_undo(entry);
+                return Err(error);
+            }
+
+            let follow_up = entry.is_follow_up();
+            self.history.push_redo(entry);
+            if !follow_up {
+                return Ok(());
+            }
+
+            // The action that led to the follow-ups may have been forgotten already.
+            match self.history.take_undo() {
+                Some(previous_entry) => entry = previous_entry,
+                None => return Ok(()),
+            }
+        }
+    }
+
+    /// Replay the most recently undone action, along with any follow-up actions taken after it.
+    /// If replaying fails, the action stays available to redo.
+    pub fn redo(&mut self) -> GameHistoryResult<D, T> {
+        let mut entry = self.history.take_redo().context(NothingToRedo)?;
+
+        loop {
+            if let Err(error) = self.replay(&mut entry) {
+                self.history.push_redo(entry);
+                return Err(error);
+            }
+
+            self.history.push_undo(entry);
+            match self.history.take_redo() {
+                Some(next_entry) if next_entry.is_follow_up() => entry = next_entry,
+                Some(next_entry) => {
+                    self.history.push_redo(next_entry);
+                    return Ok(());
+                }
+                None => return Ok(()),
+            }
+        }
+    }
+
+    fn revert(&mut self, entry: &mut GameHistoryEntry<D, T>) -> GameHistoryResult<D, T> {
+        match entry {
+            GameHistoryEntry::Table {
+                inverse, points, ..
+            } => {
+                self.table_guard
+                    .apply_unguarded(inverse.clone())
+                    .map_err(|source| GameHistoryError::UndoFailed { source })?;
+                self.moves -= 1;
+                self.score -= *points;
+                self.sync_stopwatch();
+            }
+            GameHistoryEntry::Deal {
+                inverse,
+                dealer_iter,
+                ..
+            } => {
+                self.table_guard
+                    .apply_unguarded(inverse.clone())
+                    .map_err(|source| GameHistoryError::UndoFailed { source })?;
+                mem::swap(&mut self.dealer_iter, dealer_iter);
+            }
+            GameHistoryEntry::Clear { .. } | GameHistoryEntry::Start { .. } => {
+                self.swap_state(entry);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn replay(&mut self, entry: &mut GameHistoryEntry<D, T>) -> GameHistoryResult<D, T> {
+        match entry {
+            GameHistoryEntry::Table { action, points, .. } => {
+                self.table_guard
+                    .apply_unguarded(action.clone())
+                    .map_err(|source| GameHistoryError::RedoFailed { source })?;
+                self.moves += 1;
+                self.score += *points;
+                self.sync_stopwatch();
+            }
+            GameHistoryEntry::Deal {
+                action,
+                dealer_iter,
+                ..
+            } => {
+                self.table_guard
+                    .apply_unguarded(action.clone())
+                    .map_err(|source| GameHistoryError::RedoFailed { source })?;
+                mem::swap(&mut self.dealer_iter, dealer_iter);
+            }
+            GameHistoryEntry::Clear { .. } | GameHistoryEntry::Start { .. } => {
+                self.swap_state(entry);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn swap_state(&mut self, entry: &mut GameHistoryEntry<D, T>) {
+        let now = self.clock.now();
+
+        match entry {
+            GameHistoryEntry::Clear {
+                table,
+                dealer_iter,
+                moves,
+                score,
+                deal_number,
+                paused,
+                started,
+                stopwatch,
+            } => {
+                // The game being put away keeps its time, but not a running clock.
+                self.stopwatch.stop(now);
+                self.table_guard.swap_target(table);
+                mem::swap(&mut self.dealer_iter, dealer_iter);
+                mem::swap(&mut self.moves, moves);
+                mem::swap(&mut self.score, score);
+                mem::swap(&mut self.deal_number, deal_number);
+                mem::swap(&mut self.paused, paused);
+                mem::swap(&mut self.started, started);
+                mem::swap(&mut self.stopwatch, stopwatch);
+            }
+            GameHistoryEntry::Start { started } => {
+                mem::swap(&mut self.started, started);
+            }
+            GameHistoryEntry::Table { .. } | GameHistoryEntry::Deal { .. } => {}
+        }
+
+        self.sync_stopwatch();
+    }
+
+    /// Run the clock exactly when the game is being played: started, not paused, and not yet won.
+    fn sync_stopwatch(&mut self) {
+        let now = self.clock.now();
+
+        if self.started && !self.paused && !self.table().is_won() {
+            self.stopwatch.start(now);
+        } else {
+            self.stopwatch.stop(now);
+        }
+    }
 }
 
 impl<D, R, S, SH, T> Game<D, R, S, SH, T>
 where
     D: dealer::Dealer,
+    D::Action: action::Invertible<T>,
+    for<'a> D::Context<'a>: From<GameDealerContext<'a, S>>,
+    S: scoring::ScoringScheme<D::Action, T>,
     SH: deck::Shuffle,
     T: table::Table,
 {
+    pub fn from_snapshot(
+        dealer: D,
+        rules: R,
+        shuffle: SH,
+        snapshot: GameSnapshot<D::Iter, S, T>,
+    ) -> Self {
+        let clock = Arc::new(clock::SystemClock::new());
+        let mut stopwatch = clock::Stopwatch::new(snapshot.elapsed);
+        let playing = snapshot.started && !snapshot.table.is_won();
+        if playing && !snapshot.paused {
+            stopwatch.start(clock::Clock::now(&*clock));
+        }
+
+        Self {
+            clock,
+            deal_number: snapshot.deal_number,
+            dealer,
+            dealer_iter: snapshot.dealer_iter,
+            history: Default::default(),
+            moves: snapshot.moves,
+            paused: playing && snapshot.paused,
+            score: snapshot.score,
+            settings: snapshot.settings,
+            shuffle,
+            started: snapshot.started,
+            stopwatch,
+            table_guard: rules::RulesGuard::new(rules, snapshot.table),
+        }
+    }
+
+    pub fn snapshot(&self) -> GameSnapshot<D::Iter, S, T>
+    where
+        S: Clone,
+    {
+        GameSnapshot {
+            deal_number: self.deal_number,
+            dealer_iter: self.dealer_iter.clone(),
+            elapsed: self.elapsed(),
+            moves: self.moves,
+            paused: self.is_paused(),
+            score: self.score,
+            settings: self.settings.clone(),
+            started: self.started,
+            table: self.table().clone(),
+        }
+    }
+
     // TODO: Possibly replace with a builder
     pub fn new(dealer: D, rules: R, settings: S, mut shuffle: SH) -> Self {
-        let deck = deck::Deck::new_shuffled(&mut shuffle);
+        let mut deck = dealer.deck(D::Context::from(GameDealerContext {
+            settings: &settings,
+            started: false,
+        }));
+        deck.shuffle(&mut shuffle);
+        let deal_number = shuffle.deal_number();
+        let score = settings.initial_score();
         let table = table::Table::new_with_cards(deck);
 
         let table_guarded = rules::RulesGuard::new(rules, table);
 
         Self {
+            clock: Arc::new(clock::SystemClock::new()),
+            deal_number,
             dealer,
             settings,
             shuffle,
             table_guard: table_guarded,
             dealer_iter: None,
+            history: Default::default(),
+            moves: 0,
+            paused: false,
+            score,
             started: false,
+            stopwatch: Default::default(),
+        }
+    }
+}
+
+impl<D, R, S, SH, T> Game<D, R, S, SH, T>
+where
+    D: dealer::Dealer,
+    D::Action: action::Invertible<T>,
+    S: scoring::ScoringScheme<D::Action, T>,
+    T: table::Table,
+{
+    /// The score including any points the settings award (or take away) for time.
+    pub fn total_score(&self) -> i64 {
+        self.score
+            + self
+                .settings
+                .time_points(self.elapsed(), self.table().is_won())
+    }
+}
+
+impl<D, R, S, SH, T> Game<D, R, S, SH, T>
+where
+    D: dealer::Dealer,
+    D::Action: action::Invertible<T>,
+    R: rules::Rules<D::Action>,
+    for<'a> R::Context<'a>: From<GameRulesContext<'a, S, T>>,
+{
+    /// How the game is going. A game that hasn't started yet is always in progress.
+    pub fn status(&self) -> GameStatus
+    where
+        T: table::Table,
+    {
+        let table = self.table();
+
+        if table.is_won() {
+            return GameStatus::Won;
         }
+        if !self.started {
+            return GameStatus::InProgress;
+        }
+        if table.is_auto_completable() {
+            return GameStatus::AutoCompletable;
+        }
+
+        let context = R::Context::from(GameRulesContext {
+            settings: &self.settings,
+            started: self.started,
+            table,
+        });
+        if self.rules().is_stuck(&context) {
+            GameStatus::Stuck
+        } else {
+            GameStatus::InProgress
+        }
+    }
+
+    fn apply_guarded(&mut self, action: D::Action) -> GameTableResult<D, R, T> {
+        // The context borrows the table, so we have to be done with it before we can apply the
+        // action.
+        {
+            let context = R::Context::from(GameRulesContext {
+                settings: &self.settings,
+                started: self.started,
+                table: self.table_guard.target(),
+            });
+            self.table_guard.validate(&action, &context)?;
+        }
+
+        self.table_guard
+            .apply_unguarded(action.clone())
+            .map_err(|source| rules::RulesGuardError::ActionError { action, source })
+    }
+}
+
+impl<A, D, R, S, SH, T> Game<D, R, S, SH, T>
+where
+    A: action::Invertible<T>,
+    D: dealer::Dealer<Action = A>,
+    for<'a> D::Context<'a>: From<GameDealerContext<'a, S>>,
+    R: rules::Rules<A>,
+    for<'a> R::Context<'a>: From<GameRulesContext<'a, S, T>>,
+    S: scoring::ScoringScheme<A, T>,
+    SH: deck::Shuffle,
+    T: table::Table,
+{
+    /// Deal whatever is left of the deal and start the game, the way a new game usually begins.
+    pub fn deal_and_start(&mut self) -> GameTableResult<D, R, T> {
+        while !self.is_dealt() {
+            self.apply(DealAction)?;
+        }
+        self.apply(GameAction::Start)
+            .unwrap_or_else(|never| match never {});
+
+        Ok(())
     }
 }
 
 impl<D, R, S, SH, T> action::Action<Game<D, R, S, SH, T>> for GameAction
 where
     D: for<'a> dealer::Dealer,
+    D::Action: action::Invertible<T>,
+    for<'a> D::Context<'a>: From<GameDealerContext<'a, S>>,
+    S: scoring::ScoringScheme<D::Action, T>,
     SH: deck::Shuffle,
     T: table::Table,
 {
@@ -97,17 +625,41 @@ where
     fn apply_to(self, target: &mut Game<D, R, S, SH, T>) -> Result<(), Self::Error> {
         match self {
             Self::Clear => {
-                target.dealer_iter = None;
+                let dealer_iter = target.dealer_iter.take();
 
-                let deck = deck::Deck::new_shuffled(&mut target.shuffle);
-                target
+                let mut deck = target.dealer.deck(D::Context::from(GameDealerContext {
+                    settings: &target.settings,
+                    started: false,
+                }));
+                deck.shuffle(&mut target.shuffle);
+                let table = target
                     .table_guard
-                    .set_target(table::Table::new_with_cards(deck));
+                    .replace_target(table::Table::new_with_cards(deck));
+
+                let moves = mem::take(&mut target.moves);
+                let score = mem::replace(&mut target.score, target.settings.initial_score());
+                let deal_number =
+                    mem::replace(&mut target.deal_number, target.shuffle.deal_number());
+                let paused = mem::take(&mut target.paused);
+                let started = mem::replace(&mut target.started, false);
+                target.stopwatch.stop(target.clock.now());
+                let stopwatch = mem::take(&mut target.stopwatch);
 
-                target.started = false;
+                target.history.record(GameHistoryEntry::Clear {
+                    table,
+                    dealer_iter,
+                    moves,
+                    score,
+                    deal_number,
+                    paused,
+                    started,
+                    stopwatch,
+                });
             }
             Self::Start => {
-                target.started = true;
+                let started = mem::replace(&mut target.started, true);
+                target.sync_stopwatch();
+                target.history.record(GameHistoryEntry::Start { started });
             }
         }
 
@@ -115,12 +667,13 @@ where
     }
 }
 
-impl<A, D, R, RC, S, SH, T> action::Action<Game<D, R, S, SH, T>> for TableAction<A>
+impl<A, D, R, S, SH, T> action::Action<Game<D, R, S, SH, T>> for TableAction<A>
 where
-    A: action::Action<T>,
-    D: dealer::Dealer,
-    R: for<'a> rules::Rules<A, Context<'a> = RC>,
-    RC: for<'a> From<GameRulesContext<'a, S, T>>,
+    A: action::Invertible<T>,
+    D: dealer::Dealer<Action = A>,
+    R: rules::Rules<A>,
+    for<'a> R::Context<'a>: From<GameRulesContext<'a, S, T>>,
+    S: scoring::ScoringScheme<A, T>,
     SH: deck::Shuffle,
     T: table::Table,
 {
@@ -128,22 +681,66 @@ where
 
     fn apply_to(self, target: &mut Game<D, R, S, SH, T>) -> Result<(), Self::Error> {
         let TableAction(action) = self;
-        let context = RC::from(GameRulesContext {
-            settings: &target.settings,
-            started: target.started,
-            table: target.table_guard.target(),
-        });
-        target.table_guard.apply_guarded(action, &context)
+        apply_table_action(target, action, false)
     }
 }
 
-impl<A, D, DC, R, RC, S, SH, T> action::Action<Game<D, R, S, SH, T>> for DealAction
+impl<A, D, R, S, SH, T> action::Action<Game<D, R, S, SH, T>> for FollowUpAction<A>
 where
-    A: action::Action<T>,
-    D: for<'a> dealer::Dealer<Action = A, Context<'a> = DC>,
-    DC: for<'a> From<GameDealerContext<'a, S>>,
-    R: for<'a> rules::Rules<A, Context<'a> = RC>,
-    RC: for<'a> From<GameRulesContext<'a, S, T>>,
+    A: action::Invertible<T>,
+    D: dealer::Dealer<Action = A>,
+    R: rules::Rules<A>,
+    for<'a> R::Context<'a>: From<GameRulesContext<'a, S, T>>,
+    S: scoring::ScoringScheme<A, T>,
+    SH: deck::Shuffle,
+    T: table::Table,
+{
+    type Error = rules::RulesGuardError<R::Error, A::Error, A>;
+
+    fn apply_to(self, target: &mut Game<D, R, S, SH, T>) -> Result<(), Self::Error> {
+        let FollowUpAction(action) = self;
+        apply_table_action(target, action, true)
+    }
+}
+
+fn apply_table_action<A, D, R, S, SH, T>(
+    target: &mut Game<D, R, S, SH, T>,
+    action: A,
+    follow_up: bool,
+) -> Result<(), rules::RulesGuardError<R::Error, A::Error, A>>
+where
+    A: action::Invertible<T>,
+    D: dealer::Dealer<Action = A>,
+    R: rules::Rules<A>,
+    for<'a> R::Context<'a>: From<GameRulesContext<'a, S, T>>,
+    S: scoring::ScoringScheme<A, T>,
+    SH: deck::Shuffle,
+    T: table::Table,
+{
+    let inverse = action.inverse(target.table());
+    let points = target.settings.points(&action, target.table());
+
+    target.apply_guarded(action.clone())?;
+    target.moves += 1;
+    target.score += points;
+    target.sync_stopwatch();
+    target.history.record(GameHistoryEntry::Table {
+        action,
+        inverse,
+        points,
+        follow_up,
+    });
+
+    Ok(())
+}
+
+impl<A, D, R, S, SH, T> action::Action<Game<D, R, S, SH, T>> for DealAction
+where
+    A: action::Invertible<T>,
+    D: dealer::Dealer<Action = A>,
+    for<'a> D::Context<'a>: From<GameDealerContext<'a, S>>,
+    R: rules::Rules<A>,
+    for<'a> R::Context<'a>: From<GameRulesContext<'a, S, T>>,
     SH: deck::Shuffle,
     T: table::Table,
 {
@@ -152,16 +749,28 @@ where
     fn apply_to(self, target: &mut Game<D, R, S, SH, T>) -> Result<(), Self::Error> {
         let dealer = &target.dealer;
 
-        let context = DC::from(GameDealerContext {
+        let context = D::Context::from(GameDealerContext {
             settings: &target.settings,
             started: target.started,
         });
-        let dealer_iter = target
+        // Deal from a copy, so a step the rules reject can be tried again.
+        let mut dealer_iter = target
             .dealer_iter
-            .get_or_insert_with(|| dealer.deal(context));
+            .clone()
+            .unwrap_or_else(|| dealer.deal(context));
+
+        if let Some(action) = dealer_iter.next() {
+            let inverse = action.inverse(target.table());
 
-        if let Some(table_action) = dealer_iter.next() {
-            target.apply(TableAction(table_action))?;
+            target.apply_guarded(action.clone())?;
+            let previous_dealer_iter = target.dealer_iter.replace(dealer_iter);
+            target.history.record(GameHistoryEntry::Deal {
+                action,
+                inverse,
+                dealer_iter: previous_dealer_iter,
+            });
+        } else {
+            target.dealer_iter = Some(dealer_iter);
         }
 
         Ok(())
@@ -0,0 +1,138 @@
+//! A bounded undo/redo history. The history doesn't know how to undo or redo anything itself; it
+//! only keeps track of entries in the right order. The owner of the history is responsible for
+//! reverting and replaying the entries it takes out.
+
+use std::collections::VecDeque;
+
+pub const DEFAULT_DEPTH: usize = 1000;
+
+#[derive(Debug, Clone)]
+pub struct History<E> {
+    depth: usize,
+    undo_stack: VecDeque<E>,
+    redo_stack: Vec<E>,
+}
+
+impl<E> History<E> {
+    pub fn new(depth: usize) -> Self {
+        Self {
+            depth,
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Change the maximum number of entries that may be undone. If there are more entries than
+    /// the new depth allows, the oldest entries are forgotten.
+    pub fn set_depth(&mut self, depth: usize) {
+        self.depth = depth;
+        self.trim();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    pub fn undo_len(&self) -> usize {
+        self.undo_stack.len()
+    }
+
+    pub fn redo_len(&self) -> usize {
+        self.redo_stack.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+
+    /// Record a newly applied entry. Recording a new entry invalidates anything that could have
+    /// been redone.
+    pub fn record(&mut self, entry: E) {
+        self.redo_stack.clear();
+        self.undo_stack.push_back(entry);
+        self.trim();
+    }
+
+    /// Take the most recent entry to be undone. Once it has been reverted, the caller should hand
+    /// it back with `push_redo` (or with `push_undo` if reverting failed).
+    pub fn take_undo(&mut self) -> Option<E> {
+        self.undo_stack.pop_back()
+    }
+
+    /// Take the most recently undone entry to be redone. Once it has been replayed, the caller
+    /// should hand it back with `push_undo` (or with `push_redo` if replaying failed).
+    pub fn take_redo(&mut self) -> Option<E> {
+        self.redo_stack.pop()
+    }
+
+    pub fn push_undo(&mut self, entry: E) {
+        self.undo_stack.push_back(entry);
+        self.trim();
+    }
+
+    pub fn push_redo(&mut self, entry: E) {
+        self.redo_stack.push(entry);
+    }
+
+    fn trim(&mut self) {
+        while self.undo_stack.len() > self.depth {
+            self.undo_stack.pop_front();
+        }
+    }
+}
+
+impl<E> Default for History<E> {
+    fn default() -> Self {
+        Self::new(DEFAULT_DEPTH)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_should_undo_in_reverse_order() {
+        let mut history = History::default();
+        history.record(1);
+        history.record(2);
+
+        assert_eq!(history.take_undo(), Some(2));
+        assert_eq!(history.take_undo(), Some(1));
+        assert_eq!(history.take_undo(), None);
+    }
+
+    #[test]
+    fn history_should_forget_redo_after_record() {
+        let mut history = History::default();
+        history.record(1);
+        let entry = history.take_undo().unwrap();
+        history.push_redo(entry);
+        assert!(history.can_redo());
+
+        history.record(2);
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn history_should_forget_oldest_beyond_depth() {
+        let mut history = History::new(2);
+        history.record(1);
+        history.record(2);
+        history.record(3);
+
+        assert_eq!(history.undo_len(), 2);
+        assert_eq!(history.take_undo(), Some(3));
+        assert_eq!(history.take_undo(), Some(2));
+        assert_eq!(history.take_undo(), None);
+    }
+}
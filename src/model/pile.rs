@@ -7,7 +7,8 @@ use crate::model::card;
 pub type Iter<'a> = <&'a [card::Card] as IntoIterator>::IntoIter;
 pub type IntoIter = <Vec<card::Card> as IntoIterator>::IntoIter;
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(transparent)]
 pub struct Pile {
     cards: Vec<card::Card>,
 }
@@ -26,12 +27,12 @@ impl Pile {
         pile
     }
 
-    pub fn iter(&self) -> Iter {
+    pub fn iter(&self) -> Iter<'_> {
         self.cards.iter()
     }
 
     pub fn top_card(&self) -> Option<&card::Card> {
-        self.cards.first()
+        self.cards.last()
     }
 
     pub fn top_cards(&self, count: usize) -> &[card::Card] {
@@ -87,11 +88,13 @@ impl Pile {
     }
 
     pub fn flip_top(&mut self) {
-        self.cards.first_mut().map(card::Card::reverse);
+        if let Some(top_card) = self.cards.last_mut() {
+            top_card.reverse();
+        }
     }
 
     pub fn flip_top_to(&mut self, facing: card::Facing) {
-        if let Some(top_card) = self.cards.first_mut() {
+        if let Some(top_card) = self.cards.last_mut() {
             top_card.facing = facing;
         }
     }
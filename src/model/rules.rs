@@ -1,5 +1,6 @@
 use std::error::Error;
 use std::fmt::Debug;
+use std::mem;
 
 use snafu::{ResultExt as _, Snafu};
 
@@ -11,6 +12,12 @@ pub trait Rules<A>: Debug + Clone {
     type Error: Debug + Error + 'static;
 
     fn validate(&self, action: &A, context: &Self::Context<'_>) -> Result<(), Self::Error>;
+
+    /// Whether there's nothing left to do but go round in circles, e.g., by drawing through the
+    /// same stock again and again. Rules that can't tell should say no.
+    fn is_stuck(&self, _context: &Self::Context<'_>) -> bool {
+        false
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -43,14 +50,36 @@ impl<R, T> RulesGuard<R, T> {
         A: action::Action<T>,
         R: Rules<A>,
     {
-        self.rules.validate(&action, context).context(RuleError {
-            action: action.clone(),
-        })?;
+        self.validate(&action, context)?;
         self.target
             .apply(action.clone())
             .context(ActionError { action })
     }
 
+    /// Check an action against the rules without applying it.
+    pub fn validate<A>(
+        &self,
+        action: &A,
+        context: &R::Context<'_>,
+    ) -> Result<(), RulesGuardError<R::Error, A::Error, A>>
+    where
+        A: action::Action<T>,
+        R: Rules<A>,
+    {
+        self.rules.validate(action, context).context(RuleError {
+            action: action.clone(),
+        })
+    }
+
+    /// Apply an action to the target without consulting the rules. This is intended for actions
+    /// that the rules have already approved once, e.g., when undoing or redoing.
+    pub fn apply_unguarded<A>(&mut self, action: A) -> Result<(), A::Error>
+    where
+        A: action::Action<T>,
+    {
+        self.target.apply(action)
+    }
+
     pub fn rules(&self) -> &R {
         &self.rules
     }
@@ -62,6 +91,14 @@ impl<R, T> RulesGuard<R, T> {
     pub fn set_target(&mut self, target: T) {
         self.target = target;
     }
+
+    pub fn replace_target(&mut self, target: T) -> T {
+        mem::replace(&mut self.target, target)
+    }
+
+    pub fn swap_target(&mut self, target: &mut T) {
+        mem::swap(&mut self.target, target);
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,42 @@
+use std::time;
+
+use crate::model::action;
+use crate::model::action::Actionable as _;
+
+/// Decides how many points each action is worth. Points only ever depend on the action and the
+/// table it's applied to, so a game's score can always be worked out again from the actions that
+/// were taken.
+pub trait ScoringScheme<A, T> {
+    /// The points awarded (or, if negative, deducted) for applying the action to the table, which
+    /// is given as it was *before* the action. Variants that are simply won or lost can leave the
+    /// scheme empty, so that nothing is ever worth any points.
+    fn points(&self, _action: &A, _table: &T) -> i64 {
+        0
+    }
+
+    /// The score a new game starts with.
+    fn initial_score(&self) -> i64 {
+        0
+    }
+
+    /// Points for how long the game has taken so far, on top of those for its actions. `finished`
+    /// is whether the game has been won.
+    fn time_points(&self, _elapsed: time::Duration, _finished: bool) -> i64 {
+        0
+    }
+}
+
+/// Recompute the points earned by applying the actions to the table, one after another. For a
+/// whole game, add the scheme's initial score.
+pub fn score_actions<SC, A, T, I>(scheme: &SC, mut table: T, actions: I) -> Result<i64, A::Error>
+where
+    SC: ScoringScheme<A, T>,
+    A: action::Action<T>,
+    I: IntoIterator<Item = A>,
+{
+    actions.into_iter().try_fold(0, |score, action| {
+        let points = scheme.points(&action, &table);
+        table.apply(action)?;
+        Ok(score + points)
+    })
+}
@@ -10,4 +10,20 @@ pub trait Table: Debug + Clone {
         I: IntoIterator<Item = card::Card>;
 
     fn pile(&self, pile_id: Self::PileId) -> &pile::Pile;
+
+    /// Whether every card is where it needs to be to win.
+    fn is_won(&self) -> bool;
+
+    /// Whether the game could be won without the player making any more decisions, e.g., because
+    /// every remaining card is visible and can simply be played in order.
+    fn is_auto_completable(&self) -> bool {
+        false
+    }
+
+    /// The cards that could be picked up from the top of a pile, bottom to top. By default that's
+    /// just the top card, but a variant may allow more, or fewer if the pile is blocked by another,
+    /// e.g., a card in a pyramid that is covered by the cards below it.
+    fn movable_cards(&self, pile_id: Self::PileId) -> &[card::Card] {
+        self.pile(pile_id).top_cards(1)
+    }
 }
@@ -0,0 +1,21 @@
+//! Where the game keeps its files, following the XDG base directory conventions.
+
+use std::env;
+use std::path::PathBuf;
+
+/// A file in the game's data directory, if there is a home directory to put it in.
+pub fn data_file(name: &str) -> Option<PathBuf> {
+    let data_home = env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))?;
+
+    Some(data_home.join("klondike2").join(name))
+}
+
+pub fn bankroll() -> Option<PathBuf> {
+    data_file("bankroll.json")
+}
+
+pub fn statistics() -> Option<PathBuf> {
+    data_file("statistics.jsonl")
+}
@@ -0,0 +1,11 @@
+//! Pyramid, where cards are discarded in pairs whose ranks add up to thirteen rather than built
+//! into sequences. The cards are dealt into a triangle of single-card piles, each of which is only
+//! in play once both of the cards overlapping it from the row below are gone, so whether a card can
+//! be played depends on other piles as well as its own. That's why `Table::movable_cards` lives on
+//! the trait, where a table can say a pile's card is blocked.
+
+pub mod dealer;
+pub mod game;
+pub mod rules;
+pub mod settings;
+pub mod table;
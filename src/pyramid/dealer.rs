@@ -0,0 +1,46 @@
+use crate::model;
+use crate::pyramid::{game, table};
+
+#[derive(Debug, Clone)]
+pub struct PyramidDealer;
+
+impl model::dealer::Dealer for PyramidDealer {
+    type Action = table::PyramidTableAction;
+    type Context<'a> = PyramidDealerContext;
+    type Iter = PyramidDealerIter;
+
+    fn deal(&self, _context: Self::Context<'_>) -> Self::Iter {
+        PyramidDealerIter { dealt: 0 }
+    }
+}
+
+/// The pyramid always has seven rows and the rest of the deck is left in the stock, so no setting
+/// changes the deal.
+#[derive(Debug, Clone, Copy)]
+pub struct PyramidDealerContext;
+
+impl<'a> From<game::PyramidGameDealerContext<'a>> for PyramidDealerContext {
+    fn from(_context: game::PyramidGameDealerContext<'a>) -> Self {
+        Self
+    }
+}
+
+/// Deals face up a row at a time from the peak down, leaving the rest of the deck in the stock.
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct PyramidDealerIter {
+    dealt: usize,
+}
+
+impl Iterator for PyramidDealerIter {
+    type Item = table::PyramidTableAction;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.dealt < table::PYRAMID_SIZE {
+            let pile_id = table::PyramidPileId::Pyramid(self.dealt);
+            self.dealt += 1;
+            Some(table::PyramidTableAction::Deal(pile_id))
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use std::convert;
+
+use crate::model;
+use crate::pyramid::{dealer, rules, settings, table};
+
+pub type PyramidGame<SH> = model::game::Game<
+    dealer::PyramidDealer,
+    rules::PyramidRules,
+    settings::PyramidSettings,
+    SH,
+    table::PyramidTable,
+>;
+
+pub type PyramidGameError = model::rules::RulesGuardError<
+    rules::PyramidRulesError,
+    convert::Infallible,
+    table::PyramidTableAction,
+>;
+pub type PyramidGameHistoryError =
+    model::game::GameHistoryError<convert::Infallible, convert::Infallible>;
+
+pub type PyramidGameDealerContext<'a> =
+    model::game::GameDealerContext<'a, settings::PyramidSettings>;
+pub type PyramidGameRulesContext<'a> =
+    model::game::GameRulesContext<'a, settings::PyramidSettings, table::PyramidTable>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::action::Actionable as _;
+    use crate::model::deck;
+    use crate::model::game::TableAction;
+    use crate::model::table::Table as _;
+
+    fn started_game() -> PyramidGame<deck::SeededShuffle> {
+        let mut game = PyramidGame::new(
+            dealer::PyramidDealer,
+            rules::PyramidRules,
+            Default::default(),
+            deck::SeededShuffle::new(11982),
+        );
+        game.deal_and_start().unwrap();
+
+        game
+    }
+
+    #[test]
+    fn game_should_deal_pyramid_layout() {
+        let game = started_game();
+        let table = game.table();
+
+        assert_eq!(table.pile(table::PyramidPileId::Stock).len(), 24);
+        for index in 0..table::PYRAMID_SIZE {
+            let pile = table.pile(table::PyramidPileId::Pyramid(index));
+            assert_eq!(pile.len(), 1);
+            assert!(pile.is_face_up());
+            assert_eq!(
+                table
+                    .movable_cards(table::PyramidPileId::Pyramid(index))
+                    .len(),
+                if index < 21 { 0 } else { 1 }
+            );
+        }
+    }
+
+    #[test]
+    fn undo_should_take_back_draw() {
+        let mut game = started_game();
+        let before = game.table().clone();
+
+        game.apply(TableAction(table::PyramidTableAction::Draw))
+            .unwrap();
+        assert_eq!(game.table().pile(table::PyramidPileId::Waste).len(), 1);
+
+        game.undo().unwrap();
+        assert_eq!(game.table(), &before);
+    }
+}
@@ -0,0 +1,344 @@
+use snafu::Snafu;
+
+use crate::model;
+use crate::model::rules::Rules as _;
+use crate::model::table::Table as _;
+use crate::pyramid::{game, settings, table};
+
+/// What a pair of cards must add up to in order to be removed. Kings are worth thirteen on their
+/// own, so they're removed alone.
+pub const PAIR_VALUE: usize = 13;
+
+#[derive(Debug, Clone, Default)]
+pub struct PyramidRules;
+
+#[derive(Debug, Clone, Copy)]
+pub struct PyramidRulesContext<'a> {
+    settings: &'a settings::PyramidSettings,
+    started: bool,
+    table: &'a table::PyramidTable,
+}
+
+impl<'a> PyramidRulesContext<'a> {
+    pub fn new(
+        settings: &'a settings::PyramidSettings,
+        started: bool,
+        table: &'a table::PyramidTable,
+    ) -> Self {
+        Self {
+            settings,
+            started,
+            table,
+        }
+    }
+}
+
+impl<'a> From<game::PyramidGameRulesContext<'a>> for PyramidRulesContext<'a> {
+    fn from(context: game::PyramidGameRulesContext<'a>) -> Self {
+        Self {
+            settings: context.settings,
+            started: context.started,
+            table: context.table,
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum PyramidRulesError {
+    #[snafu(display("The game has already started"))]
+    GameAlreadyStarted,
+    #[snafu(display("The game has not started yet"))]
+    GameNotStarted,
+    #[snafu(display("There is no {}", pile_id))]
+    PileOutOfBounds { pile_id: table::PyramidPileId },
+    #[snafu(display("Cannot deal to the {}", pile_id))]
+    IllegalDealTarget { pile_id: table::PyramidPileId },
+    #[snafu(display("There are no cards left to draw"))]
+    NothingToDraw,
+    #[snafu(display("The waste may only be turned over {} times", max_redeals))]
+    RedealLimitReached { max_redeals: usize },
+    #[snafu(display("Cannot remove cards from the {}", pile_id))]
+    IllegalRemoveSource { pile_id: table::PyramidPileId },
+    #[snafu(display("The {} is empty", pile_id))]
+    EmptyRemove { pile_id: table::PyramidPileId },
+    #[snafu(display("The card in the {} is covered", pile_id))]
+    Covered { pile_id: table::PyramidPileId },
+    #[snafu(display("Cannot pair the {} with itself", pile_id))]
+    SamePile { pile_id: table::PyramidPileId },
+    #[snafu(display("The {} and the {} do not add up to {}", card, other_card, PAIR_VALUE))]
+    PairMismatch {
+        card: model::card::CardFace,
+        other_card: model::card::CardFace,
+    },
+    #[snafu(display("The {} must be paired with another card", card))]
+    NotAKing { card: model::card::CardFace },
+}
+
+impl model::rules::Rules<table::PyramidTableAction> for PyramidRules {
+    type Context<'a> = PyramidRulesContext<'a>;
+    type Error = PyramidRulesError;
+
+    fn validate(
+        &self,
+        action: &table::PyramidTableAction,
+        context: &Self::Context<'_>,
+    ) -> Result<(), Self::Error> {
+        match *action {
+            table::PyramidTableAction::Deal(target_pile_id) => {
+                self.validate_deal(target_pile_id, context)
+            }
+            table::PyramidTableAction::Draw => self.validate_draw(context),
+            table::PyramidTableAction::RemovePair(pile_id, other_pile_id) => {
+                self.validate_remove_pair(pile_id, other_pile_id, context)
+            }
+            table::PyramidTableAction::RemoveKing(pile_id) => {
+                self.validate_remove_king(pile_id, context)
+            }
+        }
+    }
+
+    fn is_stuck(&self, context: &Self::Context<'_>) -> bool {
+        context.started && self.legal_actions(context).is_empty()
+    }
+}
+
+impl PyramidRules {
+    /// List every pair, lone King and draw a player could legally take in this context, counting
+    /// only cards that are uncovered.
+    pub fn legal_actions(
+        &self,
+        context: &PyramidRulesContext<'_>,
+    ) -> Vec<table::PyramidTableAction> {
+        let pile_ids = || {
+            velcro::iter![
+                table::PyramidPileId::Waste,
+                ..(0..table::PYRAMID_SIZE).map(table::PyramidPileId::Pyramid),
+            ]
+            .filter(|&pile_id| !context.table.movable_cards(pile_id).is_empty())
+        };
+
+        let kings = pile_ids().map(table::PyramidTableAction::RemoveKing);
+        let pairs = pile_ids().enumerate().flat_map(|(position, pile_id)| {
+            pile_ids().skip(position + 1).map(move |other_pile_id| {
+                table::PyramidTableAction::RemovePair(pile_id, other_pile_id)
+            })
+        });
+
+        velcro::iter![table::PyramidTableAction::Draw, ..kings, ..pairs]
+            .filter(|action| self.validate(action, context).is_ok())
+            .collect()
+    }
+
+    fn validate_deal(
+        &self,
+        target_pile_id: table::PyramidPileId,
+        context: &PyramidRulesContext<'_>,
+    ) -> Result<(), PyramidRulesError> {
+        snafu::ensure!(!context.started, GameAlreadyStarted);
+
+        match target_pile_id {
+            table::PyramidPileId::Pyramid(_) => self.validate_pile_id(target_pile_id),
+            _ => IllegalDealTarget {
+                pile_id: target_pile_id,
+            }
+            .fail(),
+        }
+    }
+
+    fn validate_draw(&self, context: &PyramidRulesContext<'_>) -> Result<(), PyramidRulesError> {
+        snafu::ensure!(context.started, GameNotStarted);
+
+        let stock = context.table.pile(table::PyramidPileId::Stock);
+        let waste = context.table.pile(table::PyramidPileId::Waste);
+        snafu::ensure!(!(stock.is_empty() && waste.is_empty()), NothingToDraw);
+
+        if let Some(max_redeals) = context.settings.max_redeals {
+            snafu::ensure!(
+                !stock.is_empty() || context.table.redeals() < max_redeals,
+                RedealLimitReached { max_redeals }
+            );
+        }
+
+        Ok(())
+    }
+
+    fn validate_remove_pair(
+        &self,
+        pile_id: table::PyramidPileId,
+        other_pile_id: table::PyramidPileId,
+        context: &PyramidRulesContext<'_>,
+    ) -> Result<(), PyramidRulesError> {
+        snafu::ensure!(context.started, GameNotStarted);
+        snafu::ensure!(pile_id != other_pile_id, SamePile { pile_id });
+
+        let card = self.available_card(pile_id, context)?;
+        let other_card = self.available_card(other_pile_id, context)?;
+        snafu::ensure!(
+            pip_value(card.rank()) + pip_value(other_card.rank()) == PAIR_VALUE,
+            PairMismatch {
+                card: card.face,
+                other_card: other_card.face,
+            }
+        );
+
+        Ok(())
+    }
+
+    fn validate_remove_king(
+        &self,
+        pile_id: table::PyramidPileId,
+        context: &PyramidRulesContext<'_>,
+    ) -> Result<(), PyramidRulesError> {
+        snafu::ensure!(context.started, GameNotStarted);
+
+        let card = self.available_card(pile_id, context)?;
+        snafu::ensure!(card.is_king(), NotAKing { card: card.face });
+
+        Ok(())
+    }
+
+    /// The card that would be removed from a pile, as long as it's in play.
+    fn available_card<'a>(
+        &self,
+        pile_id: table::PyramidPileId,
+        context: &PyramidRulesContext<'a>,
+    ) -> Result<&'a model::card::Card, PyramidRulesError> {
+        match pile_id {
+            table::PyramidPileId::Waste | table::PyramidPileId::Pyramid(_) => {
+                self.validate_pile_id(pile_id)?
+            }
+            _ => return IllegalRemoveSource { pile_id }.fail(),
+        }
+
+        let card = context
+            .table
+            .pile(pile_id)
+            .top_card()
+            .ok_or(PyramidRulesError::EmptyRemove { pile_id })?;
+        snafu::ensure!(
+            !context.table.movable_cards(pile_id).is_empty(),
+            Covered { pile_id }
+        );
+
+        Ok(card)
+    }
+
+    fn validate_pile_id(&self, pile_id: table::PyramidPileId) -> Result<(), PyramidRulesError> {
+        if let table::PyramidPileId::Pyramid(index) = pile_id {
+            snafu::ensure!(index < table::PYRAMID_SIZE, PileOutOfBounds { pile_id });
+        }
+        Ok(())
+    }
+}
+
+/// What a card counts for when pairing: Aces are one, Jacks eleven, Queens twelve and Kings
+/// thirteen.
+fn pip_value(rank: model::card::Rank) -> usize {
+    rank as usize + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::model::card::{Rank, Suit};
+    use crate::test_support::validate_started;
+
+    /// A table with the given face-up cards on the peak of the pyramid and the two cards covering
+    /// it, and the given card on the waste.
+    fn table_with_peak(
+        peak: [Option<model::card::CardFace>; 3],
+        waste: model::card::CardFace,
+    ) -> table::PyramidTable {
+        let mut table = table::PyramidTable::default();
+        for (index, face) in peak.into_iter().enumerate() {
+            if let Some(face) = face {
+                table
+                    .pile_mut(table::PyramidPileId::Pyramid(index))
+                    .place_one(face.face_up());
+            }
+        }
+        table
+            .pile_mut(table::PyramidPileId::Waste)
+            .place_one(waste.face_up());
+        table
+    }
+
+    #[test_case(Rank::Six.of(Suit::Hearts) => true; "adds up")]
+    #[test_case(Rank::Seven.of(Suit::Hearts) => false; "too high")]
+    #[test_case(Rank::Ace.of(Suit::Hearts) => false; "too low")]
+    fn remove_pair_with_waste(waste: model::card::CardFace) -> bool {
+        let table = table_with_peak([None, Some(Rank::Seven.of(Suit::Clubs)), None], waste);
+        let action = table::PyramidTableAction::RemovePair(
+            table::PyramidPileId::Pyramid(1),
+            table::PyramidPileId::Waste,
+        );
+
+        validate_started(&PyramidRules, &Default::default(), &table, &action).is_ok()
+    }
+
+    #[test]
+    fn remove_pair_should_need_uncovered_cards() {
+        let table = table_with_peak(
+            [
+                Some(Rank::Queen.of(Suit::Spades)),
+                Some(Rank::Ace.of(Suit::Hearts)),
+                None,
+            ],
+            Rank::Two.of(Suit::Clubs),
+        );
+        let action = table::PyramidTableAction::RemovePair(
+            table::PyramidPileId::Pyramid(0),
+            table::PyramidPileId::Pyramid(1),
+        );
+
+        assert_matches!(
+            validate_started(&PyramidRules, &Default::default(), &table, &action),
+            Err(PyramidRulesError::Covered {
+                pile_id: table::PyramidPileId::Pyramid(0)
+            })
+        );
+    }
+
+    #[test_case(Rank::King.of(Suit::Diamonds) => true; "king")]
+    #[test_case(Rank::Queen.of(Suit::Diamonds) => false; "queen")]
+    fn remove_king(waste: model::card::CardFace) -> bool {
+        let table = table_with_peak([None, None, None], waste);
+
+        validate_started(
+            &PyramidRules,
+            &Default::default(),
+            &table,
+            &table::PyramidTableAction::RemoveKing(table::PyramidPileId::Waste),
+        )
+        .is_ok()
+    }
+
+    #[test]
+    fn legal_actions_should_pair_available_cards() {
+        let table = table_with_peak(
+            [
+                Some(Rank::Queen.of(Suit::Spades)),
+                Some(Rank::Ace.of(Suit::Hearts)),
+                Some(Rank::King.of(Suit::Hearts)),
+            ],
+            Rank::Queen.of(Suit::Clubs),
+        );
+        let settings = settings::PyramidSettings::default();
+        let context = PyramidRulesContext::new(&settings, true, &table);
+
+        assert_eq!(
+            PyramidRules.legal_actions(&context),
+            vec![
+                table::PyramidTableAction::Draw,
+                table::PyramidTableAction::RemoveKing(table::PyramidPileId::Pyramid(2)),
+                table::PyramidTableAction::RemovePair(
+                    table::PyramidPileId::Waste,
+                    table::PyramidPileId::Pyramid(1)
+                ),
+            ]
+        );
+    }
+}
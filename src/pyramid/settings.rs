@@ -0,0 +1,22 @@
+use crate::model;
+use crate::pyramid::table;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct PyramidSettings {
+    /// How many times the waste may be turned back over to form a new stock, if there is a limit.
+    pub max_redeals: Option<usize>,
+}
+
+/// The classic game goes through the stock three times.
+impl Default for PyramidSettings {
+    fn default() -> Self {
+        Self {
+            max_redeals: Some(2),
+        }
+    }
+}
+
+impl model::scoring::ScoringScheme<table::PyramidTableAction, table::PyramidTable>
+    for PyramidSettings
+{
+}
@@ -0,0 +1,320 @@
+use std::convert;
+
+use crate::model;
+
+/// How many rows the pyramid has. Row `n` (counting from zero at the peak) holds `n + 1` cards.
+pub const ROWS: usize = 7;
+/// How many cards are dealt to the pyramid.
+pub const PYRAMID_SIZE: usize = ROWS * (ROWS + 1) / 2;
+
+#[derive(
+    Debug, Copy, Clone, Eq, PartialEq, derive_more::Display, serde::Deserialize, serde::Serialize,
+)]
+pub enum PyramidPileId {
+    #[display(fmt = "Stock")]
+    Stock,
+    #[display(fmt = "Waste")]
+    Waste,
+    /// Where removed cards go. Nothing is ever taken back out of it.
+    #[display(fmt = "Foundation")]
+    Foundation,
+    /// A single card of the pyramid, numbered a row at a time from the peak down and from left to
+    /// right within each row.
+    #[display(fmt = "Pyramid {}", _0 + 1)]
+    Pyramid(usize),
+}
+
+impl PyramidPileId {
+    pub fn full_iter() -> impl Iterator<Item = PyramidPileId> {
+        velcro::iter![
+            PyramidPileId::Stock,
+            PyramidPileId::Waste,
+            PyramidPileId::Foundation,
+            ..(0..PYRAMID_SIZE).map(PyramidPileId::Pyramid),
+        ]
+    }
+}
+
+/// The row and position within the row of a card in the pyramid, counting from zero.
+pub fn position(index: usize) -> (usize, usize) {
+    let row = (0..ROWS)
+        .take_while(|&row| row * (row + 1) / 2 <= index)
+        .last()
+        .unwrap_or_default();
+    (row, index - row * (row + 1) / 2)
+}
+
+/// The two cards in the next row down that overlap a card in the pyramid, if it isn't on the
+/// bottom row.
+pub fn covering(index: usize) -> Option<[usize; 2]> {
+    let (row, _) = position(index);
+    if row + 1 < ROWS {
+        Some([index + row + 1, index + row + 2])
+    } else {
+        None
+    }
+}
+
+#[derive(Debug, Default, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct PyramidTable {
+    stock: model::pile::Pile,
+    waste: model::pile::Pile,
+    foundation: model::pile::Pile,
+    pyramid: [model::pile::Pile; PYRAMID_SIZE],
+    redeals: usize,
+}
+
+impl model::table::Table for PyramidTable {
+    type PileId = PyramidPileId;
+
+    fn new_with_cards<I>(cards: I) -> Self
+    where
+        I: IntoIterator<Item = model::card::Card>,
+    {
+        let stock = model::pile::Pile::new_with_cards(cards);
+        assert!(stock.is_face_down());
+
+        Self {
+            stock,
+            ..Default::default()
+        }
+    }
+
+    fn pile(&self, pile_id: PyramidPileId) -> &model::pile::Pile {
+        static EMPTY: model::pile::Pile = model::pile::Pile::new();
+
+        match pile_id {
+            PyramidPileId::Stock => &self.stock,
+            PyramidPileId::Waste => &self.waste,
+            PyramidPileId::Foundation => &self.foundation,
+            PyramidPileId::Pyramid(index) => self.pyramid.get(index).unwrap_or(&EMPTY),
+        }
+    }
+
+    /// The game is won once the pyramid has been cleared, whatever is left in the stock and waste.
+    fn is_won(&self) -> bool {
+        self.pyramid.iter().all(model::pile::Pile::is_empty)
+    }
+
+    /// Only the top card of the waste and the cards of the pyramid that aren't covered are in play.
+    fn movable_cards(&self, pile_id: PyramidPileId) -> &[model::card::Card] {
+        match pile_id {
+            PyramidPileId::Stock | PyramidPileId::Foundation => &[],
+            PyramidPileId::Waste => self.waste.top_cards(1),
+            PyramidPileId::Pyramid(index) => {
+                if self.is_covered(index) {
+                    &[]
+                } else {
+                    self.pile(pile_id).top_cards(1)
+                }
+            }
+        }
+    }
+}
+
+impl PyramidTable {
+    /// How many times the waste has been turned back over to form a new stock.
+    pub fn redeals(&self) -> usize {
+        self.redeals
+    }
+
+    /// Whether either of the cards overlapping a card in the pyramid is still there.
+    pub fn is_covered(&self, index: usize) -> bool {
+        covering(index)
+            .map(|covering| {
+                covering
+                    .iter()
+                    .any(|&index| !self.pyramid[index].is_empty())
+            })
+            .unwrap_or_default()
+    }
+
+    /// Panics if the pile doesn't exist, which the rules never allow.
+    pub(crate) fn pile_mut(&mut self, pile_id: PyramidPileId) -> &mut model::pile::Pile {
+        match pile_id {
+            PyramidPileId::Stock => &mut self.stock,
+            PyramidPileId::Waste => &mut self.waste,
+            PyramidPileId::Foundation => &mut self.foundation,
+            PyramidPileId::Pyramid(index) => &mut self.pyramid[index],
+        }
+    }
+}
+
+/// Unlike the other variants, removing a pair takes a card from two piles at once, so there's no
+/// single source and target to describe it.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, derive_more::Display, serde::Deserialize, serde::Serialize,
+)]
+pub enum PyramidTableAction {
+    #[display(fmt = "Deal to the {}", _0)]
+    Deal(PyramidPileId),
+    #[display(fmt = "Draw from the Stock")]
+    Draw,
+    #[display(fmt = "Remove the {} and the {}", _0, _1)]
+    RemovePair(PyramidPileId, PyramidPileId),
+    #[display(fmt = "Remove the King from the {}", _0)]
+    RemoveKing(PyramidPileId),
+}
+
+impl model::action::Action<PyramidTable> for PyramidTableAction {
+    type Error = convert::Infallible;
+
+    fn apply_to(self, table: &mut PyramidTable) -> Result<(), Self::Error> {
+        match self {
+            // Every card is dealt face up.
+            Self::Deal(target_pile_id) => {
+                let card = table.stock.take_top().flipped();
+                table.pile_mut(target_pile_id).place(card);
+            }
+            Self::Draw => {
+                if table.stock.is_empty() {
+                    let replacement_cards = table.waste.take_all().flipped();
+                    table.stock.place(replacement_cards);
+                    table.redeals += 1;
+                } else {
+                    let drawn_card = table.stock.take_top().flipped();
+                    table.waste.place(drawn_card);
+                }
+            }
+            Self::RemovePair(pile_id, other_pile_id) => {
+                let card = table.pile_mut(pile_id).take_top();
+                let other_card = table.pile_mut(other_pile_id).take_top();
+                table.foundation.place(card);
+                table.foundation.place(other_card);
+            }
+            Self::RemoveKing(pile_id) => {
+                let card = table.pile_mut(pile_id).take_top();
+                table.foundation.place(card);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The actions needed to undo a `PyramidTableAction`. These are never validated by the rules, so
+/// they aren't suitable as player actions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PyramidTableInverseAction {
+    /// Move cards from one pile to another, flipping them over as a group (so both their order
+    /// and facing are reversed).
+    Flip(PyramidPileId, PyramidPileId, usize),
+    /// Turn the stock back over onto the waste, taking back a redeal.
+    Unredeal,
+    /// Put the top cards of the foundation back where they were removed from, the last one first.
+    Restore(PyramidPileId, Option<PyramidPileId>),
+}
+
+impl model::action::Action<PyramidTable> for PyramidTableInverseAction {
+    type Error = convert::Infallible;
+
+    fn apply_to(self, table: &mut PyramidTable) -> Result<(), Self::Error> {
+        match self {
+            Self::Flip(source_pile_id, target_pile_id, count) => {
+                let flipped_cards = table.pile_mut(source_pile_id).take(count).flipped();
+                table.pile_mut(target_pile_id).place(flipped_cards);
+            }
+            Self::Unredeal => {
+                let waste_cards = table.stock.take_all().flipped();
+                table.waste.place(waste_cards);
+                table.redeals = table.redeals.saturating_sub(1);
+            }
+            Self::Restore(pile_id, other_pile_id) => {
+                if let Some(other_pile_id) = other_pile_id {
+                    let other_card = table.foundation.take_top();
+                    table.pile_mut(other_pile_id).place(other_card);
+                }
+                let card = table.foundation.take_top();
+                table.pile_mut(pile_id).place(card);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl model::action::Invertible<PyramidTable> for PyramidTableAction {
+    type Inverse = PyramidTableInverseAction;
+
+    fn inverse(&self, table: &PyramidTable) -> Self::Inverse {
+        match *self {
+            Self::Deal(target_pile_id) => {
+                let count = table.stock.len().min(1);
+                PyramidTableInverseAction::Flip(target_pile_id, PyramidPileId::Stock, count)
+            }
+            Self::Draw => {
+                if table.stock.is_empty() {
+                    PyramidTableInverseAction::Unredeal
+                } else {
+                    PyramidTableInverseAction::Flip(PyramidPileId::Waste, PyramidPileId::Stock, 1)
+                }
+            }
+            Self::RemovePair(pile_id, other_pile_id) => {
+                PyramidTableInverseAction::Restore(pile_id, Some(other_pile_id))
+            }
+            Self::RemoveKing(pile_id) => PyramidTableInverseAction::Restore(pile_id, None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+    use crate::model::action::Actionable as _;
+    use crate::model::card::{Rank, Suit};
+    use crate::model::table::Table as _;
+
+    #[test_case(0 => (0, 0))]
+    #[test_case(2 => (1, 1))]
+    #[test_case(3 => (2, 0))]
+    #[test_case(27 => (6, 6))]
+    fn position_of(index: usize) -> (usize, usize) {
+        position(index)
+    }
+
+    #[test_case(0 => Some([1, 2]))]
+    #[test_case(4 => Some([7, 8]))]
+    #[test_case(21 => None)]
+    fn covering_of(index: usize) -> Option<[usize; 2]> {
+        covering(index)
+    }
+
+    #[test]
+    fn card_should_be_covered_until_both_covering_cards_are_gone() {
+        let mut table = PyramidTable::default();
+        for index in [0, 1, 2] {
+            table
+                .pile_mut(PyramidPileId::Pyramid(index))
+                .place_one(Rank::Five.of(Suit::Clubs).face_up());
+        }
+
+        assert!(table.movable_cards(PyramidPileId::Pyramid(0)).is_empty());
+        let _ = table.pile_mut(PyramidPileId::Pyramid(1)).take_all();
+        assert!(table.movable_cards(PyramidPileId::Pyramid(0)).is_empty());
+        let _ = table.pile_mut(PyramidPileId::Pyramid(2)).take_all();
+        assert_eq!(table.movable_cards(PyramidPileId::Pyramid(0)).len(), 1);
+    }
+
+    #[test]
+    fn remove_pair_should_be_undone_in_place() {
+        let mut table = PyramidTable::default();
+        table
+            .pile_mut(PyramidPileId::Pyramid(21))
+            .place_one(Rank::Six.of(Suit::Clubs).face_up());
+        table
+            .pile_mut(PyramidPileId::Waste)
+            .place_one(Rank::Seven.of(Suit::Hearts).face_up());
+        let before = table.clone();
+
+        let action =
+            PyramidTableAction::RemovePair(PyramidPileId::Waste, PyramidPileId::Pyramid(21));
+        let inverse = model::action::Invertible::inverse(&action, &table);
+        table.apply(action).unwrap();
+        assert_eq!(table.pile(PyramidPileId::Foundation).len(), 2);
+
+        table.apply(inverse).unwrap();
+        assert_eq!(table, before);
+    }
+}
@@ -0,0 +1,99 @@
+use crate::model;
+use crate::spider::{game, settings, table};
+
+/// How many cards are dealt to the tableaux at the start. The rest stay in the stock to be dealt
+/// a row at a time.
+const INITIAL_DEAL: usize = 54;
+
+#[derive(Debug, Clone)]
+pub struct SpiderDealer;
+
+impl model::dealer::Dealer for SpiderDealer {
+    type Action = table::SpiderTableAction;
+    type Context<'a> = SpiderDealerContext;
+    type Iter = SpiderDealerIter;
+
+    fn deal(&self, _context: Self::Context<'_>) -> Self::Iter {
+        SpiderDealerIter { step: 0 }
+    }
+
+    fn deck(&self, context: Self::Context<'_>) -> model::deck::Deck {
+        model::deck::Deck::new_with_suits(context.suits.suits(), context.suits.copies())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SpiderDealerContext {
+    suits: settings::SpiderSuits,
+}
+
+impl<'a> From<game::SpiderGameDealerContext<'a>> for SpiderDealerContext {
+    fn from(context: game::SpiderGameDealerContext<'a>) -> Self {
+        Self {
+            suits: context.settings.suits,
+        }
+    }
+}
+
+/// Deals face down a row at a time from left to right, so the first four tableaux get six cards
+/// and the rest get five, then reveals the top card of each.
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct SpiderDealerIter {
+    step: usize,
+}
+
+impl Iterator for SpiderDealerIter {
+    type Item = table::SpiderTableAction;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let action = if self.step < INITIAL_DEAL {
+            let pile_id = table::SpiderPileId::Tableaux(self.step % table::TABLEAUX_WIDTH);
+            table::SpiderTableAction::Deal(pile_id)
+        } else if self.step < INITIAL_DEAL + table::TABLEAUX_WIDTH {
+            let pile_id = table::SpiderPileId::Tableaux(self.step - INITIAL_DEAL);
+            table::SpiderTableAction::Reveal(pile_id)
+        } else {
+            return None;
+        };
+
+        self.step += 1;
+        Some(action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools as _;
+
+    use super::*;
+    use crate::model::dealer::Dealer as _;
+
+    #[test]
+    fn dealer_should_deal_then_reveal() {
+        let context = SpiderDealerContext {
+            suits: Default::default(),
+        };
+        let actions = SpiderDealer.deal(context).collect_vec();
+
+        assert_eq!(actions.len(), 64);
+        assert_eq!(
+            actions[53],
+            table::SpiderTableAction::Deal(table::SpiderPileId::Tableaux(3))
+        );
+        assert_eq!(
+            actions[54..],
+            (0..10)
+                .map(|index| table::SpiderTableAction::Reveal(table::SpiderPileId::Tableaux(index)))
+                .collect_vec()
+        );
+    }
+
+    #[test]
+    fn dealer_should_use_two_decks() {
+        let context = SpiderDealerContext {
+            suits: settings::SpiderSuits::Two,
+        };
+
+        assert_eq!(SpiderDealer.deck(context).len(), 104);
+    }
+}
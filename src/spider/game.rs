@@ -0,0 +1,73 @@
+use std::convert;
+
+use crate::model;
+use crate::spider::{dealer, rules, settings, table};
+
+pub type SpiderGame<SH> = model::game::Game<
+    dealer::SpiderDealer,
+    rules::SpiderRules,
+    settings::SpiderSettings,
+    SH,
+    table::SpiderTable,
+>;
+
+pub type SpiderGameError = model::rules::RulesGuardError<
+    rules::SpiderRulesError,
+    convert::Infallible,
+    table::SpiderTableAction,
+>;
+pub type SpiderGameHistoryError =
+    model::game::GameHistoryError<convert::Infallible, convert::Infallible>;
+
+pub type SpiderGameDealerContext<'a> = model::game::GameDealerContext<'a, settings::SpiderSettings>;
+pub type SpiderGameRulesContext<'a> =
+    model::game::GameRulesContext<'a, settings::SpiderSettings, table::SpiderTable>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::action::Actionable as _;
+    use crate::model::deck;
+    use crate::model::game::TableAction;
+    use crate::model::table::Table as _;
+
+    fn started_game(suits: settings::SpiderSuits) -> SpiderGame<deck::SeededShuffle> {
+        let mut game = SpiderGame::new(
+            dealer::SpiderDealer,
+            rules::SpiderRules,
+            settings::SpiderSettings { suits },
+            deck::SeededShuffle::new(11982),
+        );
+        game.deal_and_start().unwrap();
+
+        game
+    }
+
+    #[test]
+    fn game_should_deal_spider_layout() {
+        let game = started_game(settings::SpiderSuits::Four);
+        let table = game.table();
+
+        assert_eq!(table.pile(table::SpiderPileId::Stock).len(), 50);
+        for index in 0..table::TABLEAUX_WIDTH {
+            let pile = table.pile(table::SpiderPileId::Tableaux(index));
+            assert_eq!(pile.len(), if index < 4 { 6 } else { 5 });
+            assert_eq!(pile.top_face_up_cards().len(), 1);
+        }
+        assert_eq!(game.score(), 500);
+    }
+
+    #[test]
+    fn undo_should_take_back_deal_row() {
+        let mut game = started_game(settings::SpiderSuits::One);
+        let before = game.table().clone();
+
+        game.apply(TableAction(table::SpiderTableAction::DealRow))
+            .unwrap();
+        assert_eq!(game.table().pile(table::SpiderPileId::Stock).len(), 40);
+        assert_eq!(game.score(), 499);
+
+        game.undo().unwrap();
+        assert_eq!(game.table(), &before);
+    }
+}
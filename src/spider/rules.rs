@@ -0,0 +1,340 @@
+use snafu::Snafu;
+
+use crate::model;
+use crate::model::rules::Rules as _;
+use crate::model::table::Table as _;
+use crate::spider::{game, table};
+
+#[derive(Debug, Clone, Default)]
+pub struct SpiderRules;
+
+#[derive(Debug, Clone, Copy)]
+pub struct SpiderRulesContext<'a> {
+    started: bool,
+    table: &'a table::SpiderTable,
+}
+
+impl<'a> SpiderRulesContext<'a> {
+    pub fn new(started: bool, table: &'a table::SpiderTable) -> Self {
+        Self { started, table }
+    }
+}
+
+impl<'a> From<game::SpiderGameRulesContext<'a>> for SpiderRulesContext<'a> {
+    /// None of the settings affect the rules.
+    fn from(context: game::SpiderGameRulesContext<'a>) -> Self {
+        Self {
+            started: context.started,
+            table: context.table,
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum SpiderRulesError {
+    #[snafu(display("The game has already started"))]
+    GameAlreadyStarted,
+    #[snafu(display("The game has not started yet"))]
+    GameNotStarted,
+    #[snafu(display("There is no tableaux {}", index + 1))]
+    PileOutOfBounds { index: usize },
+    #[snafu(display("Cannot deal to the {}", pile_id))]
+    IllegalDealTarget { pile_id: table::SpiderPileId },
+    #[snafu(display("There are no cards left to deal"))]
+    NothingToDeal,
+    #[snafu(display("Cannot deal while the {} is empty", pile_id))]
+    EmptyTableaux { pile_id: table::SpiderPileId },
+    #[snafu(display("Cannot move zero cards"))]
+    EmptyMove,
+    #[snafu(display("The {} does not have {} cards", pile_id, count))]
+    InsufficientCards {
+        pile_id: table::SpiderPileId,
+        count: usize,
+    },
+    #[snafu(display("Cannot move a card that is {}", facing))]
+    IllegalMoveSourceFacing { facing: model::card::Facing },
+    #[snafu(display("Only a run of a single suit can be moved together"))]
+    OutOfSequence,
+    #[snafu(display("Cannot move cards from the {}", pile_id))]
+    IllegalMoveSource { pile_id: table::SpiderPileId },
+    #[snafu(display("Cannot move cards to the {}", pile_id))]
+    IllegalMoveTarget { pile_id: table::SpiderPileId },
+    #[snafu(display("Cannot move onto a card that is {}", facing))]
+    IllegalMoveTargetFacing { facing: model::card::Facing },
+    #[snafu(display("Cannot place the {} on the {}", card, target_card))]
+    TableauxMismatch {
+        card: model::card::CardFace,
+        target_card: model::card::CardFace,
+    },
+    #[snafu(display("Cannot reveal a card in the {}", pile_id))]
+    IllegalRevealTarget { pile_id: table::SpiderPileId },
+    #[snafu(display("The {} has no card to reveal", pile_id))]
+    EmptyReveal { pile_id: table::SpiderPileId },
+    #[snafu(display("Cannot reveal a card that is {}", facing))]
+    IllegalRevealTargetFacing { facing: model::card::Facing },
+}
+
+impl model::rules::Rules<table::SpiderTableAction> for SpiderRules {
+    type Context<'a> = SpiderRulesContext<'a>;
+    type Error = SpiderRulesError;
+
+    fn validate(
+        &self,
+        action: &table::SpiderTableAction,
+        context: &Self::Context<'_>,
+    ) -> Result<(), Self::Error> {
+        match *action {
+            table::SpiderTableAction::Deal(target_pile_id) => {
+                self.validate_deal(target_pile_id, context)
+            }
+            table::SpiderTableAction::DealRow => self.validate_deal_row(context),
+            table::SpiderTableAction::Move(source_pile_id, target_pile_id, count) => {
+                self.validate_move(source_pile_id, target_pile_id, count, context)
+            }
+            table::SpiderTableAction::Reveal(target_pile_id) => {
+                self.validate_reveal(target_pile_id, context)
+            }
+        }
+    }
+
+    fn is_stuck(&self, context: &Self::Context<'_>) -> bool {
+        context.started && self.legal_actions(context).is_empty()
+    }
+}
+
+impl SpiderRules {
+    /// List every action a player could legally take in this context. Dealing the initial layout
+    /// is the dealer's job, so `Deal` actions are never included.
+    pub fn legal_actions(&self, context: &SpiderRulesContext<'_>) -> Vec<table::SpiderTableAction> {
+        let tableaux_ids = (0..table::TABLEAUX_WIDTH).map(table::SpiderPileId::Tableaux);
+
+        let reveals = tableaux_ids.clone().map(table::SpiderTableAction::Reveal);
+        let moves = tableaux_ids.clone().flat_map(|source_pile_id| {
+            let max_count = context.table.movable_cards(source_pile_id).len();
+
+            tableaux_ids
+                .clone()
+                .filter(move |&target_pile_id| target_pile_id != source_pile_id)
+                .flat_map(move |target_pile_id| {
+                    (1..=max_count).map(move |count| {
+                        table::SpiderTableAction::Move(source_pile_id, target_pile_id, count)
+                    })
+                })
+        });
+
+        velcro::iter![table::SpiderTableAction::DealRow, ..reveals, ..moves]
+            .filter(|action| self.validate(action, context).is_ok())
+            .collect()
+    }
+
+    fn validate_deal(
+        &self,
+        target_pile_id: table::SpiderPileId,
+        context: &SpiderRulesContext<'_>,
+    ) -> Result<(), SpiderRulesError> {
+        snafu::ensure!(!context.started, GameAlreadyStarted);
+
+        match target_pile_id {
+            table::SpiderPileId::Tableaux(index) => self.validate_tableaux_index(index),
+            _ => IllegalDealTarget {
+                pile_id: target_pile_id,
+            }
+            .fail(),
+        }
+    }
+
+    fn validate_deal_row(&self, context: &SpiderRulesContext<'_>) -> Result<(), SpiderRulesError> {
+        snafu::ensure!(context.started, GameNotStarted);
+        snafu::ensure!(
+            !context.table.pile(table::SpiderPileId::Stock).is_empty(),
+            NothingToDeal
+        );
+
+        if let Some(pile_id) = (0..table::TABLEAUX_WIDTH)
+            .map(table::SpiderPileId::Tableaux)
+            .find(|&pile_id| context.table.pile(pile_id).is_empty())
+        {
+            return EmptyTableaux { pile_id }.fail();
+        }
+
+        Ok(())
+    }
+
+    fn validate_move(
+        &self,
+        source_pile_id: table::SpiderPileId,
+        target_pile_id: table::SpiderPileId,
+        count: usize,
+        context: &SpiderRulesContext<'_>,
+    ) -> Result<(), SpiderRulesError> {
+        snafu::ensure!(context.started, GameNotStarted);
+        snafu::ensure!(count > 0, EmptyMove);
+
+        match source_pile_id {
+            table::SpiderPileId::Tableaux(index) => self.validate_tableaux_index(index)?,
+            _ => {
+                return IllegalMoveSource {
+                    pile_id: source_pile_id,
+                }
+                .fail();
+            }
+        }
+
+        let source_top_cards = context.table.pile(source_pile_id).top_cards(count);
+        snafu::ensure!(
+            count == source_top_cards.len(),
+            InsufficientCards {
+                pile_id: source_pile_id,
+                count
+            }
+        );
+
+        let source_card = &source_top_cards[0];
+        snafu::ensure!(
+            source_card.is_face_up(),
+            IllegalMoveSourceFacing {
+                facing: source_card.facing
+            }
+        );
+        snafu::ensure!(table::is_run(source_top_cards), OutOfSequence);
+
+        match target_pile_id {
+            table::SpiderPileId::Tableaux(index) => self.validate_tableaux_index(index)?,
+            _ => {
+                return IllegalMoveTarget {
+                    pile_id: target_pile_id,
+                }
+                .fail();
+            }
+        }
+
+        // Any card may go on an empty tableaux, and suits only matter for moving runs, not for
+        // building them.
+        if let Some(target_top_card) = context.table.pile(target_pile_id).top_card() {
+            snafu::ensure!(
+                target_top_card.is_face_up(),
+                IllegalMoveTargetFacing {
+                    facing: target_top_card.facing
+                }
+            );
+            snafu::ensure!(
+                target_top_card.rank().follows(source_card.rank()),
+                TableauxMismatch {
+                    card: source_card.face,
+                    target_card: target_top_card.face,
+                }
+            );
+        }
+
+        Ok(())
+    }
+
+    fn validate_reveal(
+        &self,
+        target_pile_id: table::SpiderPileId,
+        context: &SpiderRulesContext<'_>,
+    ) -> Result<(), SpiderRulesError> {
+        if let table::SpiderPileId::Tableaux(index) = target_pile_id {
+            self.validate_tableaux_index(index)?;
+
+            let top_card = context.table.pile(target_pile_id).top_card().ok_or(
+                SpiderRulesError::EmptyReveal {
+                    pile_id: target_pile_id,
+                },
+            )?;
+            snafu::ensure!(
+                top_card.is_face_down(),
+                IllegalRevealTargetFacing {
+                    facing: top_card.facing
+                }
+            );
+
+            Ok(())
+        } else {
+            IllegalRevealTarget {
+                pile_id: target_pile_id,
+            }
+            .fail()
+        }
+    }
+
+    fn validate_tableaux_index(&self, index: usize) -> Result<(), SpiderRulesError> {
+        snafu::ensure!(index < table::TABLEAUX_WIDTH, PileOutOfBounds { index });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::model::card::{Rank, Suit};
+    use crate::test_support::validate_started;
+
+    /// A table with the given face-up cards, bottom to top, on the first tableaux, a single card on
+    /// each of the others unless `empty` is set, and a row's worth of cards in the stock.
+    fn table_with_tableaux(cards: &[model::card::CardFace], empty: bool) -> table::SpiderTable {
+        let mut table = table::SpiderTable::default();
+        table
+            .pile_mut(table::SpiderPileId::Tableaux(0))
+            .place_cards(cards.iter().map(|face| face.face_up()));
+        for index in 1..table::TABLEAUX_WIDTH {
+            if !empty {
+                table
+                    .pile_mut(table::SpiderPileId::Tableaux(index))
+                    .place_one(Rank::Nine.of(Suit::Diamonds).face_up());
+            }
+        }
+        table
+            .pile_mut(table::SpiderPileId::Stock)
+            .place_cards((0..table::TABLEAUX_WIDTH).map(|_| Rank::Ace.of(Suit::Clubs).face_down()));
+        table
+    }
+
+    #[test_case(&[Rank::Eight.of(Suit::Spades)] => true; "different suit")]
+    #[test_case(&[Rank::Eight.of(Suit::Diamonds), Rank::Seven.of(Suit::Diamonds)] => true; "run")]
+    #[test_case(&[Rank::Eight.of(Suit::Spades), Rank::Seven.of(Suit::Diamonds)] => false; "mixed suits")]
+    #[test_case(&[Rank::Seven.of(Suit::Spades)] => false; "skipped rank")]
+    fn move_onto_tableaux(cards: &[model::card::CardFace]) -> bool {
+        let table = table_with_tableaux(cards, false);
+        let action = table::SpiderTableAction::Move(
+            table::SpiderPileId::Tableaux(0),
+            table::SpiderPileId::Tableaux(1),
+            cards.len(),
+        );
+
+        validate_started(&SpiderRules, &Default::default(), &table, &action).is_ok()
+    }
+
+    #[test_case(false => true; "all tableaux filled")]
+    #[test_case(true => false; "empty tableaux")]
+    fn deal_row_should_need_every_tableaux_filled(empty: bool) -> bool {
+        let table = table_with_tableaux(&[Rank::King.of(Suit::Spades)], empty);
+
+        validate_started(
+            &SpiderRules,
+            &Default::default(),
+            &table,
+            &table::SpiderTableAction::DealRow,
+        )
+        .is_ok()
+    }
+
+    #[test]
+    fn deal_row_should_need_stock() {
+        let mut table = table_with_tableaux(&[Rank::King.of(Suit::Spades)], false);
+        let _ = table.pile_mut(table::SpiderPileId::Stock).take_all();
+
+        assert_matches!(
+            validate_started(
+                &SpiderRules,
+                &Default::default(),
+                &table,
+                &table::SpiderTableAction::DealRow,
+            ),
+            Err(SpiderRulesError::NothingToDeal)
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use crate::model;
+use crate::spider::table;
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    derive_more::Display,
+    serde::Deserialize,
+    serde::Serialize,
+)]
+pub enum SpiderSuits {
+    #[default]
+    #[display(fmt = "one")]
+    One,
+    #[display(fmt = "two")]
+    Two,
+    #[display(fmt = "four")]
+    Four,
+}
+
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct SpiderSettings {
+    /// How many suits the two decks' worth of cards are made up of. Fewer suits make for an easier
+    /// game, since runs of a single suit are easier to build.
+    pub suits: SpiderSuits,
+}
+
+/// The scoring from Windows Spider: start on 500 points, lose one for every move or deal, and earn
+/// 100 for every completed run.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct SpiderScoring;
+
+impl SpiderSuits {
+    pub fn suits(self) -> &'static [model::card::Suit] {
+        match self {
+            Self::One => &[model::card::Suit::Spades],
+            Self::Two => &[model::card::Suit::Spades, model::card::Suit::Hearts],
+            Self::Four => &[
+                model::card::Suit::Spades,
+                model::card::Suit::Hearts,
+                model::card::Suit::Diamonds,
+                model::card::Suit::Clubs,
+            ],
+        }
+    }
+
+    /// How many copies of each card there are, making up two full decks between the suits.
+    pub fn copies(self) -> usize {
+        8 / self.suits().len()
+    }
+}
+
+impl SpiderScoring {
+    pub const INITIAL: i64 = 500;
+    pub const MOVE: i64 = -1;
+    pub const COMPLETED_RUN: i64 = 100;
+}
+
+impl model::scoring::ScoringScheme<table::SpiderTableAction, table::SpiderTable> for SpiderScoring {
+    fn points(&self, action: &table::SpiderTableAction, table: &table::SpiderTable) -> i64 {
+        match *action {
+            table::SpiderTableAction::DealRow | table::SpiderTableAction::Move(..) => {
+                let completed_runs = table.runs_completed_by(*action).len() as i64;
+                Self::MOVE + Self::COMPLETED_RUN * completed_runs
+            }
+            table::SpiderTableAction::Deal(_) | table::SpiderTableAction::Reveal(_) => 0,
+        }
+    }
+
+    fn initial_score(&self) -> i64 {
+        Self::INITIAL
+    }
+}
+
+impl model::scoring::ScoringScheme<table::SpiderTableAction, table::SpiderTable>
+    for SpiderSettings
+{
+    fn points(&self, action: &table::SpiderTableAction, table: &table::SpiderTable) -> i64 {
+        SpiderScoring.points(action, table)
+    }
+
+    fn initial_score(&self) -> i64 {
+        SpiderScoring.initial_score()
+    }
+}
@@ -0,0 +1,388 @@
+use std::{convert, iter};
+
+use itertools::Itertools as _;
+
+use crate::model;
+use crate::model::table::Table as _;
+
+/// How many columns the cards are dealt to, which is also how many cards each deal from the stock
+/// takes.
+pub const TABLEAUX_WIDTH: usize = 10;
+/// How many cards make up a complete run, from King down to Ace.
+pub const RUN_LENGTH: usize = 13;
+
+#[derive(
+    Debug, Copy, Clone, Eq, PartialEq, derive_more::Display, serde::Deserialize, serde::Serialize,
+)]
+pub enum SpiderPileId {
+    #[display(fmt = "Stock")]
+    Stock,
+    /// Completed runs, in the order they were completed.
+    #[display(fmt = "Foundation {}", _0 + 1)]
+    Foundation(usize),
+    #[display(fmt = "Tableaux {}", _0 + 1)]
+    Tableaux(usize),
+}
+
+#[derive(Debug, Default, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct SpiderTable {
+    stock: model::pile::Pile,
+    foundations: Vec<model::pile::Pile>,
+    tableaux: [model::pile::Pile; TABLEAUX_WIDTH],
+}
+
+impl model::table::Table for SpiderTable {
+    type PileId = SpiderPileId;
+
+    fn new_with_cards<I>(cards: I) -> Self
+    where
+        I: IntoIterator<Item = model::card::Card>,
+    {
+        let stock = model::pile::Pile::new_with_cards(cards);
+        assert!(stock.is_face_down());
+
+        Self {
+            stock,
+            ..Default::default()
+        }
+    }
+
+    fn pile(&self, pile_id: SpiderPileId) -> &model::pile::Pile {
+        static EMPTY: model::pile::Pile = model::pile::Pile::new();
+
+        match pile_id {
+            SpiderPileId::Stock => &self.stock,
+            SpiderPileId::Foundation(index) => self.foundations.get(index).unwrap_or(&EMPTY),
+            SpiderPileId::Tableaux(index) => self.tableaux.get(index).unwrap_or(&EMPTY),
+        }
+    }
+
+    /// Every card has left the table in a completed run.
+    fn is_won(&self) -> bool {
+        !self.foundations.is_empty()
+            && self.stock.is_empty()
+            && self.tableaux.iter().all(model::pile::Pile::is_empty)
+    }
+
+    /// The cards that could be picked up from the top of a pile as a group, bottom to top: the
+    /// run of a single suit at the top of a tableaux. Nothing can be taken from the stock or the
+    /// foundations.
+    fn movable_cards(&self, pile_id: SpiderPileId) -> &[model::card::Card] {
+        match pile_id {
+            SpiderPileId::Stock | SpiderPileId::Foundation(_) => &[],
+            SpiderPileId::Tableaux(_) => {
+                let pile = self.pile(pile_id);
+                let run_length = (1..=pile.len())
+                    .take_while(|&count| is_run(pile.top_cards(count)))
+                    .last()
+                    .unwrap_or(0);
+                pile.top_cards(run_length)
+            }
+        }
+    }
+}
+
+impl SpiderTable {
+    /// How many runs have been completed and taken off the table.
+    pub fn completed_runs(&self) -> usize {
+        self.foundations.len()
+    }
+
+    /// The tableaux whose runs the action would complete, in the order they would be taken off.
+    pub fn runs_completed_by(&self, action: SpiderTableAction) -> Vec<usize> {
+        match action {
+            SpiderTableAction::DealRow => {
+                let dealt = self.stock.iter().rev().map(|card| card.face.face_up());
+                (0..TABLEAUX_WIDTH)
+                    .zip(dealt)
+                    .filter(|&(index, ref card)| {
+                        completes_run(&self.tableaux[index], iter::once(card))
+                    })
+                    .map(|(index, _)| index)
+                    .collect()
+            }
+            SpiderTableAction::Move(source_pile_id, SpiderPileId::Tableaux(index), count)
+                if index < TABLEAUX_WIDTH =>
+            {
+                let moved = self.pile(source_pile_id).top_cards(count);
+                if completes_run(&self.tableaux[index], moved) {
+                    vec![index]
+                } else {
+                    vec![]
+                }
+            }
+            SpiderTableAction::Deal(_)
+            | SpiderTableAction::Move(..)
+            | SpiderTableAction::Reveal(_) => vec![],
+        }
+    }
+
+    pub(crate) fn pile_mut(&mut self, pile_id: SpiderPileId) -> &mut model::pile::Pile {
+        match pile_id {
+            SpiderPileId::Stock => &mut self.stock,
+            SpiderPileId::Foundation(index) => {
+                if index >= self.foundations.len() {
+                    self.foundations.resize_with(index + 1, Default::default);
+                }
+                &mut self.foundations[index]
+            }
+            SpiderPileId::Tableaux(index) => &mut self.tableaux[index],
+        }
+    }
+
+    /// Take a complete run off the top of a tableaux, if there is one.
+    fn clear_run(&mut self, index: usize) {
+        if is_complete_run(self.tableaux[index].top_cards(RUN_LENGTH)) {
+            let run = self.tableaux[index].take(RUN_LENGTH);
+            self.foundations.push(run);
+        }
+    }
+
+    /// Put the most recently completed run back on a tableaux.
+    fn restore_run(&mut self, index: usize) {
+        if let Some(run) = self.foundations.pop() {
+            self.tableaux[index].place(run);
+        }
+    }
+}
+
+/// Whether the cards, bottom to top, are face up, of one suit, and go down by one rank at a time.
+pub fn is_run(cards: &[model::card::Card]) -> bool {
+    cards.iter().all(model::card::Card::is_face_up)
+        && cards
+            .windows(2)
+            .all(|pair| pair[0].suit() == pair[1].suit() && pair[0].rank().follows(pair[1].rank()))
+}
+
+fn is_complete_run(cards: &[model::card::Card]) -> bool {
+    cards.len() == RUN_LENGTH && cards[0].is_king() && is_run(cards)
+}
+
+/// Whether placing the cards on the pile would leave a complete run on top of it.
+fn completes_run<'a, I>(pile: &'a model::pile::Pile, placed: I) -> bool
+where
+    I: IntoIterator<Item = &'a model::card::Card>,
+{
+    let cards = pile.iter().chain(placed).cloned().collect_vec();
+    is_complete_run(&cards[cards.len().saturating_sub(RUN_LENGTH)..])
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, derive_more::Display, serde::Deserialize, serde::Serialize,
+)]
+pub enum SpiderTableAction {
+    #[display(fmt = "Deal to the {}", _0)]
+    Deal(SpiderPileId),
+    #[display(fmt = "Deal a card from the Stock to every Tableaux")]
+    DealRow,
+    #[display(fmt = "Move {} from the {} to the {}", _2, _0, _1)]
+    Move(SpiderPileId, SpiderPileId, usize),
+    #[display(fmt = "Reveal the top of the {}", _0)]
+    Reveal(SpiderPileId),
+}
+
+impl model::action::Action<SpiderTable> for SpiderTableAction {
+    type Error = convert::Infallible;
+
+    fn apply_to(self, table: &mut SpiderTable) -> Result<(), Self::Error> {
+        match self {
+            Self::Deal(target_pile_id) => {
+                let card = table.stock.take_top();
+                table.pile_mut(target_pile_id).place(card);
+            }
+            Self::DealRow => {
+                for index in 0..TABLEAUX_WIDTH {
+                    let card = table.stock.take_top().flipped();
+                    table.tableaux[index].place(card);
+                }
+                for index in 0..TABLEAUX_WIDTH {
+                    table.clear_run(index);
+                }
+            }
+            Self::Move(source_pile_id, target_pile_id, count) => {
+                let moved_cards = table.pile_mut(source_pile_id).take(count);
+                table.pile_mut(target_pile_id).place(moved_cards);
+                if let SpiderPileId::Tableaux(index) = target_pile_id {
+                    table.clear_run(index);
+                }
+            }
+            Self::Reveal(target_pile_id) => {
+                table
+                    .pile_mut(target_pile_id)
+                    .flip_top_to(model::card::Facing::FaceUp);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The actions needed to undo a `SpiderTableAction`, including putting back any runs it
+/// completed. These are never validated by the rules, so they aren't suitable as player actions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpiderTableInverseAction {
+    /// Put back the runs that were completed on the given tableaux, then return the top card of
+    /// each tableaux to the stock, face down.
+    Undeal { completed: Vec<usize> },
+    /// Put back a run that was completed on the source, if there was one, then move cards from
+    /// one pile to another as they are.
+    Move {
+        source_pile_id: SpiderPileId,
+        target_pile_id: SpiderPileId,
+        count: usize,
+        completed: bool,
+    },
+    /// Turn the top card of a pile to a particular facing.
+    Turn(SpiderPileId, model::card::Facing),
+}
+
+impl model::action::Action<SpiderTable> for SpiderTableInverseAction {
+    type Error = convert::Infallible;
+
+    fn apply_to(self, table: &mut SpiderTable) -> Result<(), Self::Error> {
+        match self {
+            Self::Undeal { completed } => {
+                for &index in completed.iter().rev() {
+                    table.restore_run(index);
+                }
+                for index in (0..TABLEAUX_WIDTH).rev() {
+                    let card = table.tableaux[index].take_top().flipped();
+                    table.stock.place(card);
+                }
+            }
+            Self::Move {
+                source_pile_id,
+                target_pile_id,
+                count,
+                completed,
+            } => {
+                if let (true, SpiderPileId::Tableaux(index)) = (completed, source_pile_id) {
+                    table.restore_run(index);
+                }
+                let moved_cards = table.pile_mut(source_pile_id).take(count);
+                table.pile_mut(target_pile_id).place(moved_cards);
+            }
+            Self::Turn(target_pile_id, facing) => {
+                table.pile_mut(target_pile_id).flip_top_to(facing);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl model::action::Invertible<SpiderTable> for SpiderTableAction {
+    type Inverse = SpiderTableInverseAction;
+
+    fn inverse(&self, table: &SpiderTable) -> Self::Inverse {
+        match *self {
+            Self::Deal(target_pile_id) => SpiderTableInverseAction::Move {
+                source_pile_id: target_pile_id,
+                target_pile_id: SpiderPileId::Stock,
+                count: table.stock.len().min(1),
+                completed: false,
+            },
+            Self::DealRow => SpiderTableInverseAction::Undeal {
+                completed: table.runs_completed_by(*self),
+            },
+            Self::Move(source_pile_id, target_pile_id, count) => SpiderTableInverseAction::Move {
+                source_pile_id: target_pile_id,
+                target_pile_id: source_pile_id,
+                count: table.pile(source_pile_id).len().min(count),
+                completed: !table.runs_completed_by(*self).is_empty(),
+            },
+            Self::Reveal(target_pile_id) => {
+                let facing = table
+                    .pile(target_pile_id)
+                    .top_card()
+                    .map(|card| card.facing)
+                    .unwrap_or(model::card::Facing::FaceUp);
+                SpiderTableInverseAction::Turn(target_pile_id, facing)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use enum_like::EnumValues as _;
+
+    use super::*;
+    use crate::model::action::{Actionable as _, Invertible as _};
+    use crate::model::card::{Rank, Suit};
+
+    /// A table with the given face-up cards, bottom to top, on the first two tableaux, and a row's
+    /// worth of Aces of Clubs in the stock.
+    fn table_with_tableaux(
+        first: &[model::card::CardFace],
+        second: &[model::card::CardFace],
+    ) -> SpiderTable {
+        let mut table = SpiderTable::default();
+        for (index, cards) in [first, second].iter().enumerate() {
+            table
+                .pile_mut(SpiderPileId::Tableaux(index))
+                .place_cards(cards.iter().map(|face| face.face_up()));
+        }
+        table
+            .pile_mut(SpiderPileId::Stock)
+            .place_cards(iter::repeat_n(
+                Rank::Ace.of(Suit::Clubs).face_down(),
+                TABLEAUX_WIDTH,
+            ));
+        table
+    }
+
+    fn descending(suit: Suit, from: Rank, to: Rank) -> Vec<model::card::CardFace> {
+        let mut cards = Rank::values()
+            .filter(|&rank| rank >= to && rank <= from)
+            .map(|rank| rank.of(suit))
+            .collect_vec();
+        cards.reverse();
+        cards
+    }
+
+    #[test]
+    fn movable_cards_should_be_single_suit_run() {
+        let mut cards = descending(Suit::Hearts, Rank::Nine, Rank::Seven);
+        cards.extend(descending(Suit::Spades, Rank::Six, Rank::Four));
+        let table = table_with_tableaux(&cards, &[]);
+
+        assert_eq!(
+            table.movable_cards(SpiderPileId::Tableaux(0)),
+            table.pile(SpiderPileId::Tableaux(0)).top_cards(3)
+        );
+    }
+
+    #[test]
+    fn move_should_clear_and_restore_completed_run() {
+        let mut table = table_with_tableaux(
+            &descending(Suit::Spades, Rank::King, Rank::Four),
+            &descending(Suit::Spades, Rank::Three, Rank::Ace),
+        );
+        let before = table.clone();
+        let action =
+            SpiderTableAction::Move(SpiderPileId::Tableaux(1), SpiderPileId::Tableaux(0), 3);
+        let inverse = action.inverse(&table);
+
+        table.apply(action).unwrap();
+        assert_eq!(table.completed_runs(), 1);
+        assert!(table.pile(SpiderPileId::Tableaux(0)).is_empty());
+
+        table.apply(inverse).unwrap();
+        assert_eq!(table, before);
+    }
+
+    #[test]
+    fn deal_row_should_clear_and_restore_completed_run() {
+        let mut table = table_with_tableaux(&[], &descending(Suit::Clubs, Rank::King, Rank::Two));
+        let before = table.clone();
+        let inverse = SpiderTableAction::DealRow.inverse(&table);
+
+        table.apply(SpiderTableAction::DealRow).unwrap();
+        assert_eq!(table.completed_runs(), 1);
+        assert!(table.pile(SpiderPileId::Stock).is_empty());
+
+        table.apply(inverse).unwrap();
+        assert_eq!(table, before);
+    }
+}
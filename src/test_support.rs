@@ -0,0 +1,60 @@
+//! Helpers shared by the tests of several modules, e.g., for checking the rules of each variant
+//! against a table set up by hand.
+
+use enum_like::EnumValues as _;
+use itertools::Itertools as _;
+
+use crate::klondike::table;
+use crate::model;
+use crate::model::action::Actionable as _;
+use crate::model::table::Table as _;
+
+/// Check an action against the rules of a game that has already started, for tests that set up a
+/// table by hand.
+pub fn validate_started<A, R, S, T>(
+    rules: &R,
+    settings: &S,
+    table: &T,
+    action: &A,
+) -> Result<(), R::Error>
+where
+    R: model::rules::Rules<A>,
+    for<'a> R::Context<'a>: From<model::game::GameRulesContext<'a, S, T>>,
+{
+    let context = R::Context::from(model::game::GameRulesContext {
+        settings,
+        started: true,
+        table,
+    });
+    rules.validate(action, &context)
+}
+
+/// Build a table whose tableaux hold exactly the given cards, bottom to top, all face up.
+/// Any cards not mentioned are left face down in the stock.
+pub fn table_with_tableaux(tableaux: &[&[model::card::CardFace]]) -> table::KlondikeTable {
+    let dealt = tableaux
+        .iter()
+        .flat_map(|pile| pile.iter())
+        .copied()
+        .collect_vec();
+    let rest = model::card::CardFace::values().filter(|face| !dealt.contains(face));
+    let cards = velcro::vec![..rest, ..dealt.iter().rev().copied()]
+        .into_iter()
+        .map(model::card::CardFace::face_down);
+
+    let mut table = table::KlondikeTable::new_with_cards(cards);
+
+    for (index, pile) in tableaux.iter().enumerate() {
+        let pile_id = table::KlondikePileId::Tableaux(index);
+        for _ in pile.iter() {
+            table
+                .apply_all(vec![
+                    table::KlondikeTableAction::Deal(pile_id),
+                    table::KlondikeTableAction::Reveal(pile_id),
+                ])
+                .unwrap();
+        }
+    }
+
+    table
+}
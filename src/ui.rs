@@ -0,0 +1,47 @@
+//! The interactive terminal front end.
+
+use std::error::Error;
+use std::{io, thread, time};
+
+use klondike2_lib::klondike;
+use termion::input::TermRead as _;
+use termion::raw::IntoRawMode as _;
+use termion::screen::AlternateScreen;
+use tui::backend::TermionBackend;
+use tui::Terminal;
+
+mod app;
+mod view;
+
+const ANIMATION_DELAY: time::Duration = time::Duration::from_millis(60);
+
+pub fn run(settings: klondike::settings::KlondikeSettings) -> Result<(), Box<dyn Error>> {
+    let mut app = app::App::new(settings)?;
+
+    let stdout = io::stdout().into_raw_mode()?;
+    let stdout = AlternateScreen::from(stdout);
+    let mut terminal = Terminal::new(TermionBackend::new(stdout))?;
+    terminal.hide_cursor()?;
+
+    let mut keys = io::stdin().keys();
+
+    while !app.should_quit() {
+        terminal.draw(|frame| view::draw(frame, &app))?;
+
+        if app.is_animating() {
+            thread::sleep(ANIMATION_DELAY);
+            app.tick();
+            continue;
+        }
+
+        match keys.next() {
+            Some(key) => app.handle_key(key?)?,
+            None => break,
+        }
+    }
+
+    terminal.show_cursor()?;
+    app.finish()?;
+
+    Ok(())
+}
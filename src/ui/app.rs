@@ -0,0 +1,436 @@
+use std::error::Error;
+use std::path::PathBuf;
+
+use itertools::Itertools as _;
+use klondike2_lib::model::action::Actionable as _;
+use klondike2_lib::model::game::{FollowUpAction, GameAction, TableAction};
+use klondike2_lib::model::table::Table as _;
+use klondike2_lib::{klondike, model};
+use termion::event::Key;
+
+use crate::paths;
+
+pub type Game = klondike::game::KlondikeGame<model::deck::NumberedShuffle>;
+
+/// The pile the player is pointing at, and how many cards from the top of it they're pointing at.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Cursor {
+    pub pile_id: klondike::table::KlondikePileId,
+    pub depth: usize,
+}
+
+/// Cards the player has picked up and is about to move.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Selection {
+    pub pile_id: klondike::table::KlondikePileId,
+    pub count: usize,
+}
+
+#[derive(Debug)]
+pub struct App {
+    game: Game,
+    cursor: Cursor,
+    selection: Option<Selection>,
+    status: Option<String>,
+    auto_complete: Option<klondike::autoplay::AutoComplete>,
+    bankroll: Option<(klondike::bankroll::Bankroll, PathBuf)>,
+    statistics_path: Option<PathBuf>,
+    /// Whether the current game is already in the statistics.
+    recorded: bool,
+    quit: bool,
+}
+
+impl App {
+    pub fn new(settings: klondike::settings::KlondikeSettings) -> Result<Self, Box<dyn Error>> {
+        let bankroll = match paths::bankroll() {
+            Some(path) if settings.scoring == klondike::scoring::KlondikeScoring::Vegas => {
+                Some((klondike::bankroll::Bankroll::load(&path)?, path))
+            }
+            _ => None,
+        };
+
+        let game = Game::new(
+            klondike::dealer::KlondikeDealer,
+            klondike::rules::KlondikeRules,
+            settings,
+            model::deck::NumberedShuffle::new_random(),
+        );
+
+        let mut app = Self {
+            game,
+            cursor: Cursor {
+                pile_id: klondike::table::KlondikePileId::Stock,
+                depth: 1,
+            },
+            selection: None,
+            status: None,
+            auto_complete: None,
+            bankroll,
+            statistics_path: paths::statistics(),
+            recorded: false,
+            quit: false,
+        };
+        app.deal()?;
+
+        Ok(app)
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn cursor(&self) -> Cursor {
+        self.cursor
+    }
+
+    pub fn selection(&self) -> Option<Selection> {
+        self.selection
+    }
+
+    pub fn status(&self) -> Option<&str> {
+        self.status.as_deref()
+    }
+
+    pub fn should_quit(&self) -> bool {
+        self.quit
+    }
+
+    /// The Vegas bankroll, including the game in progress, if there is one.
+    pub fn bankroll_total(&self) -> Option<i64> {
+        self.bankroll
+            .as_ref()
+            .map(|(bankroll, _)| bankroll.running_total(&self.game))
+    }
+
+    /// Wrap up before exiting. In Vegas, the game in progress is settled, since the deck has
+    /// already been paid for. An unfinished game that was played at all is recorded as lost.
+    pub fn finish(&mut self) -> Result<(), Box<dyn Error>> {
+        self.settle()?;
+        self.record()
+    }
+
+    /// Whether the game is playing itself, in which case `tick` should be called instead of waiting
+    /// for a key.
+    pub fn is_animating(&self) -> bool {
+        self.auto_complete.is_some()
+    }
+
+    /// Take the next step of finishing the game. If the rules reject it, finishing stops and the
+    /// player is told why.
+    pub fn tick(&mut self) {
+        if let Some(auto_complete) = &mut self.auto_complete {
+            match auto_complete.step(&mut self.game) {
+                Ok(Some(_)) => {}
+                Ok(None) => {
+                    self.auto_complete = None;
+                    self.report_status();
+                }
+                Err(error) => {
+                    self.auto_complete = None;
+                    self.status = Some(format!(
+                        "Unable to finish the game: {}",
+                        describe_error(&error)
+                    ));
+                }
+            }
+        }
+    }
+
+    pub fn handle_key(&mut self, key: Key) -> Result<(), Box<dyn Error>> {
+        self.status = None;
+
+        // Moves don't restart the clock, so hold off on them until the player resumes.
+        if self.game.is_paused()
+            && !matches!(
+                key,
+                Key::Char('q') | Key::Ctrl('c') | Key::Char('n') | Key::Char('p')
+            )
+        {
+            self.status = Some("Paused: press p to resume".to_owned());
+            return Ok(());
+        }
+
+        match key {
+            Key::Char('q') | Key::Ctrl('c') => self.quit = true,
+            Key::Left | Key::Char('h') => self.move_cursor(-1),
+            Key::Right | Key::Char('l') => self.move_cursor(1),
+            Key::Up | Key::Char('k') => self.change_depth(1),
+            Key::Down | Key::Char('j') => self.change_depth(-1),
+            Key::Char(' ') | Key::Char('\n') => self.activate(),
+            Key::Char('f') => self.send_to_foundation(),
+            Key::Char('?') => self.hint(),
+            Key::Char('a') => self.start_auto_complete(),
+            Key::Esc => self.selection = None,
+            Key::Char('u') => self.undo(),
+            Key::Char('r') => self.redo(),
+            Key::Char('n') => self.new_game()?,
+            Key::Char('p') => self.toggle_pause(),
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn pile_ids(&self) -> Vec<klondike::table::KlondikePileId> {
+        klondike::table::KlondikePileId::full_iter(self.game.settings().tableaux_width)
+            .collect_vec()
+    }
+
+    fn move_cursor(&mut self, offset: isize) {
+        let pile_ids = self.pile_ids();
+        let current_index = pile_ids
+            .iter()
+            .position(|&pile_id| pile_id == self.cursor.pile_id)
+            .unwrap_or_default();
+        let next_index = (current_index as isize + offset).rem_euclid(pile_ids.len() as isize);
+
+        self.cursor = Cursor {
+            pile_id: pile_ids[next_index as usize],
+            depth: 1,
+        };
+    }
+
+    fn change_depth(&mut self, offset: isize) {
+        let max_depth = self
+            .game
+            .table()
+            .movable_cards(self.cursor.pile_id)
+            .len()
+            .max(1);
+
+        let depth = (self.cursor.depth as isize + offset).clamp(1, max_depth as isize);
+        self.cursor.depth = depth as usize;
+    }
+
+    fn activate(&mut self) {
+        if let Some(selection) = self.selection.take() {
+            if selection.pile_id != self.cursor.pile_id {
+                self.move_cards(selection.pile_id, self.cursor.pile_id, selection.count);
+            }
+            return;
+        }
+
+        let pile = self.game.table().pile(self.cursor.pile_id);
+
+        match self.cursor.pile_id {
+            klondike::table::KlondikePileId::Stock => {
+                self.apply(klondike::table::KlondikeTableAction::Draw(
+                    self.game.settings().draw_count,
+                ));
+            }
+            _ if pile.is_empty() => {
+                self.status = Some(format!("The {} is empty", self.cursor.pile_id));
+            }
+            _ if pile.top_card().is_some_and(model::card::Card::is_face_down) => {
+                self.apply(klondike::table::KlondikeTableAction::Reveal(
+                    self.cursor.pile_id,
+                ));
+            }
+            _ => {
+                self.selection = Some(Selection {
+                    pile_id: self.cursor.pile_id,
+                    count: self.cursor.depth,
+                });
+            }
+        }
+    }
+
+    fn send_to_foundation(&mut self) {
+        self.selection = None;
+
+        if let Some(card) = self.game.table().pile(self.cursor.pile_id).top_card() {
+            let foundation_id = klondike::table::KlondikePileId::Foundation(card.suit());
+            self.move_cards(self.cursor.pile_id, foundation_id, 1);
+        }
+    }
+
+    fn move_cards(
+        &mut self,
+        source_pile_id: klondike::table::KlondikePileId,
+        target_pile_id: klondike::table::KlondikePileId,
+        count: usize,
+    ) {
+        let moved = self.apply(klondike::table::KlondikeTableAction::Move(
+            source_pile_id,
+            target_pile_id,
+            count,
+        ));
+
+        // Save the player a keystroke by turning over any card the move uncovered.
+        let uncovered_face_down = self
+            .game
+            .table()
+            .pile(source_pile_id)
+            .top_card()
+            .is_some_and(model::card::Card::is_face_down);
+
+        if moved
+            && uncovered_face_down
+            && matches!(source_pile_id, klondike::table::KlondikePileId::Tableaux(_))
+        {
+            self.apply_follow_up(klondike::table::KlondikeTableAction::Reveal(source_pile_id));
+        }
+
+        self.cursor.depth = 1;
+    }
+
+    /// Apply a player action, reporting any rule violation in the status line. Returns whether the
+    /// action was applied.
+    fn apply(&mut self, action: klondike::table::KlondikeTableAction) -> bool {
+        match self.game.apply(TableAction(action)) {
+            Ok(()) => {
+                self.apply_safe_moves();
+                true
+            }
+            Err(error) => {
+                self.status = Some(describe_error(&error));
+                false
+            }
+        }
+    }
+
+    /// Apply an action on the player's behalf, to be undone along with the action before it.
+    fn apply_follow_up(&mut self, action: klondike::table::KlondikeTableAction) {
+        match self.game.apply(FollowUpAction(action)) {
+            Ok(()) => self.apply_safe_moves(),
+            Err(error) => self.status = Some(describe_error(&error)),
+        }
+    }
+
+    /// Play whatever cards can safely go to the foundations, if the settings ask for it. The
+    /// action that came before stands even if this fails.
+    fn apply_safe_moves(&mut self) {
+        match klondike::autoplay::apply_safe_moves(&mut self.game) {
+            Ok(_) => self.report_status(),
+            Err(error) => {
+                self.status = Some(format!(
+                    "Unable to move cards to the foundations: {}",
+                    describe_error(&error)
+                ))
+            }
+        }
+    }
+
+    fn report_status(&mut self) {
+        match self.game.status() {
+            model::game::GameStatus::Won => {
+                self.status = Some(match self.record() {
+                    Ok(()) => "You won!".to_owned(),
+                    Err(error) => format!("You won! ({})", error),
+                });
+            }
+            model::game::GameStatus::Stuck => self.status = Some("No moves left".to_owned()),
+            model::game::GameStatus::AutoCompletable => {
+                self.status = Some("Press a to finish the game".to_owned())
+            }
+            model::game::GameStatus::InProgress => {}
+        }
+    }
+
+    fn start_auto_complete(&mut self) {
+        self.selection = None;
+        self.auto_complete = klondike::autoplay::AutoComplete::new(&self.game);
+
+        if self.auto_complete.is_none() {
+            self.status = Some("The game can't finish itself yet".to_owned());
+        }
+    }
+
+    fn hint(&mut self) {
+        self.status = Some(match klondike::hint::best_hint(&self.game) {
+            Some(hint) => format!("Hint: {}", hint.action),
+            None => "No moves left".to_owned(),
+        });
+    }
+
+    fn undo(&mut self) {
+        self.selection = None;
+
+        match self.game.undo() {
+            // Don't let the player undo their way back into the deal.
+            Ok(()) if !self.game.is_started() => {
+                let _ = self.game.redo();
+                self.status = Some("Nothing to undo".to_owned());
+            }
+            Ok(()) => {}
+            Err(error) => self.status = Some(error.to_string()),
+        }
+    }
+
+    fn redo(&mut self) {
+        self.selection = None;
+
+        if let Err(error) = self.game.redo() {
+            self.status = Some(error.to_string());
+        }
+    }
+
+    fn toggle_pause(&mut self) {
+        // There's nothing left to time once the game is won.
+        if !self.game.is_started() || self.game.table().is_won() {
+            return;
+        }
+
+        if self.game.is_paused() {
+            self.game.resume();
+        } else {
+            self.game.pause();
+            self.status = Some("Paused".to_owned());
+        }
+    }
+
+    fn new_game(&mut self) -> Result<(), Box<dyn Error>> {
+        self.selection = None;
+        self.settle()?;
+        self.record()?;
+        self.game.apply(GameAction::Clear)?;
+        self.deal()
+    }
+
+    fn settle(&mut self) -> Result<(), Box<dyn Error>> {
+        if let Some((bankroll, path)) = &mut self.bankroll {
+            bankroll.settle(self.game.total_score());
+            bankroll.save(path)?;
+        }
+
+        Ok(())
+    }
+
+    fn record(&mut self) -> Result<(), Box<dyn Error>> {
+        if self.recorded || self.game.moves() == 0 {
+            return Ok(());
+        }
+
+        if let Some(path) = &self.statistics_path {
+            let record = klondike::statistics::GameRecord::from_game(&self.game);
+            klondike::statistics::Statistics::append(path, &record)?;
+        }
+        self.recorded = true;
+
+        Ok(())
+    }
+
+    fn deal(&mut self) -> Result<(), Box<dyn Error>> {
+        self.recorded = false;
+
+        self.game.deal_and_start()?;
+
+        self.status = self
+            .game
+            .deal_number()
+            .map(|deal_number| format!("Dealt game {}", deal_number));
+
+        Ok(())
+    }
+}
+
+fn describe_error<RE, AE, A>(error: &model::rules::RulesGuardError<RE, AE, A>) -> String
+where
+    RE: Error + 'static,
+    AE: Error + 'static,
+{
+    match error {
+        model::rules::RulesGuardError::RuleError { source, .. } => source.to_string(),
+        model::rules::RulesGuardError::ActionError { source, .. } => source.to_string(),
+    }
+}
@@ -0,0 +1,187 @@
+use klondike2_lib::klondike::table::KlondikePileId;
+use klondike2_lib::model::table::Table as _;
+use klondike2_lib::{klondike, model};
+use tui::backend::Backend;
+use tui::layout::{Constraint, Direction, Layout};
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Paragraph};
+use tui::Frame;
+
+use crate::ui::app;
+
+const SLOT_WIDTH: usize = 4;
+
+const HELP: &str =
+    "←/→ pile  ↑/↓ depth  space pick up/drop  f foundation  ? hint  a finish  u undo  \
+                    r redo  p pause  n new  q quit";
+
+pub fn draw<B>(frame: &mut Frame<B>, app: &app::App)
+where
+    B: Backend,
+{
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .split(frame.size());
+
+    let title = match app.game().deal_number() {
+        Some(deal_number) => format!(" Klondike {} ", deal_number),
+        None => " Klondike ".to_owned(),
+    };
+    let board =
+        Paragraph::new(board_lines(app)).block(Block::default().borders(Borders::ALL).title(title));
+    frame.render_widget(board, chunks[0]);
+
+    let status = Paragraph::new(app.status().unwrap_or_default())
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    frame.render_widget(status, chunks[1]);
+
+    let help = Paragraph::new(HELP).style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(help, chunks[2]);
+}
+
+fn board_lines(app: &app::App) -> Vec<Spans<'static>> {
+    let table = app.game().table();
+    let tableaux_width = app.game().settings().tableaux_width;
+
+    let mut top_row = Vec::new();
+
+    let stock = table.pile(KlondikePileId::Stock);
+    top_row.push(slot(
+        app,
+        KlondikePileId::Stock,
+        stock.top_card(),
+        0,
+        "[  ]",
+    ));
+
+    // Show as many waste cards as one draw turns over.
+    let fan_width = app.game().settings().draw_count;
+    let waste = table.pile(KlondikePileId::Waste);
+    let waste_fan = waste.top_cards(fan_width);
+    for (index, card) in waste_fan.iter().enumerate() {
+        top_row.push(slot(
+            app,
+            KlondikePileId::Waste,
+            Some(card),
+            waste_fan.len() - index - 1,
+            "",
+        ));
+    }
+    if waste_fan.is_empty() {
+        top_row.push(slot(app, KlondikePileId::Waste, None, 0, "[  ]"));
+    }
+    top_row.push(Span::raw(
+        " ".repeat(SLOT_WIDTH * (fan_width + 1 - waste_fan.len().max(1))),
+    ));
+
+    for suit in <model::card::Suit as enum_like::EnumValues>::values() {
+        let pile_id = KlondikePileId::Foundation(suit);
+        let placeholder = format!("[{} ]", suit.symbol());
+        top_row.push(slot(
+            app,
+            pile_id,
+            table.pile(pile_id).top_card(),
+            0,
+            &placeholder,
+        ));
+    }
+
+    let mut stock_info = format!(" {} in stock", stock.len());
+    if let Some(max_redeals) = app.game().settings().redeal_limit() {
+        let redeals_left = max_redeals.saturating_sub(table.redeals());
+        stock_info.push_str(&format!(", {} redeals left", redeals_left));
+    }
+    if app.game().settings().scoring != klondike::scoring::KlondikeScoring::None {
+        stock_info.push_str(&format!("    Score: {}", app.game().total_score()));
+    }
+    if let Some(bankroll_total) = app.bankroll_total() {
+        stock_info.push_str(&format!("    Bankroll: {}", bankroll_total));
+    }
+    let elapsed = app.game().elapsed().as_secs();
+    stock_info.push_str(&format!("    Time: {}:{:02}", elapsed / 60, elapsed % 60));
+
+    let mut lines = vec![
+        Spans::from(top_row),
+        Spans::from(Span::raw(stock_info)),
+        Spans::default(),
+    ];
+
+    let tableaux = (0..tableaux_width)
+        .map(|index| table.pile(KlondikePileId::Tableaux(index)))
+        .collect::<Vec<_>>();
+    let height = tableaux
+        .iter()
+        .map(|pile| pile.len())
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    for row in 0..height {
+        let spans = tableaux
+            .iter()
+            .enumerate()
+            .map(|(index, pile)| {
+                let pile_id = KlondikePileId::Tableaux(index);
+                match pile.iter().nth(row) {
+                    Some(card) => slot(app, pile_id, Some(card), pile.len() - row - 1, ""),
+                    None if row == 0 => slot(app, pile_id, None, 0, "[  ]"),
+                    None => Span::raw(" ".repeat(SLOT_WIDTH)),
+                }
+            })
+            .collect::<Vec<_>>();
+        lines.push(Spans::from(spans));
+    }
+
+    lines
+}
+
+/// Render a single card (or an empty pile's placeholder). The `depth` is how far the card is from
+/// the top of its pile, which decides whether the cursor or selection covers it.
+fn slot(
+    app: &app::App,
+    pile_id: KlondikePileId,
+    card: Option<&model::card::Card>,
+    depth: usize,
+    placeholder: &str,
+) -> Span<'static> {
+    let (text, mut style) = match card {
+        Some(card) if card.is_face_up() => {
+            let fg = match card.color() {
+                model::card::Color::Red => Color::Red,
+                model::card::Color::Black => Color::Black,
+            };
+            (
+                card_glyph(card.face),
+                Style::default().fg(fg).bg(Color::White),
+            )
+        }
+        Some(_) => (
+            "░░░".to_owned(),
+            Style::default().fg(Color::Blue).bg(Color::Black),
+        ),
+        None => (placeholder.to_owned(), Style::default().fg(Color::DarkGray)),
+    };
+
+    let cursor = app.cursor();
+    if cursor.pile_id == pile_id && depth < cursor.depth {
+        style = style.add_modifier(Modifier::REVERSED);
+    }
+
+    if let Some(selection) = app.selection() {
+        if selection.pile_id == pile_id && depth < selection.count {
+            style = style.bg(Color::Yellow);
+        }
+    }
+
+    Span::styled(format!("{:<width$}", text, width = SLOT_WIDTH), style)
+}
+
+fn card_glyph(face: model::card::CardFace) -> String {
+    format!("{:>3}", face.notated(model::card::Notation::Symbols))
+}
@@ -0,0 +1,7 @@
+//! Yukon, Klondike's closest cousin. It's played on a Klondike table without a stock: every card
+//! is dealt to the tableaux, and any face-up card can be moved along with everything on top of it.
+
+pub mod dealer;
+pub mod game;
+pub mod rules;
+pub mod settings;
@@ -0,0 +1,169 @@
+use crate::klondike::dealer::position::{Dealing, Revealing};
+use crate::klondike::table;
+use crate::model;
+use crate::yukon::game;
+
+pub const TABLEAUX_WIDTH: usize = 7;
+/// How many rows of face-up cards are dealt on top of the Klondike-style layout, across every
+/// tableaux but the first.
+const PILED_ROWS: usize = 4;
+
+#[derive(Debug, Clone)]
+pub struct YukonDealer;
+
+impl model::dealer::Dealer for YukonDealer {
+    type Action = table::KlondikeTableAction;
+    type Context<'a> = YukonDealerContext;
+    type Iter = YukonDealerIter;
+
+    fn deal(&self, _context: Self::Context<'_>) -> Self::Iter {
+        YukonDealerIter {
+            state: DealerIterState::Deal(Default::default()),
+        }
+    }
+}
+
+/// Unlike Klondike's, Yukon's tableaux width is fixed, so the dealer doesn't need the settings.
+#[derive(Debug, Clone, Copy)]
+pub struct YukonDealerContext;
+
+impl<'a> From<game::YukonGameDealerContext<'a>> for YukonDealerContext {
+    fn from(_context: game::YukonGameDealerContext<'a>) -> Self {
+        Self
+    }
+}
+
+/// Deals a Klondike layout and reveals the top of each tableaux, then piles four more rows of
+/// face-up cards onto every tableaux but the first, revealing each card as it's dealt.
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct YukonDealerIter {
+    state: DealerIterState,
+}
+
+impl Iterator for YukonDealerIter {
+    type Item = table::KlondikeTableAction;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let action = self.state.action();
+        self.state = self.state.next();
+        action
+    }
+}
+
+/// The position of a face-up card piled on top of the Klondike layout.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Piling {
+    column: usize,
+    row: usize,
+}
+
+impl Piling {
+    fn first() -> Self {
+        Self { column: 1, row: 0 }
+    }
+
+    fn step(self) -> Option<Self> {
+        if self.column + 1 < TABLEAUX_WIDTH {
+            Some(Self {
+                column: self.column + 1,
+                ..self
+            })
+        } else if self.row + 1 < PILED_ROWS {
+            Some(Self {
+                row: self.row + 1,
+                ..Self::first()
+            })
+        } else {
+            None
+        }
+    }
+
+    fn tableaux_index(self) -> usize {
+        self.column
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+enum DealerIterState {
+    Deal(Dealing),
+    Reveal(Revealing),
+    Pile(Piling),
+    RevealPile(Piling),
+    Done,
+}
+
+impl DealerIterState {
+    fn action(&self) -> Option<table::KlondikeTableAction> {
+        match *self {
+            Self::Deal(current_position) => {
+                let pile_id = table::KlondikePileId::Tableaux(current_position.tableaux_index());
+                Some(table::KlondikeTableAction::Deal(pile_id))
+            }
+            Self::Reveal(current_position) => {
+                let pile_id = table::KlondikePileId::Tableaux(current_position.tableaux_index());
+                Some(table::KlondikeTableAction::Reveal(pile_id))
+            }
+            Self::Pile(current_position) => {
+                let pile_id = table::KlondikePileId::Tableaux(current_position.tableaux_index());
+                Some(table::KlondikeTableAction::Deal(pile_id))
+            }
+            Self::RevealPile(current_position) => {
+                let pile_id = table::KlondikePileId::Tableaux(current_position.tableaux_index());
+                Some(table::KlondikeTableAction::Reveal(pile_id))
+            }
+            Self::Done => None,
+        }
+    }
+
+    fn next(&self) -> Self {
+        match *self {
+            Self::Deal(current_position) => current_position
+                .step(TABLEAUX_WIDTH)
+                .map(Self::Deal)
+                .unwrap_or_else(|| Self::Reveal(Default::default())),
+            Self::Reveal(current_position) => current_position
+                .step(TABLEAUX_WIDTH)
+                .map(Self::Reveal)
+                .unwrap_or_else(|| Self::Pile(Piling::first())),
+            Self::Pile(current_position) => Self::RevealPile(current_position),
+            Self::RevealPile(current_position) => current_position
+                .step()
+                .map(Self::Pile)
+                .unwrap_or(Self::Done),
+            Self::Done => Self::Done,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+    use crate::model::action::Actionable as _;
+    use crate::model::dealer::Dealer as _;
+    use crate::model::table::Table as _;
+
+    #[test_case(Piling { column: 1, row: 0 } => Some(Piling { column: 2, row: 0 }))]
+    #[test_case(Piling { column: 6, row: 0 } => Some(Piling { column: 1, row: 1 }))]
+    #[test_case(Piling { column: 6, row: 3 } => None)]
+    fn piling_step(position: Piling) -> Option<Piling> {
+        position.step()
+    }
+
+    #[test]
+    fn dealer_should_deal_yukon_layout() {
+        let mut table = table::KlondikeTable::new_with_cards(model::deck::Deck::new());
+        table
+            .apply_all(YukonDealer.deal(YukonDealerContext))
+            .unwrap();
+
+        assert!(table.pile(table::KlondikePileId::Stock).is_empty());
+        for index in 0..TABLEAUX_WIDTH {
+            let pile = table.pile(table::KlondikePileId::Tableaux(index));
+            let face_up = if index == 0 { 1 } else { 5 };
+            assert_eq!(pile.len(), index + face_up);
+            assert_eq!(pile.top_face_up_cards().len(), face_up);
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use std::convert;
+
+use crate::klondike::table;
+use crate::model;
+use crate::yukon::{dealer, rules, settings};
+
+pub type YukonGame<SH> = model::game::Game<
+    dealer::YukonDealer,
+    rules::YukonRules,
+    settings::YukonSettings,
+    SH,
+    table::KlondikeTable,
+>;
+
+pub type YukonGameError = model::rules::RulesGuardError<
+    rules::YukonRulesError,
+    convert::Infallible,
+    table::KlondikeTableAction,
+>;
+pub type YukonGameHistoryError =
+    model::game::GameHistoryError<convert::Infallible, convert::Infallible>;
+
+pub type YukonGameDealerContext<'a> = model::game::GameDealerContext<'a, settings::YukonSettings>;
+pub type YukonGameRulesContext<'a> =
+    model::game::GameRulesContext<'a, settings::YukonSettings, table::KlondikeTable>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::action::Actionable as _;
+    use crate::model::deck;
+    use crate::model::game::{GameStatus, TableAction};
+
+    #[test]
+    fn game_should_play_and_undo() {
+        let mut game = YukonGame::new(
+            dealer::YukonDealer,
+            rules::YukonRules,
+            Default::default(),
+            deck::SeededShuffle::new(11982),
+        );
+        game.deal_and_start().unwrap();
+        assert_eq!(game.status(), GameStatus::InProgress);
+
+        let before = game.table().clone();
+        let context = rules::YukonRulesContext::new(game.settings(), true, game.table());
+        let action = game.rules().legal_actions(&context)[0];
+        game.apply(TableAction(action)).unwrap();
+        assert_ne!(game.table(), &before);
+
+        game.undo().unwrap();
+        assert_eq!(game.table(), &before);
+    }
+}
@@ -0,0 +1,379 @@
+use enum_like::EnumValues as _;
+use snafu::Snafu;
+
+use crate::klondike::rules::{FoundationMismatchType, TableauxMismatchType};
+use crate::klondike::table;
+use crate::model;
+use crate::model::rules::Rules as _;
+use crate::model::table::Table as _;
+use crate::yukon::{dealer, game, settings};
+
+#[derive(Debug, Clone, Default)]
+pub struct YukonRules;
+
+#[derive(Debug, Clone, Copy)]
+pub struct YukonRulesContext<'a> {
+    settings: &'a settings::YukonSettings,
+    started: bool,
+    table: &'a table::KlondikeTable,
+}
+
+impl<'a> YukonRulesContext<'a> {
+    pub fn new(
+        settings: &'a settings::YukonSettings,
+        started: bool,
+        table: &'a table::KlondikeTable,
+    ) -> Self {
+        Self {
+            settings,
+            started,
+            table,
+        }
+    }
+}
+
+impl<'a> From<game::YukonGameRulesContext<'a>> for YukonRulesContext<'a> {
+    fn from(context: game::YukonGameRulesContext<'a>) -> Self {
+        Self {
+            settings: context.settings,
+            started: context.started,
+            table: context.table,
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum YukonRulesError {
+    #[snafu(display("The game has already started"))]
+    GameAlreadyStarted,
+    #[snafu(display("The game has not started yet"))]
+    GameNotStarted,
+    #[snafu(display("There is no tableaux {}", index + 1))]
+    PileOutOfBounds { index: usize },
+    #[snafu(display("Cannot deal to the {}", pile_id))]
+    IllegalDealTarget { pile_id: table::KlondikePileId },
+    #[snafu(display("There is no stock to draw from"))]
+    IllegalDraw,
+    #[snafu(display("Cannot move zero cards"))]
+    EmptyMove,
+    #[snafu(display("The {} does not have {} cards", pile_id, count))]
+    InsufficientCards {
+        pile_id: table::KlondikePileId,
+        count: usize,
+    },
+    #[snafu(display("Cannot move a card that is {}", facing))]
+    IllegalMoveSourceFacing { facing: model::card::Facing },
+    #[snafu(display("Cannot move cards from the {}", pile_id))]
+    IllegalMoveSource { pile_id: table::KlondikePileId },
+    #[snafu(display("Cannot move onto a card that is {}", facing))]
+    IllegalMoveTargetFacing { facing: model::card::Facing },
+    #[snafu(display("Cannot place the {} on the tableaux: {}", card, mismatch))]
+    TableauxMismatch {
+        card: model::card::CardFace,
+        mismatch: TableauxMismatchType,
+    },
+    #[snafu(display("The {} may only accept a single card", pile_id))]
+    MayOnlyAcceptSingleCard { pile_id: table::KlondikePileId },
+    #[snafu(display("Cannot place the {} on the foundation: {}", card, mismatch))]
+    FoundationMismatch {
+        card: model::card::CardFace,
+        mismatch: FoundationMismatchType,
+    },
+    #[snafu(display("Cannot move cards to the {}", pile_id))]
+    IllegalMoveTarget { pile_id: table::KlondikePileId },
+    #[snafu(display("Cannot reveal a card in the {}", pile_id))]
+    IllegalRevealTarget { pile_id: table::KlondikePileId },
+    #[snafu(display("The {} has no card to reveal", pile_id))]
+    EmptyReveal { pile_id: table::KlondikePileId },
+    #[snafu(display("Cannot reveal a card that is {}", facing))]
+    IllegalRevealTargetFacing { facing: model::card::Facing },
+}
+
+impl model::rules::Rules<table::KlondikeTableAction> for YukonRules {
+    type Context<'a> = YukonRulesContext<'a>;
+    type Error = YukonRulesError;
+
+    fn validate(
+        &self,
+        action: &table::KlondikeTableAction,
+        context: &Self::Context<'_>,
+    ) -> Result<(), Self::Error> {
+        match *action {
+            table::KlondikeTableAction::Deal(target_pile_id) => {
+                self.validate_deal(target_pile_id, context)
+            }
+            table::KlondikeTableAction::Draw(_) => IllegalDraw.fail(),
+            table::KlondikeTableAction::Move(source_pile_id, target_pile_id, count) => {
+                self.validate_move(source_pile_id, target_pile_id, count, context)
+            }
+            table::KlondikeTableAction::Reveal(target_pile_id) => {
+                self.validate_reveal(target_pile_id, context)
+            }
+        }
+    }
+
+    fn is_stuck(&self, context: &Self::Context<'_>) -> bool {
+        context.started && self.legal_actions(context).is_empty()
+    }
+}
+
+impl YukonRules {
+    /// List every move and reveal a player could legally make in this context. Any run of face-up
+    /// cards can move, so there's a move for each count up to the whole face-up part of the pile.
+    pub fn legal_actions(
+        &self,
+        context: &YukonRulesContext<'_>,
+    ) -> Vec<table::KlondikeTableAction> {
+        let tableaux_ids = (0..dealer::TABLEAUX_WIDTH).map(table::KlondikePileId::Tableaux);
+        let foundation_ids = model::card::Suit::values().map(table::KlondikePileId::Foundation);
+
+        let reveals = tableaux_ids.clone().map(table::KlondikeTableAction::Reveal);
+        let moves = tableaux_ids.clone().flat_map(|source_pile_id| {
+            let max_count = context.table.movable_cards(source_pile_id).len();
+            let target_ids = velcro::iter![..foundation_ids.clone(), ..tableaux_ids.clone()];
+
+            target_ids
+                .filter(move |&target_pile_id| target_pile_id != source_pile_id)
+                .flat_map(move |target_pile_id| {
+                    (1..=max_count).map(move |count| {
+                        table::KlondikeTableAction::Move(source_pile_id, target_pile_id, count)
+                    })
+                })
+        });
+
+        velcro::iter![..reveals, ..moves]
+            .filter(|action| self.validate(action, context).is_ok())
+            .collect()
+    }
+
+    fn validate_deal(
+        &self,
+        target_pile_id: table::KlondikePileId,
+        context: &YukonRulesContext<'_>,
+    ) -> Result<(), YukonRulesError> {
+        snafu::ensure!(!context.started, GameAlreadyStarted);
+
+        // Unlike in Klondike, cards are dealt onto face-up cards as well as face-down ones.
+        if let table::KlondikePileId::Tableaux(index) = target_pile_id {
+            self.validate_tableaux_index(index)
+        } else {
+            IllegalDealTarget {
+                pile_id: target_pile_id,
+            }
+            .fail()
+        }
+    }
+
+    fn validate_move(
+        &self,
+        source_pile_id: table::KlondikePileId,
+        target_pile_id: table::KlondikePileId,
+        count: usize,
+        context: &YukonRulesContext<'_>,
+    ) -> Result<(), YukonRulesError> {
+        snafu::ensure!(context.started, GameNotStarted);
+        snafu::ensure!(count > 0, EmptyMove);
+
+        match source_pile_id {
+            table::KlondikePileId::Tableaux(index) => self.validate_tableaux_index(index)?,
+            _ => {
+                return IllegalMoveSource {
+                    pile_id: source_pile_id,
+                }
+                .fail();
+            }
+        }
+
+        let source_top_cards = context.table.pile(source_pile_id).top_cards(count);
+        snafu::ensure!(
+            count == source_top_cards.len(),
+            InsufficientCards {
+                pile_id: source_pile_id,
+                count
+            }
+        );
+
+        // Any face-up card can be moved along with whatever is on top of it, in sequence or not.
+        // The face-up cards always sit on top of any face-down cards, so it's sufficient to check
+        // the facing of the bottom card being moved.
+        let source_card = &source_top_cards[0];
+        snafu::ensure!(
+            source_card.is_face_up(),
+            IllegalMoveSourceFacing {
+                facing: source_card.facing
+            }
+        );
+
+        match target_pile_id {
+            table::KlondikePileId::Tableaux(index) => {
+                self.validate_tableaux_index(index)?;
+
+                if let Some(target_top_card) = context.table.pile(target_pile_id).top_card() {
+                    snafu::ensure!(
+                        target_top_card.is_face_up(),
+                        IllegalMoveTargetFacing {
+                            facing: target_top_card.facing
+                        }
+                    );
+
+                    let suit_matches = if context.settings.russian {
+                        target_top_card.suit() == source_card.suit()
+                    } else {
+                        target_top_card.color() != source_card.color()
+                    };
+                    snafu::ensure!(
+                        target_top_card.rank().follows(source_card.rank()) && suit_matches,
+                        TableauxMismatch {
+                            card: source_card.face,
+                            mismatch: TableauxMismatchType::Follow(target_top_card.face)
+                        }
+                    );
+                } else {
+                    snafu::ensure!(
+                        source_card.is_king(),
+                        TableauxMismatch {
+                            card: source_card.face,
+                            mismatch: TableauxMismatchType::Start
+                        }
+                    );
+                }
+            }
+            table::KlondikePileId::Foundation(suit) => {
+                snafu::ensure!(
+                    count == 1,
+                    MayOnlyAcceptSingleCard {
+                        pile_id: target_pile_id
+                    }
+                );
+
+                if let Some(target_top_card) = context.table.pile(target_pile_id).top_card() {
+                    snafu::ensure!(
+                        source_card.suit() == suit
+                            && source_card.rank().follows(target_top_card.rank()),
+                        FoundationMismatch {
+                            card: source_card.face,
+                            mismatch: FoundationMismatchType::Follow(target_top_card.face)
+                        }
+                    );
+                } else {
+                    snafu::ensure!(
+                        source_card.suit() == suit && source_card.is_ace(),
+                        FoundationMismatch {
+                            card: source_card.face,
+                            mismatch: FoundationMismatchType::Start(suit)
+                        }
+                    );
+                }
+            }
+            _ => {
+                return IllegalMoveTarget {
+                    pile_id: target_pile_id,
+                }
+                .fail();
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_reveal(
+        &self,
+        target_pile_id: table::KlondikePileId,
+        context: &YukonRulesContext<'_>,
+    ) -> Result<(), YukonRulesError> {
+        if let table::KlondikePileId::Tableaux(index) = target_pile_id {
+            self.validate_tableaux_index(index)?;
+
+            let top_card = context.table.pile(target_pile_id).top_card().ok_or(
+                YukonRulesError::EmptyReveal {
+                    pile_id: target_pile_id,
+                },
+            )?;
+            snafu::ensure!(
+                top_card.is_face_down(),
+                IllegalRevealTargetFacing {
+                    facing: top_card.facing
+                }
+            );
+
+            Ok(())
+        } else {
+            IllegalRevealTarget {
+                pile_id: target_pile_id,
+            }
+            .fail()
+        }
+    }
+
+    fn validate_tableaux_index(&self, index: usize) -> Result<(), YukonRulesError> {
+        snafu::ensure!(index < dealer::TABLEAUX_WIDTH, PileOutOfBounds { index });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::model::card::{Rank, Suit};
+    use crate::test_support::{table_with_tableaux, validate_started};
+
+    #[test_case(Rank::Ten.of(Suit::Hearts), false => true; "alternating colors")]
+    #[test_case(Rank::Ten.of(Suit::Clubs), false => false; "same color")]
+    #[test_case(Rank::Ten.of(Suit::Spades), true => true; "russian in suit")]
+    #[test_case(Rank::Ten.of(Suit::Hearts), true => false; "russian out of suit")]
+    fn move_should_take_everything_on_top(onto: model::card::CardFace, russian: bool) -> bool {
+        // Only the bottom card has to fit; the cards on top of it can be in any order.
+        let table = table_with_tableaux(&[
+            &[
+                Rank::Nine.of(Suit::Spades),
+                Rank::Two.of(Suit::Hearts),
+                Rank::King.of(Suit::Clubs),
+            ],
+            &[onto],
+        ]);
+        let action = table::KlondikeTableAction::Move(
+            table::KlondikePileId::Tableaux(0),
+            table::KlondikePileId::Tableaux(1),
+            3,
+        );
+
+        validate_started(
+            &YukonRules,
+            &settings::YukonSettings { russian },
+            &table,
+            &action,
+        )
+        .is_ok()
+    }
+
+    #[test_case(Rank::King.of(Suit::Hearts) => true; "king")]
+    #[test_case(Rank::Queen.of(Suit::Hearts) => false; "queen")]
+    fn empty_tableaux_accepts_only_kings(card: model::card::CardFace) -> bool {
+        let table = table_with_tableaux(&[&[card, Rank::Two.of(Suit::Spades)]]);
+        let action = table::KlondikeTableAction::Move(
+            table::KlondikePileId::Tableaux(0),
+            table::KlondikePileId::Tableaux(1),
+            2,
+        );
+
+        validate_started(&YukonRules, &Default::default(), &table, &action).is_ok()
+    }
+
+    #[test]
+    fn draw_should_be_rejected() {
+        let table = table_with_tableaux(&[]);
+
+        assert_matches!(
+            validate_started(
+                &YukonRules,
+                &Default::default(),
+                &table,
+                &table::KlondikeTableAction::Draw(1),
+            ),
+            Err(YukonRulesError::IllegalDraw)
+        );
+    }
+}
@@ -0,0 +1,15 @@
+use crate::klondike::table;
+use crate::model;
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct YukonSettings {
+    /// Play Russian Solitaire, where cards are built down the tableaux in suit rather than in
+    /// alternating colors.
+    #[serde(default)]
+    pub russian: bool,
+}
+
+impl model::scoring::ScoringScheme<table::KlondikeTableAction, table::KlondikeTable>
+    for YukonSettings
+{
+}